@@ -3,6 +3,26 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20241126_123847_initial_schema;
+mod m20261019_090000_import_profiles;
+mod m20261019_100000_possible_duplicates;
+mod m20261019_110000_transaction_rules;
+mod m20261019_120000_category_classifiers;
+mod m20261019_130000_transaction_splits;
+mod m20261019_140000_transfer_amounts;
+mod m20261019_150000_reconciliations;
+mod m20261019_160000_transaction_search;
+mod m20261019_170000_exchange_rates;
+mod m20261019_180000_balance_snapshots;
+mod m20261019_190000_exports;
+mod m20261019_200000_account_deletion;
+mod m20261019_210000_hashed_api_keys;
+mod m20261019_220000_personal_access_tokens;
+mod m20261019_230000_two_factor_authentication;
+mod m20261020_000000_pending_email;
+mod m20261020_010000_admin_audit_log;
+mod m20261020_020000_invite_codes;
+mod m20261020_030000_transaction_external_id;
+mod m20261020_040000_totp_last_step;
 pub struct Migrator;
 
 #[async_trait::async_trait]
@@ -10,6 +30,26 @@ impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
             Box::new(m20241126_123847_initial_schema::Migration),
+            Box::new(m20261019_090000_import_profiles::Migration),
+            Box::new(m20261019_100000_possible_duplicates::Migration),
+            Box::new(m20261019_110000_transaction_rules::Migration),
+            Box::new(m20261019_120000_category_classifiers::Migration),
+            Box::new(m20261019_130000_transaction_splits::Migration),
+            Box::new(m20261019_140000_transfer_amounts::Migration),
+            Box::new(m20261019_150000_reconciliations::Migration),
+            Box::new(m20261019_160000_transaction_search::Migration),
+            Box::new(m20261019_170000_exchange_rates::Migration),
+            Box::new(m20261019_180000_balance_snapshots::Migration),
+            Box::new(m20261019_190000_exports::Migration),
+            Box::new(m20261019_200000_account_deletion::Migration),
+            Box::new(m20261019_210000_hashed_api_keys::Migration),
+            Box::new(m20261019_220000_personal_access_tokens::Migration),
+            Box::new(m20261019_230000_two_factor_authentication::Migration),
+            Box::new(m20261020_000000_pending_email::Migration),
+            Box::new(m20261020_010000_admin_audit_log::Migration),
+            Box::new(m20261020_020000_invite_codes::Migration),
+            Box::new(m20261020_030000_transaction_external_id::Migration),
+            Box::new(m20261020_040000_totp_last_step::Migration),
             // inject-above (do not remove this comment)
         ]
     }
@@ -0,0 +1,26 @@
+-- ############################################################
+-- #                                                          #
+-- #                    Import Profiles                       #
+-- #                                                          #
+-- ############################################################
+
+CREATE TYPE import_sign_convention AS ENUM ('signed', 'inverted', 'separate_columns');
+
+CREATE TABLE import_profiles
+(
+    id                  BIGINT PRIMARY KEY,
+    user_id             BIGINT REFERENCES "users" (id) ON DELETE CASCADE NOT NULL,
+    name                TEXT                                             NOT NULL,
+    column_mapping      JSONB                                            NOT NULL,
+    date_format         TEXT                                             NOT NULL,
+    decimal_separator   TEXT                                             NOT NULL,
+    thousands_separator TEXT,
+    delimiter           TEXT                                             NOT NULL,
+    has_header          BOOLEAN                                          NOT NULL DEFAULT TRUE,
+    skip_rows           INTEGER                                          NOT NULL DEFAULT 0,
+    sign_convention     import_sign_convention                           NOT NULL,
+    created_at          timestamp with time zone                         NOT NULL,
+    updated_at          timestamp with time zone                         NOT NULL
+);
+
+CREATE INDEX idx_import_profiles_user_id ON import_profiles (user_id);
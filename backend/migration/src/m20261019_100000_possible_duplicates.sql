@@ -0,0 +1,24 @@
+-- ############################################################
+-- #                                                          #
+-- #                   Possible Duplicates                    #
+-- #                                                          #
+-- ############################################################
+
+CREATE TYPE possible_duplicate_status AS ENUM ('open', 'dismissed');
+
+CREATE TABLE possible_duplicates
+(
+    id             BIGINT PRIMARY KEY,
+    transaction_id BIGINT REFERENCES transactions (id) ON DELETE CASCADE NOT NULL,
+    duplicate_id   BIGINT REFERENCES transactions (id) ON DELETE CASCADE NOT NULL,
+    score          SMALLINT                                              NOT NULL,
+    status         possible_duplicate_status                             NOT NULL DEFAULT 'open',
+    created_at     timestamp with time zone                              NOT NULL,
+    updated_at     timestamp with time zone                              NOT NULL,
+    UNIQUE (transaction_id, duplicate_id),
+    CHECK (transaction_id <> duplicate_id)
+);
+
+CREATE INDEX idx_possible_duplicates_transaction_id ON possible_duplicates (transaction_id);
+CREATE INDEX idx_possible_duplicates_duplicate_id ON possible_duplicates (duplicate_id);
+CREATE INDEX idx_possible_duplicates_status ON possible_duplicates (status);
@@ -0,0 +1,41 @@
+-- ############################################################
+-- #                                                          #
+-- #                   Transaction Rules                      #
+-- #                                                          #
+-- ############################################################
+
+CREATE TABLE transaction_rules
+(
+    id               BIGINT PRIMARY KEY,
+    user_id          BIGINT REFERENCES "users" (id) ON DELETE CASCADE NOT NULL,
+    name             TEXT                                             NOT NULL,
+    position         INTEGER                                          NOT NULL DEFAULT 0,
+    stop_processing  BOOLEAN                                          NOT NULL DEFAULT FALSE,
+    -- Conditions (all given conditions have to match)
+    name_contains    TEXT,
+    purpose_contains TEXT,
+    source_iban      TEXT,
+    destination_iban TEXT,
+    min_amount       BIGINT,
+    max_amount       BIGINT,
+    transaction_type transaction_type,
+    -- Actions
+    category_id      BIGINT                                           REFERENCES categories (id) ON DELETE SET NULL,
+    rename_to        TEXT,
+    created_at       timestamp with time zone                         NOT NULL,
+    updated_at       timestamp with time zone                         NOT NULL,
+    CHECK (min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount)
+);
+
+CREATE INDEX idx_transaction_rules_user_id ON transaction_rules (user_id, position);
+
+CREATE TABLE transaction_rule_tags
+(
+    transaction_rule_id BIGINT REFERENCES transaction_rules (id) ON DELETE CASCADE,
+    tag_id              BIGINT REFERENCES tags (id) ON DELETE CASCADE,
+    created_at          timestamp with time zone NOT NULL,
+    updated_at          timestamp with time zone NOT NULL,
+    PRIMARY KEY (transaction_rule_id, tag_id)
+);
+
+CREATE INDEX idx_transaction_rule_tags_tag_id ON transaction_rule_tags (tag_id);
@@ -0,0 +1,19 @@
+-- ############################################################
+-- #                                                          #
+-- #                  Category Classifiers                    #
+-- #                                                          #
+-- ############################################################
+
+CREATE TABLE category_classifiers
+(
+    id                      BIGINT PRIMARY KEY,
+    user_id                 BIGINT REFERENCES "users" (id) ON DELETE CASCADE UNIQUE NOT NULL,
+    model                   JSONB                                                   NOT NULL,
+    -- The amount of categorized transactions the model was trained on.
+    transaction_count       INTEGER                                                 NOT NULL DEFAULT 0,
+    -- The amount of new categorizations since the last training.
+    pending_categorizations INTEGER                                                 NOT NULL DEFAULT 0,
+    trained_at              timestamp with time zone,
+    created_at              timestamp with time zone                                NOT NULL,
+    updated_at              timestamp with time zone                                NOT NULL
+);
@@ -0,0 +1,19 @@
+-- ############################################################
+-- #                                                          #
+-- #                   Transaction Splits                     #
+-- #                                                          #
+-- ############################################################
+
+CREATE TABLE transaction_splits
+(
+    id             BIGINT PRIMARY KEY,
+    transaction_id BIGINT REFERENCES transactions (id) ON DELETE CASCADE NOT NULL,
+    amount         BIGINT                                                NOT NULL CHECK (amount > 0),
+    category_id    BIGINT                                                REFERENCES categories (id) ON DELETE SET NULL,
+    note           TEXT,
+    created_at     timestamp with time zone                              NOT NULL,
+    updated_at     timestamp with time zone                              NOT NULL
+);
+
+CREATE INDEX idx_transaction_splits_transaction_id ON transaction_splits (transaction_id);
+CREATE INDEX idx_transaction_splits_category_id ON transaction_splits (category_id);
@@ -0,0 +1,10 @@
+-- ############################################################
+-- #                                                          #
+-- #                     Transfer Amounts                     #
+-- #                                                          #
+-- ############################################################
+
+-- The amount that arrives at the destination of a transfer between bank accounts with different currencies.
+-- It is given in the currency of the destination bank account, `amount` is given in the currency of the transaction.
+ALTER TABLE transactions
+    ADD COLUMN destination_amount BIGINT CHECK (destination_amount > 0);
@@ -0,0 +1,34 @@
+-- ############################################################
+-- #                                                          #
+-- #                      Reconciliations                     #
+-- #                                                          #
+-- ############################################################
+
+CREATE TABLE reconciliations
+(
+    id                BIGINT PRIMARY KEY,
+    bank_account_id   BIGINT REFERENCES bank_accounts (id) ON DELETE CASCADE NOT NULL,
+    -- The balance of the bank account at the end of the statement.
+    statement_date    timestamp with time zone                              NOT NULL,
+    statement_balance BIGINT                                                NOT NULL,
+    created_at        timestamp with time zone                              NOT NULL,
+    updated_at        timestamp with time zone                              NOT NULL
+);
+
+CREATE INDEX idx_reconciliations_bank_account_id ON reconciliations (bank_account_id, statement_date);
+
+-- A transfer is reconciled once per involved bank account.
+CREATE TABLE reconciliation_transactions
+(
+    reconciliation_id BIGINT REFERENCES reconciliations (id) ON DELETE CASCADE,
+    transaction_id    BIGINT REFERENCES transactions (id) ON DELETE CASCADE,
+    created_at        timestamp with time zone NOT NULL,
+    updated_at        timestamp with time zone NOT NULL,
+    PRIMARY KEY (reconciliation_id, transaction_id)
+);
+
+CREATE INDEX idx_reconciliation_transactions_transaction_id ON reconciliation_transactions (transaction_id);
+
+-- Reconciled transactions are locked against edits.
+ALTER TABLE transactions
+    ADD COLUMN reconciled_at timestamp with time zone;
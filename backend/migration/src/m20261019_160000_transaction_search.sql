@@ -0,0 +1,20 @@
+-- ############################################################
+-- #                                                          #
+-- #                    Transaction Search                    #
+-- #                                                          #
+-- ############################################################
+
+-- The `simple` configuration is used, because transactions are written in many languages
+-- and mostly consist of names and references that should not be stemmed.
+ALTER TABLE transactions
+    ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(source_name, '') || ' ' || coalesce(destination_name, '')), 'B') ||
+        setweight(to_tsvector('simple', coalesce(purpose, '') || ' ' || coalesce(note, '')), 'C')
+        ) STORED;
+
+CREATE INDEX idx_transactions_search_vector ON transactions USING GIN (search_vector);
+-- Searches filter and sort by the effective date, which falls back to the creation date.
+CREATE INDEX idx_transactions_effective_date ON transactions (COALESCE(booking_date, created_at));
+CREATE INDEX idx_transactions_amount ON transactions (amount);
+CREATE INDEX idx_transactions_file_attachment_id ON transactions (file_attachment_id);
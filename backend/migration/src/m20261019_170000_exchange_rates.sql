@@ -0,0 +1,23 @@
+-- ############################################################
+-- #                                                          #
+-- #                      Exchange rates                      #
+-- #                                                          #
+-- ############################################################
+
+-- The rate is the price of one major unit of the source currency in major units of the target currency.
+CREATE TABLE exchange_rates
+(
+    id                 BIGINT PRIMARY KEY,
+    user_id            BIGINT REFERENCES users (id) ON DELETE CASCADE      NOT NULL,
+    source_currency_id BIGINT REFERENCES currencies (id) ON DELETE CASCADE NOT NULL,
+    target_currency_id BIGINT REFERENCES currencies (id) ON DELETE CASCADE NOT NULL,
+    rate               DOUBLE PRECISION                                    NOT NULL CHECK (rate > 0),
+    created_at         timestamp with time zone                            NOT NULL,
+    updated_at         timestamp with time zone                            NOT NULL,
+    UNIQUE (user_id, source_currency_id, target_currency_id),
+    CHECK (source_currency_id <> target_currency_id)
+);
+
+-- Reports are converted into this currency.
+ALTER TABLE users
+    ADD COLUMN preferred_currency_id BIGINT REFERENCES currencies (id) ON DELETE SET NULL;
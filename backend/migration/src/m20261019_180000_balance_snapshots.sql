@@ -0,0 +1,17 @@
+-- ############################################################
+-- #                                                          #
+-- #                    Balance snapshots                     #
+-- #                                                          #
+-- ############################################################
+
+-- The balance of a bank account at the end of a day (UTC), materialized by the `snapshot_balances` task.
+-- Snapshots are removed from the date of a transaction on whenever it changes the history.
+CREATE TABLE balance_snapshots
+(
+    bank_account_id BIGINT REFERENCES bank_accounts (id) ON DELETE CASCADE NOT NULL,
+    date            DATE                                                   NOT NULL,
+    balance         BIGINT                                                 NOT NULL,
+    created_at      timestamp with time zone                               NOT NULL,
+    updated_at      timestamp with time zone                               NOT NULL,
+    PRIMARY KEY (bank_account_id, date)
+);
@@ -0,0 +1,25 @@
+-- ############################################################
+-- #                                                          #
+-- #                         Exports                          #
+-- #                                                          #
+-- ############################################################
+
+CREATE TYPE export_format AS ENUM ('csv', 'json', 'ledger', 'beancount');
+
+CREATE TYPE export_status AS ENUM ('pending', 'finished', 'failed');
+
+-- The file is written into the storage by a background worker.
+CREATE TABLE exports
+(
+    id          BIGINT PRIMARY KEY,
+    user_id     BIGINT REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+    format      export_format                                 NOT NULL,
+    status      export_status                                 NOT NULL DEFAULT 'pending',
+    file_path   TEXT,
+    error       TEXT,
+    finished_at timestamp with time zone,
+    created_at  timestamp with time zone                      NOT NULL,
+    updated_at  timestamp with time zone                      NOT NULL
+);
+
+CREATE INDEX idx_exports_user_id ON exports (user_id);
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const UP: &str = include_str!("m20261019_200000_account_deletion.sql");
+
+const DOWN: &str = r#"
+ALTER TABLE budget_histories DROP CONSTRAINT budget_histories_budget_id_fkey;
+
+ALTER TABLE budgets
+    DROP CONSTRAINT budgets_criteria_id_fkey,
+    ADD CONSTRAINT budgets_criteria_id_fkey FOREIGN KEY (criteria_id) REFERENCES budget_criteria (id);
+
+ALTER TABLE pending_transactions
+    DROP CONSTRAINT pending_transactions_source_id_fkey,
+    DROP CONSTRAINT pending_transactions_destination_id_fkey,
+    ADD CONSTRAINT pending_transactions_source_id_fkey FOREIGN KEY (source_id) REFERENCES transaction_parties (id),
+    ADD CONSTRAINT pending_transactions_destination_id_fkey FOREIGN KEY (destination_id) REFERENCES transaction_parties (id);
+
+ALTER TABLE recurring_transactions
+    DROP CONSTRAINT recurring_transactions_source_id_fkey,
+    DROP CONSTRAINT recurring_transactions_destination_id_fkey,
+    ADD CONSTRAINT recurring_transactions_source_id_fkey FOREIGN KEY (source_id) REFERENCES transaction_parties (id),
+    ADD CONSTRAINT recurring_transactions_destination_id_fkey FOREIGN KEY (destination_id) REFERENCES transaction_parties (id);
+
+ALTER TABLE transaction_templates
+    DROP CONSTRAINT transaction_templates_source_id_fkey,
+    DROP CONSTRAINT transaction_templates_destination_id_fkey,
+    ADD CONSTRAINT transaction_templates_source_id_fkey FOREIGN KEY (source_id) REFERENCES transaction_parties (id),
+    ADD CONSTRAINT transaction_templates_destination_id_fkey FOREIGN KEY (destination_id) REFERENCES transaction_parties (id);
+
+ALTER TABLE transactions
+    DROP CONSTRAINT transactions_source_id_fkey,
+    DROP CONSTRAINT transactions_destination_id_fkey,
+    ADD CONSTRAINT transactions_source_id_fkey FOREIGN KEY (source_id) REFERENCES transaction_parties (id),
+    ADD CONSTRAINT transactions_destination_id_fkey FOREIGN KEY (destination_id) REFERENCES transaction_parties (id);
+
+ALTER TABLE transaction_parties
+    DROP CONSTRAINT transaction_parties_bank_account_id_fkey,
+    ADD CONSTRAINT transaction_parties_bank_account_id_fkey FOREIGN KEY (bank_account_id) REFERENCES bank_accounts (id);
+
+-- Enum values can not be dropped, so the type is recreated without the archive format.
+DELETE FROM exports WHERE format = 'archive';
+ALTER TYPE export_format RENAME TO export_format_old;
+CREATE TYPE export_format AS ENUM ('csv', 'json', 'ledger', 'beancount');
+ALTER TABLE exports ALTER COLUMN format TYPE export_format USING format::text::export_format;
+DROP TYPE export_format_old;
+
+ALTER TABLE users DROP COLUMN deletion_scheduled_at;
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(UP).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(DOWN).await?;
+
+        Ok(())
+    }
+}
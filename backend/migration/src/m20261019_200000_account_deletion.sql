@@ -0,0 +1,65 @@
+-- ############################################################
+-- #                                                          #
+-- #                     Account Deletion                     #
+-- #                                                          #
+-- ############################################################
+
+-- The account is deleted by the `delete_accounts` task once this point in time has passed.
+ALTER TABLE users
+    ADD COLUMN deletion_scheduled_at timestamp with time zone;
+
+CREATE INDEX idx_users_deletion_scheduled_at ON users (deletion_scheduled_at);
+
+-- A zip archive of everything stored about the user.
+ALTER TYPE export_format ADD VALUE 'archive';
+
+-- Bank accounts and budgets are owned through user permissions and are deleted together with their dependents.
+-- Transactions between a deleted and a remaining bank account are kept for the remaining one.
+ALTER TABLE transaction_parties
+    DROP CONSTRAINT transaction_parties_bank_account_id_fkey,
+    ADD CONSTRAINT transaction_parties_bank_account_id_fkey
+        FOREIGN KEY (bank_account_id) REFERENCES bank_accounts (id) ON DELETE CASCADE;
+
+ALTER TABLE transactions
+    DROP CONSTRAINT transactions_source_id_fkey,
+    DROP CONSTRAINT transactions_destination_id_fkey,
+    ADD CONSTRAINT transactions_source_id_fkey
+        FOREIGN KEY (source_id) REFERENCES transaction_parties (id) ON DELETE SET NULL,
+    ADD CONSTRAINT transactions_destination_id_fkey
+        FOREIGN KEY (destination_id) REFERENCES transaction_parties (id) ON DELETE SET NULL;
+
+ALTER TABLE transaction_templates
+    DROP CONSTRAINT transaction_templates_source_id_fkey,
+    DROP CONSTRAINT transaction_templates_destination_id_fkey,
+    ADD CONSTRAINT transaction_templates_source_id_fkey
+        FOREIGN KEY (source_id) REFERENCES transaction_parties (id) ON DELETE SET NULL,
+    ADD CONSTRAINT transaction_templates_destination_id_fkey
+        FOREIGN KEY (destination_id) REFERENCES transaction_parties (id) ON DELETE SET NULL;
+
+ALTER TABLE recurring_transactions
+    DROP CONSTRAINT recurring_transactions_source_id_fkey,
+    DROP CONSTRAINT recurring_transactions_destination_id_fkey,
+    ADD CONSTRAINT recurring_transactions_source_id_fkey
+        FOREIGN KEY (source_id) REFERENCES transaction_parties (id) ON DELETE SET NULL,
+    ADD CONSTRAINT recurring_transactions_destination_id_fkey
+        FOREIGN KEY (destination_id) REFERENCES transaction_parties (id) ON DELETE SET NULL;
+
+ALTER TABLE pending_transactions
+    DROP CONSTRAINT pending_transactions_source_id_fkey,
+    DROP CONSTRAINT pending_transactions_destination_id_fkey,
+    ADD CONSTRAINT pending_transactions_source_id_fkey
+        FOREIGN KEY (source_id) REFERENCES transaction_parties (id) ON DELETE SET NULL,
+    ADD CONSTRAINT pending_transactions_destination_id_fkey
+        FOREIGN KEY (destination_id) REFERENCES transaction_parties (id) ON DELETE SET NULL;
+
+ALTER TABLE budgets
+    DROP CONSTRAINT budgets_criteria_id_fkey,
+    ADD CONSTRAINT budgets_criteria_id_fkey
+        FOREIGN KEY (criteria_id) REFERENCES budget_criteria (id) ON DELETE CASCADE;
+
+DELETE FROM budget_histories
+WHERE budget_id NOT IN (SELECT id FROM budgets);
+
+ALTER TABLE budget_histories
+    ADD CONSTRAINT budget_histories_budget_id_fkey
+        FOREIGN KEY (budget_id) REFERENCES budgets (id) ON DELETE CASCADE;
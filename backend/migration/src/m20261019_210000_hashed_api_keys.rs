@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const UP: &str = include_str!("m20261019_210000_hashed_api_keys.sql");
+
+const DOWN: &str = r#"
+-- The plain api keys can not be restored from their hashes, so all sessions are revoked.
+DELETE FROM sessions;
+
+ALTER INDEX idx_sessions_api_key_hash RENAME TO idx_sessions_api_key;
+ALTER TABLE sessions RENAME CONSTRAINT sessions_api_key_hash_key TO sessions_api_key_key;
+ALTER TABLE sessions RENAME COLUMN api_key_hash TO api_key;
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(UP).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(DOWN).await?;
+
+        Ok(())
+    }
+}
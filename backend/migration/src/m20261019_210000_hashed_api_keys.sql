@@ -0,0 +1,14 @@
+-- ############################################################
+-- #                                                          #
+-- #                     Hashed API Keys                      #
+-- #                                                          #
+-- ############################################################
+
+-- Only the hex encoded SHA-256 digest of an api key is stored, the plain key is returned once at creation.
+-- The existing keys are hashed in place, so the sessions stay valid.
+ALTER TABLE sessions RENAME COLUMN api_key TO api_key_hash;
+ALTER TABLE sessions RENAME CONSTRAINT sessions_api_key_key TO sessions_api_key_hash_key;
+ALTER INDEX idx_sessions_api_key RENAME TO idx_sessions_api_key_hash;
+
+UPDATE sessions
+SET api_key_hash = encode(sha256(convert_to(api_key_hash, 'UTF8')), 'hex');
@@ -0,0 +1,23 @@
+-- ############################################################
+-- #                                                          #
+-- #                  Personal Access Tokens                  #
+-- #                                                          #
+-- ############################################################
+
+-- Long-lived tokens for scripts and integrations, restricted to their scopes.
+-- Like the api keys of sessions, only the hex encoded SHA-256 digest of a token is stored.
+CREATE TABLE personal_access_tokens
+(
+    id           BIGINT PRIMARY KEY,
+    user_id      BIGINT REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+    name         TEXT                                           NOT NULL,
+    token_hash   TEXT                                           NOT NULL UNIQUE,
+    -- Bit flags of the granted scopes.
+    scopes       INTEGER                                        NOT NULL,
+    expires_at   timestamp with time zone,
+    last_used_at timestamp with time zone,
+    created_at   timestamp with time zone                       NOT NULL,
+    updated_at   timestamp with time zone                       NOT NULL
+);
+
+CREATE INDEX idx_personal_access_tokens_user_id ON personal_access_tokens (user_id);
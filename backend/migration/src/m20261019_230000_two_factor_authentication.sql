@@ -0,0 +1,40 @@
+-- ############################################################
+-- #                                                          #
+-- #                Two-Factor Authentication                 #
+-- #                                                          #
+-- ############################################################
+
+-- The base32 encoded TOTP secret is set on enrollment, two-factor authentication is enabled once a code confirmed it.
+ALTER TABLE users
+    ADD COLUMN totp_secret     TEXT,
+    ADD COLUMN totp_enabled_at timestamp with time zone;
+
+-- One-time codes that replace a TOTP code, e.g. when the authenticator got lost.
+CREATE TABLE recovery_codes
+(
+    id         BIGINT PRIMARY KEY,
+    user_id    BIGINT REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+    code_hash  TEXT                                           NOT NULL,
+    used_at    timestamp with time zone,
+    created_at timestamp with time zone                       NOT NULL,
+    updated_at timestamp with time zone                       NOT NULL
+);
+
+CREATE INDEX idx_recovery_codes_user_id ON recovery_codes (user_id);
+
+-- Issued after the password was verified for a user with two-factor authentication,
+-- exchanged for a session together with a TOTP or recovery code.
+CREATE TABLE two_factor_challenges
+(
+    id         BIGINT PRIMARY KEY,
+    user_id    BIGINT REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+    token_hash TEXT                                           NOT NULL UNIQUE,
+    -- The name and user agent of the session to create.
+    name       TEXT,
+    user_agent TEXT,
+    expires_at timestamp with time zone                       NOT NULL,
+    created_at timestamp with time zone                       NOT NULL,
+    updated_at timestamp with time zone                       NOT NULL
+);
+
+CREATE INDEX idx_two_factor_challenges_user_id ON two_factor_challenges (user_id);
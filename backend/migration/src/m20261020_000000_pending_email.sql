@@ -0,0 +1,10 @@
+-- ############################################################
+-- #                                                          #
+-- #                      Pending Email                       #
+-- #                                                          #
+-- ############################################################
+
+-- The new email address of a requested email change.
+-- It replaces the email address once the email verification token sent to it is confirmed.
+ALTER TABLE users
+    ADD COLUMN pending_email TEXT;
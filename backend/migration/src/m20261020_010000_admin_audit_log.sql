@@ -0,0 +1,26 @@
+-- ############################################################
+-- #                                                          #
+-- #                  Admin User Management                   #
+-- #                                                          #
+-- ############################################################
+
+-- Disabled accounts can not log in until an admin enables them again.
+ALTER TABLE users
+    ADD COLUMN disabled_at timestamp with time zone;
+
+CREATE TYPE audit_action AS ENUM ('verify_email', 'grant_admin', 'revoke_admin', 'force_password_reset', 'revoke_sessions', 'disable_account', 'enable_account');
+
+-- Every action of an admin on another account.
+-- The entries are kept when either account gets deleted.
+CREATE TABLE audit_logs
+(
+    id             BIGINT PRIMARY KEY,
+    actor_id       BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    target_user_id BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    action         audit_action             NOT NULL,
+    details        JSONB,
+    created_at     timestamp with time zone NOT NULL,
+    updated_at     timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_audit_logs_target_user_id ON audit_logs (target_user_id);
@@ -0,0 +1,23 @@
+-- ############################################################
+-- #                                                          #
+-- #                       Invite Codes                       #
+-- #                                                          #
+-- ############################################################
+
+-- Allow registering while the registration is invite-only.
+CREATE TABLE invite_codes
+(
+    id            BIGINT PRIMARY KEY,
+    code_hash     TEXT                     NOT NULL UNIQUE,
+    created_by_id BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    -- Restricts the code to this email address, the invite is sent to it.
+    email         TEXT,
+    max_uses      INTEGER                  NOT NULL,
+    uses          INTEGER                  NOT NULL DEFAULT 0,
+    expires_at    timestamp with time zone,
+    created_at    timestamp with time zone NOT NULL,
+    updated_at    timestamp with time zone NOT NULL
+);
+
+ALTER TYPE audit_action ADD VALUE 'create_invite_code';
+ALTER TYPE audit_action ADD VALUE 'delete_invite_code';
@@ -0,0 +1,12 @@
+-- ############################################################
+-- #                                                          #
+-- #                 Transaction External Id                  #
+-- #                                                          #
+-- ############################################################
+
+-- The id of the transaction at the bank, like the FITID of an OFX file.
+-- Imports use it to detect duplicates, because it stays the same when the bank exports the transaction again.
+ALTER TABLE transactions
+    ADD COLUMN external_id TEXT;
+
+CREATE INDEX idx_transactions_external_id ON transactions (external_id);
@@ -0,0 +1,10 @@
+-- ############################################################
+-- #                                                          #
+-- #                      TOTP Last Step                      #
+-- #                                                          #
+-- ############################################################
+
+-- The time step of the last accepted TOTP code.
+-- Codes of this or an earlier step are rejected, so an observed code can not be used a second time.
+ALTER TABLE users
+    ADD COLUMN totp_last_step BIGINT;
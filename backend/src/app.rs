@@ -1,10 +1,15 @@
 use crate::initializers::openapi::OpenApiInitializer;
 use crate::initializers::path_normalization::PathNormalizationInitializer;
+use crate::initializers::rate_limit::RateLimitInitializer;
 use crate::initializers::services::ServicesInitializer;
 use crate::models::_entities::instances;
 use crate::utils::folder::{create_necessary_folders, STORAGE_FOLDER};
 use crate::utils::routes::ExtendedAppRoutes;
+use crate::workers::access_token_used::AccessTokenUsedWorker;
+use crate::workers::export::ExportWorker;
+use crate::workers::reapply_rules::ReapplyRulesWorker;
 use crate::workers::session_used::SessionUsedWorker;
+use crate::workers::train_category_classifier::TrainCategoryClassifierWorker;
 use crate::{controllers, models::_entities::users, tasks};
 use async_trait::async_trait;
 use loco_rs::cache::Cache;
@@ -63,6 +68,7 @@ impl Hooks for App {
     async fn initializers(_ctx: &AppContext) -> Result<Vec<Box<dyn Initializer>>> {
         Ok(vec![
             Box::new(PathNormalizationInitializer),
+            Box::new(RateLimitInitializer),
             Box::new(OpenApiInitializer),
             Box::new(ServicesInitializer),
         ])
@@ -80,8 +86,18 @@ impl Hooks for App {
             .add_route(controllers::openapi::non_versioned_routes())
             .prefix("/v1")
             .add_route(controllers::user::routes())
+            .add_route(controllers::two_factor::routes())
             .add_route(controllers::session::routes())
+            .add_route(controllers::access_token::routes())
+            .add_route(controllers::admin::routes())
             .add_route(controllers::status::routes())
+            .add_route(controllers::import::routes())
+            .add_route(controllers::transaction::routes())
+            .add_route(controllers::rule::routes())
+            .add_route(controllers::reconciliation::routes())
+            .add_route(controllers::exchange_rate::routes())
+            .add_route(controllers::report::routes())
+            .add_route(controllers::export::routes())
             .into()
     }
 
@@ -95,11 +111,18 @@ impl Hooks for App {
 
     async fn connect_workers(ctx: &AppContext, queue: &Queue) -> Result<()> {
         queue.register(SessionUsedWorker::build(ctx)).await?;
+        queue.register(AccessTokenUsedWorker::build(ctx)).await?;
+        queue.register(ReapplyRulesWorker::build(ctx)).await?;
+        queue.register(TrainCategoryClassifierWorker::build(ctx)).await?;
+        queue.register(ExportWorker::build(ctx)).await?;
 
         Ok(())
     }
     fn register_tasks(tasks: &mut Tasks) {
         tasks.register(tasks::seed::SeedData);
+        tasks.register(tasks::snapshot_balances::SnapshotBalances);
+        tasks.register(tasks::delete_accounts::DeleteAccounts);
+        tasks.register(tasks::clean_up_sessions::CleanUpSessions);
         // tasks-inject (do not remove)
     }
 
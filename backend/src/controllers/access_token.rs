@@ -0,0 +1,150 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{personal_access_tokens, sessions};
+use crate::models::personal_access_tokens::Scope;
+use crate::services::secret_generator::SecretGenerator;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::validation::access_token::validate_expires_in_future;
+use crate::views::access_token::AccessTokenResponse;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{delete, get};
+use axum::{debug_handler, Extension, Json};
+use chrono::{DateTime, FixedOffset};
+use enumflags2::BitFlags;
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::ModelTrait;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub const MIN_ACCESS_TOKEN_NAME: u64 = 1;
+pub const MAX_ACCESS_TOKEN_NAME: u64 = 512;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct CreateAccessTokenParams {
+    #[validate(length(min = "MIN_ACCESS_TOKEN_NAME", max = "MAX_ACCESS_TOKEN_NAME"))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<Scope>,
+    /// The token is rejected after this point in time. `null` creates a token that does not expire.
+    #[validate(custom(function = "validate_expires_in_future"))]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+/// List all personal access tokens of the current User, the latest first.
+#[utoipa::path(get,
+    path = "/api/v1/access-tokens",
+    tag = "Access Token",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all personal access tokens.", content_type="application/json", body = Vec<AccessTokenResponse>),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_access_tokens(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<Vec<AccessTokenResponse>>)> {
+    let access_tokens = personal_access_tokens::Model::find_all_by_user_id(&ctx.db, session.user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(access_tokens.into_iter().map(AccessTokenResponse::from).collect()),
+    ))
+}
+
+/// Create a personal access token for scripts and integrations.
+///
+/// The token can only be used for the endpoints its scopes allow. Creating and managing tokens requires a login
+/// Session. The token is only returned here, it can not be retrieved again.
+#[utoipa::path(post,
+    path = "/api/v1/access-tokens",
+    tag = "Access Token",
+    request_body = CreateAccessTokenParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created the personal access token.", content_type="application/json", body = AccessTokenResponse),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_access_token(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<CreateAccessTokenParams>,
+) -> AppResult<(StatusCode, Json<AccessTokenResponse>)> {
+    params.validate()?;
+
+    let (access_token, token) = personal_access_tokens::Model::create(
+        &ctx.db,
+        &snowflake_generator,
+        &secret_generator,
+        session.user_id,
+        params.name,
+        params.scopes.into_iter().collect::<BitFlags<Scope>>(),
+        params.expires_at,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AccessTokenResponse {
+            token: Some(token),
+            ..AccessTokenResponse::from(access_token)
+        }),
+    ))
+}
+
+/// Revoke a personal access token.
+#[utoipa::path(delete,
+    path = "/api/v1/access-tokens/{id}",
+    tag = "Access Token",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the personal access token."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully revoked the personal access token."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_access_token(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    personal_access_tokens::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id)
+        .await?
+        .delete(&ctx.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/access-tokens")
+        .add("/", get(list_access_tokens).post(create_access_token))
+        .add("/{id}", delete(delete_access_token))
+}
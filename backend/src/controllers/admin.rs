@@ -0,0 +1,595 @@
+use crate::error::app_error::{
+    AppError, AppResult, EmailConfigurationMissingResponse, EntityNotFoundResponse, GeneralInternalServerErrorResponse,
+    GeneralValidationErrorResponse, InvalidBearerTokenResponse, MissingPermissionsResponse,
+    OwnAccountNotAllowedResponse,
+};
+use crate::mailers::auth::AuthMailer;
+use crate::middlewares::authentication::Admin;
+use crate::models::_entities::sea_orm_active_enums::AuditAction;
+use crate::models::_entities::{invite_codes, sessions};
+use crate::models::{audit_logs, users};
+use crate::services::secret_generator::SecretGenerator;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{Page, Pagination};
+use crate::types::snowflake::Snowflake;
+use crate::utils::context::AdditionalAppContextMethods;
+use crate::validation::access_token::validate_expires_in_future;
+use crate::views::admin::{AuditLogResponse, InviteCodeResponse};
+use crate::views::user::UserResponse;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{delete, get, post, put};
+use axum::{debug_handler, Extension, Json};
+use chrono::{DateTime, FixedOffset};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::{IntoActiveModel, ModelTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const MAX_SEARCH_TEXT_LENGTH: u64 = 512;
+pub const MAX_DISABLE_REASON_LENGTH: u64 = 1024;
+pub const MAX_INVITE_CODE_USES: i32 = 1000;
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct UserSearchQuery {
+    /// Matches the email address or the name of the User, ignoring case.
+    #[validate(length(min = 1, max = "MAX_SEARCH_TEXT_LENGTH"))]
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogQuery {
+    /// Only returns the entries of the actions on this User.
+    pub user_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SetAdminParams {
+    pub admin: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct DisableUserParams {
+    /// Recorded in the audit log.
+    #[validate(length(min = 1, max = "MAX_DISABLE_REASON_LENGTH"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct CreateInviteCodeParams {
+    /// Restricts the code to this email address and sends the invite to it, if an Email Server was configured.
+    #[validate(email)]
+    pub email: Option<String>,
+    /// The number of accounts that can be registered with the code.
+    #[validate(range(min = 1, max = "MAX_INVITE_CODE_USES"))]
+    pub max_uses: i32,
+    /// The code is rejected after this point in time. `null` creates a code that does not expire.
+    #[validate(custom(function = "validate_expires_in_future"))]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+/// List and search all Users.
+#[utoipa::path(get,
+    path = "/api/v1/admin/users",
+    tag = "Admin",
+    params(UserSearchQuery, Pagination),
+    responses(
+        (status = StatusCode::OK, description = "Successfully searched the Users.", content_type="application/json", body = Page<UserResponse>),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_users(
+    State(ctx): State<AppContext>,
+    Admin(_admin): Admin,
+    Query(params): Query<UserSearchQuery>,
+    pagination: Pagination,
+) -> AppResult<(StatusCode, Json<Page<UserResponse>>)> {
+    params.validate()?;
+
+    let page = users::Model::search(&ctx.db, params.q.as_deref(), &pagination).await?;
+
+    Ok((StatusCode::OK, Json(page.map(UserResponse::from))))
+}
+
+/// Retrieve a User.
+#[utoipa::path(get,
+    path = "/api/v1/admin/users/{id}",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the User.", content_type="application/json", body = UserResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_user(
+    State(ctx): State<AppContext>,
+    Admin(_admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Verify the email address of a User manually.
+#[utoipa::path(post,
+    path = "/api/v1/admin/users/{id}/verify",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully verified the User.", content_type="application/json", body = UserResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn verify_user(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    let txn = ctx.db.begin().await?;
+    let user = user.into_active_model().verified(&txn).await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        Some(user.id),
+        AuditAction::VerifyEmail,
+        None,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Grant or revoke the admin flag of a User.
+///
+/// Admins can not revoke their own admin flag.
+#[utoipa::path(put,
+    path = "/api/v1/admin/users/{id}/admin",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    request_body = SetAdminParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully updated the admin flag.", content_type="application/json", body = UserResponse),
+        OwnAccountNotAllowedResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn set_admin(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<SetAdminParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    if admin.id == id.id && !params.admin {
+        return Err(AppError::OwnAccountNotAllowed());
+    }
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+    let action = match params.admin {
+        true => AuditAction::GrantAdmin,
+        false => AuditAction::RevokeAdmin,
+    };
+
+    let txn = ctx.db.begin().await?;
+    let user = user.into_active_model().set_admin(&txn, params.admin).await?;
+    audit_logs::Model::record(&txn, &snowflake_generator, admin.id, Some(user.id), action, None).await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Force a User to reset their password.
+///
+/// The password is replaced with a random one and all Sessions of the User are revoked.
+/// The User receives a reset token by email, like for a forgotten password.
+/// This endpoint only works if an Email Server was configured.
+#[utoipa::path(post,
+    path = "/api/v1/admin/users/{id}/reset-password",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully forced the password reset.", content_type="application/json", body = UserResponse),
+        EmailConfigurationMissingResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn force_password_reset(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    if !ctx.is_mailer_enabled() {
+        return Err(AppError::EmailConfigurationMissing());
+    }
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    let txn = ctx.db.begin().await?;
+    let user = user
+        .into_active_model()
+        .force_password_reset(&txn, &secret_generator)
+        .await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        Some(user.id),
+        AuditAction::ForcePasswordReset,
+        None,
+    )
+    .await?;
+    txn.commit().await?;
+
+    AuthMailer::forgot_password(&ctx, &user).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Revoke all Sessions of a User.
+#[utoipa::path(delete,
+    path = "/api/v1/admin/users/{id}/sessions",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully revoked the Sessions."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn revoke_sessions(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    let txn = ctx.db.begin().await?;
+    let revoked = sessions::Model::revoke_all_by_user_id(&txn, user.id, None).await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        Some(user.id),
+        AuditAction::RevokeSessions,
+        Some(json!({ "revoked_sessions": revoked })),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Disable the account of a User.
+///
+/// Disabled Users can not log in, all of their Sessions and personal access tokens are revoked.
+/// Admins can not disable their own account.
+#[utoipa::path(post,
+    path = "/api/v1/admin/users/{id}/disable",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    request_body = DisableUserParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully disabled the User.", content_type="application/json", body = UserResponse),
+        OwnAccountNotAllowedResponse,
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn disable_user(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<DisableUserParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate()?;
+    if admin.id == id.id {
+        return Err(AppError::OwnAccountNotAllowed());
+    }
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    let txn = ctx.db.begin().await?;
+    let user = user.into_active_model().disable(&txn).await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        Some(user.id),
+        AuditAction::DisableAccount,
+        params.reason.map(|reason| json!({ "reason": reason })),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Enable the disabled account of a User again.
+#[utoipa::path(post,
+    path = "/api/v1/admin/users/{id}/enable",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the User."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully enabled the User.", content_type="application/json", body = UserResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn enable_user(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = users::Model::find_by_id(&ctx.db, id.id).await?;
+
+    let txn = ctx.db.begin().await?;
+    let user = user.into_active_model().enable(&txn).await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        Some(user.id),
+        AuditAction::EnableAccount,
+        None,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// List the audit log of all admin actions, the latest first.
+#[utoipa::path(get,
+    path = "/api/v1/admin/audit-logs",
+    tag = "Admin",
+    params(AuditLogQuery, Pagination),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the audit log.", content_type="application/json", body = Page<AuditLogResponse>),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_audit_logs(
+    State(ctx): State<AppContext>,
+    Admin(_admin): Admin,
+    Query(params): Query<AuditLogQuery>,
+    pagination: Pagination,
+) -> AppResult<(StatusCode, Json<Page<AuditLogResponse>>)> {
+    let page = audit_logs::Model::find_page(&ctx.db, params.user_id.map(|user_id| user_id.id), &pagination).await?;
+
+    Ok((StatusCode::OK, Json(page.map(AuditLogResponse::from))))
+}
+
+/// List all invite codes, the latest first.
+#[utoipa::path(get,
+    path = "/api/v1/admin/invite-codes",
+    tag = "Admin",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all invite codes.", content_type="application/json", body = Vec<InviteCodeResponse>),
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_invite_codes(
+    State(ctx): State<AppContext>,
+    Admin(_admin): Admin,
+) -> AppResult<(StatusCode, Json<Vec<InviteCodeResponse>>)> {
+    let invite_codes = invite_codes::Model::find_all(&ctx.db).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(invite_codes.into_iter().map(InviteCodeResponse::from).collect()),
+    ))
+}
+
+/// Create an invite code, which allows registering while the registration is invite-only.
+///
+/// The code is only returned here, it can not be retrieved again.
+#[utoipa::path(post,
+    path = "/api/v1/admin/invite-codes",
+    tag = "Admin",
+    request_body = CreateInviteCodeParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created the invite code.", content_type="application/json", body = InviteCodeResponse),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_invite_code(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Admin(admin): Admin,
+    Json(params): Json<CreateInviteCodeParams>,
+) -> AppResult<(StatusCode, Json<InviteCodeResponse>)> {
+    params.validate()?;
+
+    let txn = ctx.db.begin().await?;
+    let (invite_code, code) = invite_codes::Model::create(
+        &txn,
+        &snowflake_generator,
+        &secret_generator,
+        admin.id,
+        params.email,
+        params.max_uses,
+        params.expires_at,
+    )
+    .await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        None,
+        AuditAction::CreateInviteCode,
+        Some(json!({ "invite_code_id": Snowflake::new(invite_code.id) })),
+    )
+    .await?;
+    txn.commit().await?;
+
+    if ctx.is_mailer_enabled() {
+        AuthMailer::send_invite(&ctx, &invite_code, &code, &admin).await?;
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InviteCodeResponse {
+            code: Some(code),
+            ..InviteCodeResponse::from(invite_code)
+        }),
+    ))
+}
+
+/// Delete an invite code, so it can not be used anymore.
+#[utoipa::path(delete,
+    path = "/api/v1/admin/invite-codes/{id}",
+    tag = "Admin",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the invite code."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the invite code."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingPermissionsResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_invite_code(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Admin(admin): Admin,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let txn = ctx.db.begin().await?;
+    invite_codes::Model::find_by_id(&txn, id.id).await?.delete(&txn).await?;
+    audit_logs::Model::record(
+        &txn,
+        &snowflake_generator,
+        admin.id,
+        None,
+        AuditAction::DeleteInviteCode,
+        Some(json!({ "invite_code_id": id })),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/admin")
+        .add("/users", get(list_users))
+        .add("/users/{id}", get(get_user))
+        .add("/users/{id}/verify", post(verify_user))
+        .add("/users/{id}/admin", put(set_admin))
+        .add("/users/{id}/reset-password", post(force_password_reset))
+        .add("/users/{id}/sessions", delete(revoke_sessions))
+        .add("/users/{id}/disable", post(disable_user))
+        .add("/users/{id}/enable", post(enable_user))
+        .add("/invite-codes", get(list_invite_codes).post(create_invite_code))
+        .add("/invite-codes/{id}", delete(delete_invite_code))
+        .add("/audit-logs", get(list_audit_logs))
+}
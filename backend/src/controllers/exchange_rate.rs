@@ -0,0 +1,136 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{currencies, exchange_rates, sessions};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::validation::exchange_rate::validate_exchange_rate;
+use crate::views::exchange_rate::ExchangeRateResponse;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{delete, get};
+use axum::{debug_handler, Extension, Json};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::ModelTrait;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_exchange_rate"))]
+pub struct ExchangeRateParams {
+    pub source_currency_id: Snowflake,
+    pub target_currency_id: Snowflake,
+    /// The price of one major unit of the source currency in major units of the target currency
+    /// (e.g. `1.08` for EUR to USD).
+    #[validate(range(exclusive_min = 0.0))]
+    pub rate: f64,
+}
+
+/// List all exchange rates of the current User.
+#[utoipa::path(get,
+    path = "/api/v1/exchange-rates",
+    tag = "Exchange Rate",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all exchange rates.", content_type="application/json", body = Vec<ExchangeRateResponse>),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_exchange_rates(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<Vec<ExchangeRateResponse>>)> {
+    let exchange_rates = exchange_rates::Model::find_all_by_user_id(&ctx.db, session.user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(exchange_rates.into_iter().map(ExchangeRateResponse::from).collect()),
+    ))
+}
+
+/// Set the exchange rate between two currencies.
+///
+/// Replaces the previous rate of the same currency pair.
+/// The inverse rate is used automatically if only the opposite direction is known.
+#[utoipa::path(put,
+    path = "/api/v1/exchange-rates",
+    tag = "Exchange Rate",
+    request_body = ExchangeRateParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully set the exchange rate.", content_type="application/json", body = ExchangeRateResponse),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn set_exchange_rate(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<ExchangeRateParams>,
+) -> AppResult<(StatusCode, Json<ExchangeRateResponse>)> {
+    params.validate()?;
+    currencies::Model::find_accessible_by_id(&ctx.db, params.source_currency_id.id, session.user_id).await?;
+    currencies::Model::find_accessible_by_id(&ctx.db, params.target_currency_id.id, session.user_id).await?;
+
+    let exchange_rate = exchange_rates::Model::set(
+        &ctx.db,
+        &snowflake_generator,
+        session.user_id,
+        params.source_currency_id.id,
+        params.target_currency_id.id,
+        params.rate,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, Json(ExchangeRateResponse::from(exchange_rate))))
+}
+
+/// Delete an exchange rate.
+#[utoipa::path(delete,
+    path = "/api/v1/exchange-rates/{id}",
+    tag = "Exchange Rate",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the exchange rate."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the exchange rate."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_exchange_rate(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let exchange_rate = exchange_rates::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+    exchange_rate.delete(&ctx.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/exchange-rates")
+        .add("/", get(list_exchange_rates).put(set_exchange_rate))
+        .add("/{id}", delete(delete_exchange_rate))
+}
@@ -0,0 +1,204 @@
+use crate::error::app_error::{
+    AppError, AppResult, EntityNotFoundResponse, ExportNotFinishedResponse, GeneralInternalServerErrorResponse,
+    InvalidBearerTokenResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::sea_orm_active_enums::{ExportFormat, ExportStatus};
+use crate::models::_entities::{exports, sessions};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::views::export::ExportResponse;
+use crate::workers::export::{ExportWorker, ExportWorkerArgs};
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderName, StatusCode};
+use axum::routing::get;
+use axum::{debug_handler, Extension, Json};
+use loco_rs::app::AppContext;
+use loco_rs::bgworker::BackgroundWorker;
+use loco_rs::controller::Routes;
+use sea_orm::ModelTrait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateExportParams {
+    pub format: ExportFormat,
+}
+
+/// List all exports of the current User, the latest first.
+#[utoipa::path(get,
+    path = "/api/v1/exports",
+    tag = "Export",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all exports.", content_type="application/json", body = Vec<ExportResponse>),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_exports(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<Vec<ExportResponse>>)> {
+    let exports = exports::Model::find_all_by_user_id(&ctx.db, session.user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(exports.into_iter().map(ExportResponse::from).collect()),
+    ))
+}
+
+/// Export the data of the current User.
+///
+/// Contains the transactions of all bank accounts the User can read, together with the bank accounts,
+/// categories and tags. The `archive` format additionally contains everything else that is stored about the User,
+/// like sessions, permissions, budgets and the attached files.
+/// The export is written in the background, its download url is set once it is finished.
+#[utoipa::path(post,
+    path = "/api/v1/exports",
+    tag = "Export",
+    request_body = CreateExportParams,
+    responses(
+        (status = StatusCode::ACCEPTED, description = "Successfully scheduled the export.", content_type="application/json", body = ExportResponse),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_export(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<CreateExportParams>,
+) -> AppResult<(StatusCode, Json<ExportResponse>)> {
+    let export = exports::Model::create(&ctx.db, &snowflake_generator, session.user_id, params.format).await?;
+    ExportWorker::perform_later(&ctx, ExportWorkerArgs { export_id: export.id }).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(ExportResponse::from(export))))
+}
+
+/// Retrieve an export.
+#[utoipa::path(get,
+    path = "/api/v1/exports/{id}",
+    tag = "Export",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the export."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the export.", content_type="application/json", body = ExportResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_export(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<ExportResponse>)> {
+    let export = exports::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+
+    Ok((StatusCode::OK, Json(ExportResponse::from(export))))
+}
+
+/// Download the file of a finished export.
+#[utoipa::path(get,
+    path = "/api/v1/exports/{id}/download",
+    tag = "Export",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the export."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully downloaded the export.", content_type="application/octet-stream", body = Vec<u8>),
+        ExportNotFinishedResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn download_export(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, [(HeaderName, String); 2], Vec<u8>)> {
+    let export = exports::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+    let file_path = match (&export.status, &export.file_path) {
+        (ExportStatus::Finished, Some(file_path)) => PathBuf::from(file_path),
+        _ => return Err(AppError::ExportNotFinished()),
+    };
+    let content = ctx
+        .storage
+        .download::<Vec<u8>>(&file_path)
+        .await
+        .map_err(|err| AppError::StorageError(err.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, export.format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", export.file_name()),
+            ),
+        ],
+        content,
+    ))
+}
+
+/// Delete an export together with its file.
+#[utoipa::path(delete,
+    path = "/api/v1/exports/{id}",
+    tag = "Export",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the export."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the export."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_export(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let export = exports::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+    if let Some(file_path) = &export.file_path {
+        ctx.storage
+            .delete(&PathBuf::from(file_path))
+            .await
+            .map_err(|err| AppError::StorageError(err.to_string()))?;
+    }
+    export.delete(&ctx.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/exports")
+        .add("/", get(list_exports).post(create_export))
+        .add("/{id}", get(get_export).delete(delete_export))
+        .add("/{id}/download", get(download_export))
+}
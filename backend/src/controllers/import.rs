@@ -0,0 +1,446 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse, InvalidImportFileResponse, MissingPermissionsResponse, MissingScopeResponse,
+};
+use crate::middlewares::authentication::{Authenticated, Principal};
+use crate::models::_entities::sea_orm_active_enums::ImportSignConvention;
+use crate::models::_entities::{bank_accounts, currencies, import_profiles};
+use crate::models::personal_access_tokens::scopes;
+use crate::models::user_permissions::Permission;
+use crate::services::import::csv::{parse_csv, CsvColumnMapping, CsvImportSettings};
+use crate::services::import::ofx::parse_ofx;
+use crate::services::import::qif::{parse_qif, QifImportSettings};
+use crate::services::import::{ImportRowOutcome, ImportService};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::validation::import::{validate_decimal_separator, validate_import_profile};
+use crate::views::import::{ImportProfileResponse, ImportResponse};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{debug_handler, Extension, Json};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::ModelTrait;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const MIN_PROFILE_NAME_LENGTH: u64 = 1;
+pub const MAX_PROFILE_NAME_LENGTH: u64 = 255;
+
+pub const MAX_DATE_FORMAT_LENGTH: u64 = 64;
+pub const DECIMAL_SEPARATORS: [&str; 2] = [".", ","];
+pub const MAX_SKIP_ROWS: u32 = 100;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_import_profile"))]
+pub struct ImportProfileParams {
+    #[validate(length(min = "MIN_PROFILE_NAME_LENGTH", max = "MAX_PROFILE_NAME_LENGTH"))]
+    pub name: String,
+    pub column_mapping: CsvColumnMapping,
+    /// A `chrono` format string, e.g. `%d.%m.%Y`.
+    #[validate(length(min = 1, max = "MAX_DATE_FORMAT_LENGTH"))]
+    pub date_format: String,
+    #[validate(length(equal = 1))]
+    pub decimal_separator: String,
+    #[validate(length(equal = 1))]
+    pub thousands_separator: Option<String>,
+    #[validate(length(equal = 1))]
+    pub delimiter: String,
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    /// The amount of lines that are skipped before the header (or the first row).
+    #[serde(default)]
+    #[validate(range(max = "MAX_SKIP_ROWS"))]
+    pub skip_rows: u32,
+    pub sign_convention: ImportSignConvention,
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CsvImportQuery {
+    /// The bank account the transactions are imported into.
+    pub bank_account_id: Snowflake,
+    /// The import profile that describes the layout of the file.
+    pub profile_id: Snowflake,
+    /// Only validates the file and returns the result without persisting anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OfxImportQuery {
+    /// The bank account the transactions are imported into.
+    pub bank_account_id: Snowflake,
+    /// Only validates the file and returns the result without persisting anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct QifImportQuery {
+    /// The bank account the transactions are imported into.
+    pub bank_account_id: Snowflake,
+    /// Only validates the file and returns the result without persisting anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// A `chrono` format string for the dates of the file. Defaults to `%m/%d/%Y`.
+    #[validate(length(min = 1, max = "MAX_DATE_FORMAT_LENGTH"))]
+    pub date_format: Option<String>,
+    /// Either `.` or `,`. Defaults to `.`.
+    #[validate(custom(function = "validate_decimal_separator"))]
+    pub decimal_separator: Option<String>,
+}
+
+impl From<&QifImportQuery> for QifImportSettings {
+    fn from(value: &QifImportQuery) -> Self {
+        let default = Self::default();
+
+        Self {
+            date_format: value.date_format.clone().unwrap_or(default.date_format),
+            decimal_separator: value
+                .decimal_separator
+                .as_ref()
+                .and_then(|separator| separator.chars().next())
+                .unwrap_or(default.decimal_separator),
+        }
+    }
+}
+
+/// List all import profiles of the current User.
+#[utoipa::path(get,
+    path = "/api/v1/import/profiles",
+    tag = "Import",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all import profiles.", content_type="application/json", body = Vec<ImportProfileResponse>),
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_profiles(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+) -> AppResult<(StatusCode, Json<Vec<ImportProfileResponse>>)> {
+    let profiles = import_profiles::Model::find_all_by_user_id(&ctx.db, principal.user_id)
+        .await?
+        .into_iter()
+        .map(ImportProfileResponse::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+
+    Ok((StatusCode::OK, Json(profiles)))
+}
+
+/// Create a new import profile.
+///
+/// An import profile describes the layout of a csv file (columns, formats and sign convention)
+/// so that files of the same bank can be imported repeatedly.
+#[utoipa::path(post,
+    path = "/api/v1/import/profiles",
+    tag = "Import",
+    request_body = ImportProfileParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created a new import profile.", content_type="application/json", body = ImportProfileResponse),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_profile(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Json(params): Json<ImportProfileParams>,
+) -> AppResult<(StatusCode, Json<ImportProfileResponse>)> {
+    params.validate()?;
+
+    let profile = import_profiles::Model::create(&ctx.db, &snowflake_generator, principal.user_id, &params).await?;
+
+    Ok((StatusCode::CREATED, Json(ImportProfileResponse::try_from(profile)?)))
+}
+
+/// Retrieve an import profile.
+#[utoipa::path(get,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "Import",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the import profile."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the import profile.", content_type="application/json", body = ImportProfileResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_profile(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<ImportProfileResponse>)> {
+    let profile = import_profiles::Model::find_by_id_and_user_id(&ctx.db, id.id, principal.user_id).await?;
+
+    Ok((StatusCode::OK, Json(ImportProfileResponse::try_from(profile)?)))
+}
+
+/// Update an import profile.
+#[utoipa::path(put,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "Import",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the import profile."),
+    ),
+    request_body = ImportProfileParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully updated the import profile.", content_type="application/json", body = ImportProfileResponse),
+        EntityNotFoundResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_profile(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<ImportProfileParams>,
+) -> AppResult<(StatusCode, Json<ImportProfileResponse>)> {
+    params.validate()?;
+
+    let profile = import_profiles::Model::find_by_id_and_user_id(&ctx.db, id.id, principal.user_id)
+        .await?
+        .update_with_params(&ctx.db, &params)
+        .await?;
+
+    Ok((StatusCode::OK, Json(ImportProfileResponse::try_from(profile)?)))
+}
+
+/// Delete an import profile.
+#[utoipa::path(delete,
+    path = "/api/v1/import/profiles/{id}",
+    tag = "Import",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the import profile."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the import profile."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_profile(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let profile = import_profiles::Model::find_by_id_and_user_id(&ctx.db, id.id, principal.user_id).await?;
+    profile.delete(&ctx.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Import transactions from a csv file.
+///
+/// The raw csv file is expected as request body and parsed with the given import profile.
+/// Rows that can not be parsed are reported with their errors and do not abort the import.
+/// Rows that already exist in the bank account are skipped.
+/// Use `dry_run` to preview the result without persisting anything.
+#[utoipa::path(post,
+    path = "/api/v1/import/csv",
+    tag = "Import",
+    params(CsvImportQuery),
+    request_body(content = String, content_type = "text/csv"),
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully imported the file.", content_type="application/json", body = ImportResponse),
+        (status = StatusCode::OK, description = "Successfully previewed the import (dry-run).", content_type="application/json", body = ImportResponse),
+        InvalidImportFileResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn import_csv(
+    State(ctx): State<AppContext>,
+    Extension(import_service): Extension<ImportService>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Query(params): Query<CsvImportQuery>,
+    body: String,
+) -> AppResult<(StatusCode, Json<ImportResponse>)> {
+    let (bank_account, decimal_places) = find_import_target(&ctx, principal.user_id, &params.bank_account_id).await?;
+    let profile =
+        import_profiles::Model::find_by_id_and_user_id(&ctx.db, params.profile_id.id, principal.user_id).await?;
+
+    let settings = CsvImportSettings::try_from(&profile)?;
+    let rows = parse_csv(&settings, &body, decimal_places)?;
+    let outcomes = import_service
+        .import(principal.user_id, &bank_account, rows, params.dry_run)
+        .await?;
+
+    Ok(import_response(outcomes, params.dry_run))
+}
+
+/// Import transactions from an OFX or QFX file.
+///
+/// The raw file is expected as request body.
+/// Both SGML (OFX 1.x) and XML (OFX 2.x) files are supported.
+/// Rows that already exist in the bank account are skipped.
+/// Use `dry_run` to preview the result without persisting anything.
+#[utoipa::path(post,
+    path = "/api/v1/import/ofx",
+    tag = "Import",
+    params(OfxImportQuery),
+    request_body(content = String, content_type = "application/x-ofx"),
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully imported the file.", content_type="application/json", body = ImportResponse),
+        (status = StatusCode::OK, description = "Successfully previewed the import (dry-run).", content_type="application/json", body = ImportResponse),
+        InvalidImportFileResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn import_ofx(
+    State(ctx): State<AppContext>,
+    Extension(import_service): Extension<ImportService>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Query(params): Query<OfxImportQuery>,
+    body: String,
+) -> AppResult<(StatusCode, Json<ImportResponse>)> {
+    let (bank_account, decimal_places) = find_import_target(&ctx, principal.user_id, &params.bank_account_id).await?;
+
+    let rows = parse_ofx(&body, decimal_places)?;
+    let outcomes = import_service
+        .import(principal.user_id, &bank_account, rows, params.dry_run)
+        .await?;
+
+    Ok(import_response(outcomes, params.dry_run))
+}
+
+/// Import transactions from a QIF file.
+///
+/// The raw file is expected as request body.
+/// Categories (`L` fields) that do not exist yet are created.
+/// Rows that already exist in the bank account are skipped.
+/// Use `dry_run` to preview the result without persisting anything.
+#[utoipa::path(post,
+    path = "/api/v1/import/qif",
+    tag = "Import",
+    params(QifImportQuery),
+    request_body(content = String, content_type = "application/qif"),
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully imported the file.", content_type="application/json", body = ImportResponse),
+        (status = StatusCode::OK, description = "Successfully previewed the import (dry-run).", content_type="application/json", body = ImportResponse),
+        InvalidImportFileResponse,
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn import_qif(
+    State(ctx): State<AppContext>,
+    Extension(import_service): Extension<ImportService>,
+    Authenticated(principal): Authenticated<Principal, { scopes::IMPORT }>,
+    Query(params): Query<QifImportQuery>,
+    body: String,
+) -> AppResult<(StatusCode, Json<ImportResponse>)> {
+    params.validate()?;
+    let (bank_account, decimal_places) = find_import_target(&ctx, principal.user_id, &params.bank_account_id).await?;
+
+    let rows = parse_qif(&QifImportSettings::from(&params), &body, decimal_places)?;
+    let outcomes = import_service
+        .import(principal.user_id, &bank_account, rows, params.dry_run)
+        .await?;
+
+    Ok(import_response(outcomes, params.dry_run))
+}
+
+/// Finds the bank account to import into and the decimal places of its currency.
+async fn find_import_target(
+    ctx: &AppContext,
+    user_id: i64,
+    bank_account_id: &Snowflake,
+) -> AppResult<(bank_accounts::Model, u32)> {
+    let bank_account = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        user_id,
+        bank_account_id.id,
+        Permission::Read | Permission::Write,
+    )
+    .await?;
+    let currency = currencies::Model::find_by_id(&ctx.db, bank_account.currency_id).await?;
+
+    Ok((bank_account, currency.decimal_places.max(0) as u32))
+}
+
+fn import_response(outcomes: Vec<ImportRowOutcome>, dry_run: bool) -> (StatusCode, Json<ImportResponse>) {
+    let status = match dry_run {
+        true => StatusCode::OK,
+        false => StatusCode::CREATED,
+    };
+
+    (status, Json(ImportResponse::from((outcomes, dry_run))))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/import")
+        .add("/csv", post(import_csv))
+        .add("/ofx", post(import_ofx))
+        .add("/qif", post(import_qif))
+        .add("/profiles", get(list_profiles).post(create_profile))
+        .add(
+            "/profiles/{id}",
+            get(get_profile).put(update_profile).delete(delete_profile),
+        )
+}
@@ -0,0 +1,234 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse, MissingPermissionsResponse, OutdatedStatementResponse, ReconciliationMismatchResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{bank_accounts, reconciliations, sessions};
+use crate::models::user_permissions::Permission;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::views::reconciliation::{ReconciliationPreviewResponse, ReconciliationResponse};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{debug_handler, Extension, Json};
+use chrono::{DateTime, FixedOffset};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const MAX_RECONCILED_TRANSACTIONS: u64 = 10000;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReconciliationListQuery {
+    pub bank_account_id: Snowflake,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReconciliationPreviewQuery {
+    pub bank_account_id: Snowflake,
+    /// The end of the statement.
+    pub statement_date: DateTime<FixedOffset>,
+    /// The balance at the end of the statement in the smallest unit of the currency.
+    pub statement_balance: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct ReconcileParams {
+    pub bank_account_id: Snowflake,
+    /// The end of the statement.
+    pub statement_date: DateTime<FixedOffset>,
+    /// The balance at the end of the statement in the smallest unit of the currency.
+    pub statement_balance: i64,
+    /// The uncleared transactions that are part of the statement.
+    #[validate(length(max = "MAX_RECONCILED_TRANSACTIONS"))]
+    pub transaction_ids: Vec<Snowflake>,
+}
+
+/// List all reconciliations of a bank account, the latest first.
+#[utoipa::path(get,
+    path = "/api/v1/reconciliations",
+    tag = "Reconciliation",
+    params(ReconciliationListQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all reconciliations.", content_type="application/json", body = Vec<ReconciliationResponse>),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_reconciliations(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ReconciliationListQuery>,
+) -> AppResult<(StatusCode, Json<Vec<ReconciliationResponse>>)> {
+    let bank_account = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        session.user_id,
+        params.bank_account_id.id,
+        Permission::Read.into(),
+    )
+    .await?;
+
+    let mut responses = Vec::new();
+    for reconciliation in reconciliations::Model::find_all_by_bank_account_id(&ctx.db, bank_account.id).await? {
+        let transaction_ids = reconciliation.transaction_ids(&ctx.db).await?;
+        responses.push(ReconciliationResponse::from((reconciliation, transaction_ids)));
+    }
+
+    Ok((StatusCode::OK, Json(responses)))
+}
+
+/// Compare a bank account with a statement.
+///
+/// Lists all uncleared transactions of the bank account up to the end of the statement.
+/// The difference is zero if reconciling all of them matches the statement balance.
+#[utoipa::path(get,
+    path = "/api/v1/reconciliations/preview",
+    tag = "Reconciliation",
+    params(ReconciliationPreviewQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully compared the bank account with the statement.", content_type="application/json", body = ReconciliationPreviewResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn preview_reconciliation(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ReconciliationPreviewQuery>,
+) -> AppResult<(StatusCode, Json<ReconciliationPreviewResponse>)> {
+    let bank_account = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        session.user_id,
+        params.bank_account_id.id,
+        Permission::Read.into(),
+    )
+    .await?;
+    let preview = reconciliations::Model::preview(&ctx.db, &bank_account, params.statement_date).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReconciliationPreviewResponse::from((preview, params.statement_balance))),
+    ))
+}
+
+/// Reconcile a bank account with a statement.
+///
+/// The given transactions are marked as reconciled and locked against edits.
+/// Together with the last reconciled balance, they have to match the statement balance exactly.
+#[utoipa::path(post,
+    path = "/api/v1/reconciliations",
+    tag = "Reconciliation",
+    request_body = ReconcileParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully reconciled the bank account.", content_type="application/json", body = ReconciliationResponse),
+        GeneralValidationErrorResponse,
+        ReconciliationMismatchResponse,
+        OutdatedStatementResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn reconcile(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<ReconcileParams>,
+) -> AppResult<(StatusCode, Json<ReconciliationResponse>)> {
+    params.validate()?;
+
+    let bank_account = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        session.user_id,
+        params.bank_account_id.id,
+        Permission::Read | Permission::Write,
+    )
+    .await?;
+    let transaction_ids = params.transaction_ids.iter().map(|id| id.id).collect();
+
+    let txn = ctx.db.begin().await?;
+    let reconciliation = reconciliations::Model::reconcile(
+        &txn,
+        &snowflake_generator,
+        &bank_account,
+        params.statement_date,
+        params.statement_balance,
+        transaction_ids,
+    )
+    .await?;
+    let transaction_ids = reconciliation.transaction_ids(&txn).await?;
+    txn.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ReconciliationResponse::from((reconciliation, transaction_ids))),
+    ))
+}
+
+/// Retrieve a reconciliation.
+#[utoipa::path(get,
+    path = "/api/v1/reconciliations/{id}",
+    tag = "Reconciliation",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the reconciliation."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the reconciliation.", content_type="application/json", body = ReconciliationResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_reconciliation(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<ReconciliationResponse>)> {
+    let reconciliation = reconciliations::Model::find_by_id(&ctx.db, id.id).await?;
+    bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        session.user_id,
+        reconciliation.bank_account_id,
+        Permission::Read.into(),
+    )
+    .await?;
+    let transaction_ids = reconciliation.transaction_ids(&ctx.db).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ReconciliationResponse::from((reconciliation, transaction_ids))),
+    ))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/reconciliations")
+        .add("/", get(list_reconciliations).post(reconcile))
+        .add("/preview", get(preview_reconciliation))
+        .add("/{id}", get(get_reconciliation))
+}
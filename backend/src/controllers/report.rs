@@ -0,0 +1,371 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{balance_snapshots, bank_accounts, categories, currencies, sessions, users};
+use crate::models::balance_snapshots::{sample, Interval};
+use crate::models::exchange_rates::CurrencyConverter;
+use crate::models::forecasts;
+use crate::models::reports::{self, convert_rows, roll_up, sum_balances, ReportFilter};
+use crate::models::user_permissions::Permission;
+use crate::types::snowflake::Snowflake;
+use crate::types::snowflake_list::SnowflakeList;
+use crate::validation::report::{validate_net_worth_query, validate_report_query};
+use crate::views::report::{
+    CashFlowResponse, CategorySpendingResponse, CounterpartyResponse, ForecastResponse, NetWorthResponse,
+};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{debug_handler, Json};
+use chrono::{DateTime, FixedOffset, Months, NaiveDate};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use validator::Validate;
+
+pub const DEFAULT_COUNTERPARTIES: u64 = 10;
+pub const MAX_COUNTERPARTIES: u64 = 100;
+/// The longest net worth history, about ten years.
+pub const MAX_NET_WORTH_DAYS: i64 = 3660;
+pub const DEFAULT_FORECAST_MONTHS: u32 = 3;
+pub const MAX_FORECAST_MONTHS: u32 = 24;
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "validate_report_query"))]
+pub struct ReportQuery {
+    /// Only counts money that flows into or out of these bank accounts.
+    /// Transfers between them are not counted. Defaults to all bank accounts of the User.
+    pub bank_account_ids: Option<SnowflakeList>,
+    /// Only counts transactions with at least one of the tags, also on one of their split lines.
+    pub tag_ids: Option<SnowflakeList>,
+    /// The earliest booking date (inclusive).
+    pub from: Option<DateTime<FixedOffset>>,
+    /// The latest booking date (inclusive).
+    pub to: Option<DateTime<FixedOffset>>,
+    /// Converts all amounts with a known exchange rate into this currency.
+    /// Defaults to the preferred currency of the User.
+    pub currency_id: Option<Snowflake>,
+}
+
+impl ReportQuery {
+    fn filter(&self, user_id: i64) -> ReportFilter {
+        ReportFilter {
+            user_id,
+            bank_account_ids: self
+                .bank_account_ids
+                .as_ref()
+                .map(SnowflakeList::ids)
+                .unwrap_or_default(),
+            tag_ids: self.tag_ids.as_ref().map(SnowflakeList::ids).unwrap_or_default(),
+            from: self.from,
+            to: self.to,
+        }
+    }
+}
+
+/// Loads the converter into the requested or the preferred currency of the user, if there is one.
+async fn load_converter(
+    db: &DatabaseConnection,
+    user_id: i64,
+    currency_id: Option<&Snowflake>,
+) -> AppResult<Option<CurrencyConverter>> {
+    let currency_id = match currency_id {
+        Some(currency_id) => Some(currency_id.id),
+        None => users::Model::find_by_id(db, user_id).await?.preferred_currency_id,
+    };
+
+    match currency_id {
+        Some(currency_id) => {
+            let currency = currencies::Model::find_accessible_by_id(db, currency_id, user_id).await?;
+            Ok(Some(CurrencyConverter::load(db, user_id, currency).await?))
+        }
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct CounterpartyReportQuery {
+    /// The maximum number of counterparties.
+    #[validate(range(min = 1, max = "MAX_COUNTERPARTIES"))]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "validate_net_worth_query"))]
+pub struct NetWorthQuery {
+    /// Only sums up these bank accounts. Defaults to all bank accounts of the User.
+    pub bank_account_ids: Option<SnowflakeList>,
+    /// The first day (inclusive). Defaults to one year before the last day.
+    pub from: Option<NaiveDate>,
+    /// The last day (inclusive). Defaults to today.
+    pub to: Option<NaiveDate>,
+    /// The length of the periods, defaults to `month`.
+    pub interval: Option<Interval>,
+    /// Converts all balances with a known exchange rate into this currency.
+    /// Defaults to the preferred currency of the User.
+    pub currency_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+pub struct ForecastQuery {
+    /// Only projects these bank accounts. Defaults to all bank accounts of the User.
+    pub bank_account_ids: Option<SnowflakeList>,
+    /// The number of months to look ahead.
+    #[validate(range(min = 1, max = "MAX_FORECAST_MONTHS"))]
+    pub months: Option<u32>,
+}
+
+impl NetWorthQuery {
+    /// The first and the last day of the history.
+    pub fn range(&self) -> (NaiveDate, NaiveDate) {
+        let to = self.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let from = self
+            .from
+            .unwrap_or_else(|| to.checked_sub_months(Months::new(12)).unwrap_or(to));
+
+        (from, to)
+    }
+}
+
+/// Income and expenses per month.
+///
+/// Amounts in a currency without a known exchange rate are reported in their own currency.
+#[utoipa::path(get,
+    path = "/api/v1/reports/cash-flow",
+    tag = "Report",
+    params(ReportQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully created the report.", content_type="application/json", body = Vec<CashFlowResponse>),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn cash_flow(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ReportQuery>,
+) -> AppResult<(StatusCode, Json<Vec<CashFlowResponse>>)> {
+    params.validate()?;
+    let converter = load_converter(&ctx.db, session.user_id, params.currency_id.as_ref()).await?;
+
+    let rows = reports::cash_flow(&ctx.db, &params.filter(session.user_id)).await?;
+    let rows = convert_rows(rows, converter.as_ref());
+
+    Ok((
+        StatusCode::OK,
+        Json(rows.into_iter().map(CashFlowResponse::from).collect()),
+    ))
+}
+
+/// Income and expenses per category.
+///
+/// The totals of each category include all of its children.
+/// Amounts in a currency without a known exchange rate are reported in their own currency.
+#[utoipa::path(get,
+    path = "/api/v1/reports/categories",
+    tag = "Report",
+    params(ReportQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully created the report.", content_type="application/json", body = Vec<CategorySpendingResponse>),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn category_spending(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ReportQuery>,
+) -> AppResult<(StatusCode, Json<Vec<CategorySpendingResponse>>)> {
+    params.validate()?;
+    let converter = load_converter(&ctx.db, session.user_id, params.currency_id.as_ref()).await?;
+
+    let rows = reports::category_spending(&ctx.db, &params.filter(session.user_id)).await?;
+    let rows = convert_rows(rows, converter.as_ref());
+    let categories = categories::Model::find_all_accessible(&ctx.db, session.user_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            roll_up(&categories, rows)
+                .into_iter()
+                .map(CategorySpendingResponse::from)
+                .collect(),
+        ),
+    ))
+}
+
+/// The counterparties with the most income and expenses combined.
+///
+/// Counterparties are grouped by their name.
+/// Amounts in a currency without a known exchange rate are reported in their own currency.
+#[utoipa::path(get,
+    path = "/api/v1/reports/counterparties",
+    tag = "Report",
+    params(ReportQuery, CounterpartyReportQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully created the report.", content_type="application/json", body = Vec<CounterpartyResponse>),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn counterparties(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ReportQuery>,
+    Query(counterparty_params): Query<CounterpartyReportQuery>,
+) -> AppResult<(StatusCode, Json<Vec<CounterpartyResponse>>)> {
+    params.validate()?;
+    counterparty_params.validate()?;
+    let converter = load_converter(&ctx.db, session.user_id, params.currency_id.as_ref()).await?;
+
+    let rows = reports::counterparties(&ctx.db, &params.filter(session.user_id)).await?;
+    let mut rows = convert_rows(rows, converter.as_ref());
+    rows.sort_by_key(|row| std::cmp::Reverse(row.totals.income.saturating_add(row.totals.expense)));
+    rows.truncate(counterparty_params.limit.unwrap_or(DEFAULT_COUNTERPARTIES) as usize);
+
+    Ok((
+        StatusCode::OK,
+        Json(rows.into_iter().map(CounterpartyResponse::from).collect()),
+    ))
+}
+
+/// The sum of the balances of all bank accounts at the end of every period.
+///
+/// The balance of each bank account is reconstructed from its original balance and its transactions.
+/// Balances in a currency without a known exchange rate are reported in their own currency.
+#[utoipa::path(get,
+    path = "/api/v1/reports/net-worth",
+    tag = "Report",
+    params(NetWorthQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully created the report.", content_type="application/json", body = Vec<NetWorthResponse>),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn net_worth(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<NetWorthQuery>,
+) -> AppResult<(StatusCode, Json<Vec<NetWorthResponse>>)> {
+    params.validate()?;
+    let converter = load_converter(&ctx.db, session.user_id, params.currency_id.as_ref()).await?;
+    let (from, to) = params.range();
+    let interval = params.interval.unwrap_or_default();
+
+    let bank_account_ids = params
+        .bank_account_ids
+        .as_ref()
+        .map(SnowflakeList::ids)
+        .unwrap_or_default();
+    let bank_accounts = bank_accounts::Model::find_all_with_permissions(
+        &ctx.db,
+        session.user_id,
+        &bank_account_ids,
+        Permission::Read.into(),
+    )
+    .await?;
+
+    let mut balances = Vec::with_capacity(bank_accounts.len());
+    for bank_account in bank_accounts {
+        let daily_balances = balance_snapshots::Model::daily_balances(&ctx.db, &bank_account, from, to).await?;
+        balances.push((bank_account.currency_id, sample(&daily_balances, interval)));
+    }
+    let rows = sum_balances(balances, converter.as_ref());
+
+    Ok((
+        StatusCode::OK,
+        Json(rows.into_iter().map(NetWorthResponse::from).collect()),
+    ))
+}
+
+/// The projected balance of every bank account for the next months.
+///
+/// Every future execution of a recurring transaction (or contract) and every pending transaction is expected.
+/// Days on which a balance would be negative are flagged.
+#[utoipa::path(get,
+    path = "/api/v1/reports/forecast",
+    tag = "Report",
+    params(ForecastQuery),
+    responses(
+        (status = StatusCode::OK, description = "Successfully created the forecast.", content_type="application/json", body = Vec<ForecastResponse>),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn forecast(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Query(params): Query<ForecastQuery>,
+) -> AppResult<(StatusCode, Json<Vec<ForecastResponse>>)> {
+    params.validate()?;
+    let months = params.months.unwrap_or(DEFAULT_FORECAST_MONTHS);
+    let now = chrono::Utc::now();
+    let until = now.checked_add_months(Months::new(months)).unwrap_or(now);
+
+    let bank_account_ids = params
+        .bank_account_ids
+        .as_ref()
+        .map(SnowflakeList::ids)
+        .unwrap_or_default();
+    let bank_accounts = bank_accounts::Model::find_all_with_permissions(
+        &ctx.db,
+        session.user_id,
+        &bank_account_ids,
+        Permission::Read.into(),
+    )
+    .await?;
+    let forecasts = forecasts::forecast(&ctx.db, bank_accounts, until).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(forecasts.into_iter().map(ForecastResponse::from).collect()),
+    ))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/reports")
+        .add("/cash-flow", get(cash_flow))
+        .add("/categories", get(category_spending))
+        .add("/counterparties", get(counterparties))
+        .add("/net-worth", get(net_worth))
+        .add("/forecast", get(forecast))
+}
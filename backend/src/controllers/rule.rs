@@ -0,0 +1,298 @@
+use crate::error::app_error::{
+    AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::{categories, sessions, tags, transaction_rule_tags, transaction_rules};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::snowflake::Snowflake;
+use crate::validation::rule::validate_transaction_rule;
+use crate::views::rule::TransactionRuleResponse;
+use crate::workers::reapply_rules::{ReapplyRulesWorker, ReapplyRulesWorkerArgs};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{debug_handler, Extension, Json};
+use loco_rs::app::AppContext;
+use loco_rs::bgworker::BackgroundWorker;
+use loco_rs::controller::Routes;
+use sea_orm::{ConnectionTrait, ModelTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub const MIN_RULE_NAME_LENGTH: u64 = 1;
+pub const MAX_RULE_NAME_LENGTH: u64 = 255;
+pub const MAX_PATTERN_LENGTH: u64 = 255;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_transaction_rule"))]
+pub struct TransactionRuleParams {
+    #[validate(length(min = "MIN_RULE_NAME_LENGTH", max = "MAX_RULE_NAME_LENGTH"))]
+    pub name: String,
+    /// Rules are applied in ascending order of their position.
+    #[serde(default)]
+    pub position: i32,
+    /// Skips all following rules if this rule matched.
+    #[serde(default)]
+    pub stop_processing: bool,
+    /// Matches if the name contains the text (case-insensitive).
+    #[validate(length(min = 1, max = "MAX_PATTERN_LENGTH"))]
+    pub name_contains: Option<String>,
+    /// Matches if the purpose contains the text (case-insensitive).
+    #[validate(length(min = 1, max = "MAX_PATTERN_LENGTH"))]
+    pub purpose_contains: Option<String>,
+    #[validate(length(min = 5, max = 34))]
+    pub source_iban: Option<String>,
+    #[validate(length(min = 5, max = 34))]
+    pub destination_iban: Option<String>,
+    /// The minimum (positive) amount in the smallest unit of the currency.
+    #[validate(range(min = 0))]
+    pub min_amount: Option<i64>,
+    /// The maximum (positive) amount in the smallest unit of the currency.
+    #[validate(range(min = 0))]
+    pub max_amount: Option<i64>,
+    pub transaction_type: Option<TransactionType>,
+    /// Sets the category of matching transactions.
+    pub category_id: Option<Snowflake>,
+    /// Adds these tags to matching transactions.
+    #[serde(default)]
+    pub tag_ids: Vec<Snowflake>,
+    /// Renames matching transactions.
+    #[validate(length(min = 1, max = "MAX_RULE_NAME_LENGTH"))]
+    pub rename_to: Option<String>,
+}
+
+impl TransactionRuleParams {
+    pub fn tag_ids(&self) -> Vec<i64> {
+        let mut tag_ids: Vec<i64> = self.tag_ids.iter().map(|tag_id| tag_id.id).collect();
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+
+        tag_ids
+    }
+}
+
+/// List all transaction rules of the current User in the order they are applied.
+#[utoipa::path(get,
+    path = "/api/v1/rules",
+    tag = "Rule",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all transaction rules.", content_type="application/json", body = Vec<TransactionRuleResponse>),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_rules(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<Vec<TransactionRuleResponse>>)> {
+    let rules = transaction_rules::Model::find_all_by_user_id(&ctx.db, session.user_id).await?;
+    let mut tag_ids =
+        transaction_rule_tags::Model::find_tag_ids_by_rule_ids(&ctx.db, rules.iter().map(|rule| rule.id).collect())
+            .await?;
+
+    let rules = rules
+        .into_iter()
+        .map(|rule| {
+            let tag_ids = tag_ids.remove(&rule.id).unwrap_or_default();
+            TransactionRuleResponse::from((rule, tag_ids))
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(rules)))
+}
+
+/// Create a new transaction rule.
+///
+/// Rules are applied to every new transaction (created manually or imported).
+/// All given conditions have to match for the actions to be applied.
+#[utoipa::path(post,
+    path = "/api/v1/rules",
+    tag = "Rule",
+    request_body = TransactionRuleParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created a new transaction rule.", content_type="application/json", body = TransactionRuleResponse),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_rule(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<TransactionRuleParams>,
+) -> AppResult<(StatusCode, Json<TransactionRuleResponse>)> {
+    params.validate()?;
+    ensure_references(&ctx.db, session.user_id, &params).await?;
+
+    let txn = ctx.db.begin().await?;
+    let rule = transaction_rules::Model::create(&txn, &snowflake_generator, session.user_id, &params).await?;
+    txn.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TransactionRuleResponse::from((rule, params.tag_ids()))),
+    ))
+}
+
+/// Retrieve a transaction rule.
+#[utoipa::path(get,
+    path = "/api/v1/rules/{id}",
+    tag = "Rule",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction rule."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the transaction rule.", content_type="application/json", body = TransactionRuleResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_rule(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<TransactionRuleResponse>)> {
+    let rule = transaction_rules::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+    let tag_ids = rule.tag_ids(&ctx.db).await?;
+
+    Ok((StatusCode::OK, Json(TransactionRuleResponse::from((rule, tag_ids)))))
+}
+
+/// Update a transaction rule.
+///
+/// Existing transactions are not changed, use `POST /rules/reapply` for that.
+#[utoipa::path(put,
+    path = "/api/v1/rules/{id}",
+    tag = "Rule",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction rule."),
+    ),
+    request_body = TransactionRuleParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully updated the transaction rule.", content_type="application/json", body = TransactionRuleResponse),
+        EntityNotFoundResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_rule(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<TransactionRuleParams>,
+) -> AppResult<(StatusCode, Json<TransactionRuleResponse>)> {
+    params.validate()?;
+    ensure_references(&ctx.db, session.user_id, &params).await?;
+
+    let txn = ctx.db.begin().await?;
+    let rule = transaction_rules::Model::find_by_id_and_user_id(&txn, id.id, session.user_id)
+        .await?
+        .update_with_params(&txn, &params)
+        .await?;
+    txn.commit().await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TransactionRuleResponse::from((rule, params.tag_ids()))),
+    ))
+}
+
+/// Delete a transaction rule.
+#[utoipa::path(delete,
+    path = "/api/v1/rules/{id}",
+    tag = "Rule",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction rule."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the transaction rule."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_rule(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let rule = transaction_rules::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id).await?;
+    rule.delete(&ctx.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reapply all rules to existing transactions.
+///
+/// Runs in the background over all transactions the current User is allowed to edit.
+#[utoipa::path(post,
+    path = "/api/v1/rules/reapply",
+    tag = "Rule",
+    responses(
+        (status = StatusCode::ACCEPTED, description = "Successfully scheduled reapplying the rules."),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn reapply_rules(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<StatusCode> {
+    ReapplyRulesWorker::perform_later(
+        &ctx,
+        ReapplyRulesWorkerArgs {
+            user_id: session.user_id,
+        },
+    )
+    .await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Ensures that the category and all tags of the rule exist and are usable by the user.
+async fn ensure_references(db: &impl ConnectionTrait, user_id: i64, params: &TransactionRuleParams) -> AppResult<()> {
+    if let Some(category_id) = &params.category_id {
+        categories::Model::find_accessible_by_id(db, category_id.id, user_id).await?;
+    }
+
+    tags::Model::ensure_owned_by_user(db, params.tag_ids(), user_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/rules")
+        .add("/", get(list_rules).post(create_rule))
+        .add("/reapply", post(reapply_rules))
+        .add("/{id}", get(get_rule).put(update_rule).delete(delete_rule))
+}
@@ -1,20 +1,29 @@
 use crate::controllers::user::{MAX_PASSWORD_LENGTH, MIN_PASSWORD_LENGTH};
 use crate::error::app_error::{
-    AppError, AppResult, GeneralInternalServerErrorResponse, InvalidBearerTokenResponse, InvalidEmailOrPasswordResponse,
+    AccountDisabledResponse, AppError, AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse,
+    GeneralValidationErrorResponse, InvalidBearerTokenResponse, InvalidEmailOrPasswordResponse,
+    InvalidTwoFactorChallengeResponse, InvalidTwoFactorCodeResponse, TooManyLoginAttemptsResponse,
 };
 use crate::middlewares::authentication::Authenticated;
-use crate::models::_entities::sessions;
+use crate::middlewares::client_ip::ClientIp;
+use crate::models::_entities::{sessions, two_factor_challenges};
 use crate::models::users;
+use crate::services::login_protection::LoginProtectionService;
 use crate::services::secret_generator::SecretGenerator;
 use crate::services::snowflake_generator::SnowflakeGenerator;
-use crate::views::session::SessionResponse;
-use axum::extract::State;
+use crate::types::pagination::{Page, Pagination};
+use crate::types::snowflake::Snowflake;
+use crate::views::session::{SessionInfoResponse, SessionResponse};
+use crate::views::two_factor::TwoFactorChallengeResponse;
+use axum::extract::{Path, State};
 use axum::http::StatusCode;
-use axum::routing::{get, post};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post};
 use axum::{debug_handler, Extension, Json};
 use loco_rs::app::AppContext;
 use loco_rs::controller::Routes;
-use serde::Deserialize;
+use sea_orm::{IntoActiveModel, ModelTrait};
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
@@ -36,16 +45,43 @@ pub struct CreateSessionParams {
     pub user_agent: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct TwoFactorSessionParams {
+    /// The challenge token returned by the login.
+    #[validate(length(min = 1))]
+    pub challenge_token: String,
+    /// A TOTP code of the authenticator app or one of the recovery codes.
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct UpdateSessionParams {
+    /// The new name of the Session. `null` removes the name.
+    #[validate(length(min = "MIN_SESSION_NAME", max = "MAX_SESSION_NAME"))]
+    pub name: Option<String>,
+}
+
 /// Login/Create a new Session.
 ///
 /// This endpoint is used to create a new Session for a User.
 /// Returns a new Session with a Bearer Token that can be used to authenticate the User.
+/// The token is only returned here, it can not be retrieved again.
+///
+/// If the User has two-factor authentication enabled, a challenge token is returned instead,
+/// which has to be exchanged together with a TOTP code at `/api/v1/sessions/two-factor`.
+///
+/// Failed logins are counted per account and per IP address. After a few of them,
+/// further attempts are delayed with an increasing backoff until the account gets locked temporarily.
 #[utoipa::path(post,
     path = "/api/v1/sessions",
     tag = "Session",
     responses(
         (status = StatusCode::CREATED, description = "Successfully created a new Session.", content_type="application/json", body = SessionResponse),
+        (status = StatusCode::ACCEPTED, description = "The password was correct, a TOTP code is required.", content_type="application/json", body = TwoFactorChallengeResponse),
         InvalidEmailOrPasswordResponse,
+        AccountDisabledResponse,
+        TooManyLoginAttemptsResponse,
         GeneralInternalServerErrorResponse,
     ),
 )]
@@ -54,13 +90,21 @@ async fn create(
     State(ctx): State<AppContext>,
     Extension(snowflake_generator): Extension<SnowflakeGenerator>,
     Extension(secret_generator): Extension<SecretGenerator>,
+    Extension(login_protection): Extension<LoginProtectionService>,
+    ClientIp(ip): ClientIp,
     Json(params): Json<CreateSessionParams>,
-) -> AppResult<(StatusCode, Json<SessionResponse>)> {
+) -> AppResult<Response> {
     params.validate()?;
+    let attempt = login_protection.check(&params.email, ip).await?;
     let user = users::Model::find_by_email(&ctx.db, &params.email).await?;
 
     let user = match user {
-        None => return Err(AppError::InvalidEmailOrPassword())?,
+        None => {
+            login_protection
+                .record_failure(&attempt, &params.email, ip, None)
+                .await?;
+            return Err(AppError::InvalidEmailOrPassword())?;
+        }
         Some(user) => user,
     };
 
@@ -69,13 +113,126 @@ async fn create(
     }
 
     if !user.verify_password(&params.password) {
+        login_protection
+            .record_failure(&attempt, &params.email, ip, Some(&user))
+            .await?;
         return Err(AppError::InvalidEmailOrPassword())?;
     }
 
-    let session =
-        sessions::Model::create_session(&ctx.db, &snowflake_generator, &secret_generator, &user, &params).await?;
+    if user.is_disabled() {
+        return Err(AppError::AccountDisabled())?;
+    }
+
+    if user.is_two_factor_enabled() {
+        let (challenge, challenge_token) = two_factor_challenges::Model::create(
+            &ctx.db,
+            &snowflake_generator,
+            &secret_generator,
+            user.id,
+            params.name,
+            params.user_agent,
+        )
+        .await?;
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(TwoFactorChallengeResponse {
+                challenge_token,
+                expires_at: challenge.expires_at,
+            }),
+        )
+            .into_response());
+    }
+
+    login_protection.reset(&attempt, &params.email).await?;
+    let (session, api_key) = sessions::Model::create_session(
+        &ctx.db,
+        &snowflake_generator,
+        &secret_generator,
+        &user,
+        params.name,
+        params.user_agent,
+    )
+    .await?;
 
-    Ok((StatusCode::CREATED, Json(SessionResponse::from((session, user)))))
+    Ok((
+        StatusCode::CREATED,
+        Json(SessionResponse {
+            api_key: Some(api_key),
+            ..SessionResponse::from((session, user))
+        }),
+    )
+        .into_response())
+}
+
+/// Complete a login with two-factor authentication.
+///
+/// Exchanges the challenge token of the login together with a TOTP or recovery code for a new Session.
+/// A wrong code invalidates the challenge, the login has to be started again.
+#[utoipa::path(post,
+    path = "/api/v1/sessions/two-factor",
+    tag = "Session",
+    request_body = TwoFactorSessionParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created a new Session.", content_type="application/json", body = SessionResponse),
+        InvalidTwoFactorChallengeResponse,
+        InvalidTwoFactorCodeResponse,
+        AccountDisabledResponse,
+        TooManyLoginAttemptsResponse,
+        GeneralValidationErrorResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+)]
+#[debug_handler]
+async fn create_with_two_factor(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Extension(login_protection): Extension<LoginProtectionService>,
+    ClientIp(ip): ClientIp,
+    Json(params): Json<TwoFactorSessionParams>,
+) -> AppResult<(StatusCode, Json<SessionResponse>)> {
+    params.validate()?;
+
+    let challenge =
+        two_factor_challenges::Model::find_valid_by_token(&ctx.db, &params.challenge_token, chrono::Utc::now()).await?;
+    // Every challenge allows a single attempt, so codes can not be guessed without the password.
+    challenge.clone().delete(&ctx.db).await?;
+
+    let user = users::Model::find_by_id(&ctx.db, challenge.user_id).await?;
+    if !user.is_two_factor_enabled() {
+        return Err(AppError::InvalidTwoFactorChallenge());
+    }
+    if user.is_disabled() {
+        return Err(AppError::AccountDisabled());
+    }
+    let attempt = login_protection.check(&user.email, ip).await?;
+    if !user.verify_two_factor_code(&ctx.db, &params.code).await? {
+        login_protection
+            .record_failure(&attempt, &user.email, ip, Some(&user))
+            .await?;
+        return Err(AppError::InvalidTwoFactorCode());
+    }
+
+    login_protection.reset(&attempt, &user.email).await?;
+
+    let (session, api_key) = sessions::Model::create_session(
+        &ctx.db,
+        &snowflake_generator,
+        &secret_generator,
+        &user,
+        challenge.name,
+        challenge.user_agent,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SessionResponse {
+            api_key: Some(api_key),
+            ..SessionResponse::from((session, user))
+        }),
+    ))
 }
 
 /// Retrieve the current Session.
@@ -101,9 +258,157 @@ async fn current(
     Ok((StatusCode::OK, Json(SessionResponse::from((session, user)))))
 }
 
+/// List the Sessions of the current User, the most recently created first.
+#[utoipa::path(get,
+    path = "/api/v1/sessions",
+    tag = "Session",
+    params(Pagination),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the Sessions.", content_type="application/json", body = Page<SessionInfoResponse>),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    pagination: Pagination,
+) -> AppResult<(StatusCode, Json<Page<SessionInfoResponse>>)> {
+    let sessions = sessions::Model::find_page_by_user_id(&ctx.db, session.user_id, &pagination).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(sessions.map(|other| SessionInfoResponse::new(other, session.id))),
+    ))
+}
+
+/// Rename a Session of the current User.
+#[utoipa::path(patch,
+    path = "/api/v1/sessions/{id}",
+    tag = "Session",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the Session."),
+    ),
+    request_body = UpdateSessionParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully renamed the Session.", content_type="application/json", body = SessionInfoResponse),
+        EntityNotFoundResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<UpdateSessionParams>,
+) -> AppResult<(StatusCode, Json<SessionInfoResponse>)> {
+    params.validate()?;
+
+    let renamed = sessions::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id)
+        .await?
+        .into_active_model()
+        .rename(&ctx.db, params.name)
+        .await?;
+
+    Ok((StatusCode::OK, Json(SessionInfoResponse::new(renamed, session.id))))
+}
+
+/// Revoke a Session of the current User.
+#[utoipa::path(delete,
+    path = "/api/v1/sessions/{id}",
+    tag = "Session",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the Session."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully revoked the Session."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn revoke(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    sessions::Model::find_by_id_and_user_id(&ctx.db, id.id, session.user_id)
+        .await?
+        .delete(&ctx.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Logout/Revoke the current Session.
+#[utoipa::path(delete,
+    path = "/api/v1/sessions/current",
+    tag = "Session",
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully revoked the current Session."),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn logout(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<StatusCode> {
+    session.delete(&ctx.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Revoke all Sessions of the current User except the current one.
+///
+/// Logs the User out everywhere else.
+#[utoipa::path(delete,
+    path = "/api/v1/sessions/others",
+    tag = "Session",
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully revoked all other Sessions."),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn revoke_others(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<StatusCode> {
+    sessions::Model::revoke_all_by_user_id(&ctx.db, session.user_id, Some(session.id)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub fn routes() -> Routes {
     Routes::new()
         .prefix("/sessions")
-        .add("/", post(create))
-        .add("/current", get(current))
+        .add("/", get(list).post(create))
+        .add("/two-factor", post(create_with_two_factor))
+        .add("/current", get(current).delete(logout))
+        .add("/others", delete(revoke_others))
+        .add("/{id}", patch(update).delete(revoke))
 }
@@ -0,0 +1,894 @@
+use crate::error::app_error::{
+    AppError, AppResult, EntityNotFoundResponse, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse, InvalidCursorResponse, InvalidExchangeRateResponse, InvalidSplitAmountsResponse,
+    InvalidTransferResponse, MissingPermissionsResponse, MissingScopeResponse, TransactionReconciledResponse,
+};
+use crate::middlewares::authentication::{Authenticated, Principal};
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::{
+    bank_accounts, categories, category_classifiers, currencies, possible_duplicates, taggings, tags,
+    transaction_splits, transactions,
+};
+use crate::models::categories::subtree_ids;
+use crate::models::category_classifiers::suggest;
+use crate::models::personal_access_tokens::scopes;
+use crate::models::transaction_rules::RuleSet;
+use crate::models::transaction_splits::NewTransactionSplit;
+use crate::models::transactions::{CounterpartyTransaction, NewTransaction, TransactionSearch, TransferTransaction};
+use crate::models::user_permissions::Permission;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{Page, Pagination, SortField};
+use crate::types::snowflake::Snowflake;
+use crate::types::snowflake_list::SnowflakeList;
+use crate::utils::exchange_rate::convert_amount;
+use crate::utils::iban::normalize_iban;
+use crate::validation::transaction::{
+    validate_create_transfer, validate_non_zero_amount, validate_split_transaction, validate_transaction_search,
+};
+use crate::views::transaction::{
+    CategorySuggestionResponse, PossibleDuplicateResponse, TransactionResponse, TransactionSplitResponse,
+};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{debug_handler, Extension, Json};
+use chrono::{DateTime, FixedOffset};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityName, IntoActiveModel, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const MIN_NAME_LENGTH: u64 = 1;
+pub const MAX_NAME_LENGTH: u64 = 255;
+pub const MAX_TEXT_LENGTH: u64 = 4096;
+pub const MAX_SPLITS: u64 = 100;
+pub const MAX_SEARCH_TEXT_LENGTH: u64 = 255;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct CreateTransactionParams {
+    /// The bank account the transaction belongs to.
+    pub bank_account_id: Snowflake,
+    /// The amount in the smallest unit of the currency.
+    /// Positive amounts are incoming, negative amounts are outgoing.
+    #[validate(custom(function = "validate_non_zero_amount"))]
+    pub amount: i64,
+    #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
+    pub name: String,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub purpose: Option<String>,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub note: Option<String>,
+    pub booking_date: Option<DateTime<FixedOffset>>,
+    pub category_id: Option<Snowflake>,
+    #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
+    pub counterparty_name: Option<String>,
+    #[validate(length(min = 5, max = 34))]
+    pub counterparty_iban: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+#[validate(schema(function = "validate_transaction_search"))]
+pub struct TransactionSearchQuery {
+    /// Free text that is searched in the name, purpose, note and the names of both parties.
+    /// Supports `"exact phrases"`, `-excluded` words and `or`.
+    #[validate(length(min = 1, max = "MAX_SEARCH_TEXT_LENGTH"))]
+    pub q: Option<String>,
+    /// The earliest booking date (inclusive).
+    pub from: Option<DateTime<FixedOffset>>,
+    /// The latest booking date (inclusive).
+    pub to: Option<DateTime<FixedOffset>>,
+    /// The minimum (positive) amount in the smallest unit of the currency.
+    #[validate(range(min = 0))]
+    pub min_amount: Option<i64>,
+    /// The maximum (positive) amount in the smallest unit of the currency.
+    #[validate(range(min = 0))]
+    pub max_amount: Option<i64>,
+    pub r#type: Option<TransactionType>,
+    /// Matches the category and all of its children.
+    pub category_id: Option<Snowflake>,
+    /// Matches transactions with at least one of the tags, also on one of their split lines.
+    pub tag_ids: Option<SnowflakeList>,
+    /// Matches transactions from or to at least one of the bank accounts.
+    pub bank_account_ids: Option<SnowflakeList>,
+    pub has_attachment: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSort {
+    #[default]
+    Id,
+    Name,
+    Amount,
+}
+
+impl SortField<transactions::Entity> for TransactionSort {
+    fn column(&self) -> Option<transactions::Column> {
+        match self {
+            Self::Id => None,
+            Self::Name => Some(transactions::Column::Name),
+            Self::Amount => Some(transactions::Column::Amount),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct UpdateTransactionParams {
+    #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
+    pub name: String,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub purpose: Option<String>,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub note: Option<String>,
+    pub booking_date: Option<DateTime<FixedOffset>>,
+    pub category_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_create_transfer"))]
+pub struct CreateTransferParams {
+    pub source_bank_account_id: Snowflake,
+    pub destination_bank_account_id: Snowflake,
+    /// The (positive) amount in the smallest unit of the currency of the source bank account.
+    #[validate(range(min = 1))]
+    pub amount: i64,
+    /// The price of one unit of the source currency in units of the destination currency (e.g. `1.08` for EUR to USD).
+    /// Either this or `destination_amount` is required if both bank accounts use different currencies.
+    #[validate(range(exclusive_min = 0.0))]
+    pub exchange_rate: Option<f64>,
+    /// The (positive) amount in the smallest unit of the currency of the destination bank account.
+    #[validate(range(min = 1))]
+    pub destination_amount: Option<i64>,
+    #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
+    pub name: String,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub purpose: Option<String>,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub note: Option<String>,
+    pub booking_date: Option<DateTime<FixedOffset>>,
+    pub category_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct TransactionSplitParams {
+    /// The (positive) amount in the smallest unit of the currency.
+    #[validate(range(min = 1))]
+    pub amount: i64,
+    pub category_id: Option<Snowflake>,
+    #[validate(length(max = "MAX_TEXT_LENGTH"))]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tag_ids: Vec<Snowflake>,
+}
+
+impl TransactionSplitParams {
+    pub fn tag_ids(&self) -> Vec<i64> {
+        let mut tag_ids: Vec<i64> = self.tag_ids.iter().map(|tag_id| tag_id.id).collect();
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+
+        tag_ids
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+#[validate(schema(function = "validate_split_transaction"))]
+pub struct SplitTransactionParams {
+    /// The split lines, their amounts have to sum up to the absolute amount of the transaction.
+    /// An empty list removes the split.
+    #[validate(length(max = "MAX_SPLITS"), nested)]
+    pub splits: Vec<TransactionSplitParams>,
+}
+
+/// Create a new transaction.
+///
+/// The rules of the current User are applied to the transaction.
+/// Afterward, it is compared with the existing transactions of the bank account.
+/// Likely duplicates are queued for review (see `GET /transactions/duplicates`).
+/// If it is the opposite leg of a transfer from or to another bank account of the current User,
+/// both legs are paired into a single transfer.
+#[utoipa::path(post,
+    path = "/api/v1/transactions",
+    tag = "Transaction",
+    request_body = CreateTransactionParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created the transaction.", content_type="application/json", body = TransactionResponse),
+        GeneralValidationErrorResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_transaction(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Json(params): Json<CreateTransactionParams>,
+) -> AppResult<(StatusCode, Json<TransactionResponse>)> {
+    params.validate()?;
+
+    let bank_account = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        params.bank_account_id.id,
+        Permission::Read | Permission::Write,
+    )
+    .await?;
+    let category_id = match params.category_id {
+        None => None,
+        Some(category_id) => Some(
+            categories::Model::find_accessible_by_id(&ctx.db, category_id.id, principal.user_id)
+                .await?
+                .id,
+        ),
+    };
+
+    let txn = ctx.db.begin().await?;
+    let mut new_transaction = NewTransaction::with_counterparty(
+        &txn,
+        &snowflake_generator,
+        &bank_account,
+        CounterpartyTransaction {
+            amount: params.amount,
+            name: params.name,
+            purpose: params.purpose,
+            note: params.note,
+            booking_date: params.booking_date,
+            category_id,
+            counterparty_name: params.counterparty_name,
+            counterparty_iban: params.counterparty_iban.as_deref().map(normalize_iban),
+        },
+    )
+    .await?;
+    RuleSet::load(&txn, principal.user_id)
+        .await?
+        .apply(&mut new_transaction);
+    let transaction = transactions::Model::create(&txn, &snowflake_generator, new_transaction)
+        .await?
+        .pair_transfer(&txn, principal.user_id)
+        .await?;
+    txn.commit().await?;
+
+    if transaction.category_id.is_some() {
+        category_classifiers::Model::record_categorizations(&ctx, &snowflake_generator, principal.user_id, 1).await?;
+    }
+
+    Ok((StatusCode::CREATED, Json(TransactionResponse::from(transaction))))
+}
+
+/// Create a transfer between two bank accounts of the current User.
+///
+/// The current User has to be allowed to write to both bank accounts.
+/// Transfers between bank accounts with different currencies require an exchange rate or the destination amount.
+/// The rules of the current User are applied to the transfer.
+#[utoipa::path(post,
+    path = "/api/v1/transactions/transfers",
+    tag = "Transaction",
+    request_body = CreateTransferParams,
+    responses(
+        (status = StatusCode::CREATED, description = "Successfully created the transfer.", content_type="application/json", body = TransactionResponse),
+        GeneralValidationErrorResponse,
+        InvalidTransferResponse,
+        InvalidExchangeRateResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn create_transfer(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Json(params): Json<CreateTransferParams>,
+) -> AppResult<(StatusCode, Json<TransactionResponse>)> {
+    params.validate()?;
+
+    let required = Permission::Read | Permission::Write;
+    let source = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        params.source_bank_account_id.id,
+        required,
+    )
+    .await?;
+    let destination = bank_accounts::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        params.destination_bank_account_id.id,
+        required,
+    )
+    .await?;
+    let category_id = match params.category_id {
+        None => None,
+        Some(category_id) => Some(
+            categories::Model::find_accessible_by_id(&ctx.db, category_id.id, principal.user_id)
+                .await?
+                .id,
+        ),
+    };
+    let destination_amount = match (params.destination_amount, params.exchange_rate) {
+        (None, Some(rate)) if source.currency_id != destination.currency_id => {
+            let source_currency = currencies::Model::find_by_id(&ctx.db, source.currency_id).await?;
+            let destination_currency = currencies::Model::find_by_id(&ctx.db, destination.currency_id).await?;
+            let amount = convert_amount(
+                params.amount,
+                rate,
+                source_currency.decimal_places,
+                destination_currency.decimal_places,
+            );
+
+            Some(
+                amount
+                    .filter(|amount| *amount > 0)
+                    .ok_or_else(AppError::InvalidExchangeRate)?,
+            )
+        }
+        (destination_amount, _) => destination_amount,
+    };
+
+    let txn = ctx.db.begin().await?;
+    let mut new_transaction = NewTransaction::transfer(
+        &txn,
+        &snowflake_generator,
+        &source,
+        &destination,
+        TransferTransaction {
+            amount: params.amount,
+            destination_amount,
+            name: params.name,
+            purpose: params.purpose,
+            note: params.note,
+            booking_date: params.booking_date,
+            category_id,
+        },
+    )
+    .await?;
+    RuleSet::load(&txn, principal.user_id)
+        .await?
+        .apply(&mut new_transaction);
+    let transaction = transactions::Model::create(&txn, &snowflake_generator, new_transaction).await?;
+    txn.commit().await?;
+
+    if transaction.category_id.is_some() {
+        category_classifiers::Model::record_categorizations(&ctx, &snowflake_generator, principal.user_id, 1).await?;
+    }
+
+    Ok((StatusCode::CREATED, Json(TransactionResponse::from(transaction))))
+}
+
+/// Search the transactions of the current User.
+///
+/// All given filters have to match. By default, the newest transactions come first.
+#[utoipa::path(get,
+    path = "/api/v1/transactions/search",
+    tag = "Transaction",
+    params(TransactionSearchQuery, Pagination<TransactionSort>),
+    responses(
+        (status = StatusCode::OK, description = "Successfully searched the transactions.", content_type="application/json", body = Page<TransactionResponse>),
+        GeneralValidationErrorResponse,
+        InvalidCursorResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn search_transactions(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_READ }>,
+    Query(params): Query<TransactionSearchQuery>,
+    pagination: Pagination<TransactionSort>,
+) -> AppResult<(StatusCode, Json<Page<TransactionResponse>>)> {
+    params.validate()?;
+
+    let category_ids = match &params.category_id {
+        None => None,
+        Some(category_id) => {
+            let category = categories::Model::find_accessible_by_id(&ctx.db, category_id.id, principal.user_id).await?;
+            let categories = categories::Model::find_all_accessible(&ctx.db, principal.user_id).await?;
+            Some(subtree_ids(&categories, category.id))
+        }
+    };
+    let search = TransactionSearch {
+        text: params.q,
+        from: params.from,
+        to: params.to,
+        min_amount: params.min_amount,
+        max_amount: params.max_amount,
+        r#type: params.r#type,
+        category_ids,
+        tag_ids: params.tag_ids.map(|tag_ids| tag_ids.ids()).unwrap_or_default(),
+        bank_account_ids: params
+            .bank_account_ids
+            .map(|bank_account_ids| bank_account_ids.ids())
+            .unwrap_or_default(),
+        has_attachment: params.has_attachment,
+    };
+
+    let page = transactions::Model::search(&ctx.db, principal.user_id, &search, &pagination).await?;
+
+    Ok((StatusCode::OK, Json(page.map(TransactionResponse::from))))
+}
+
+/// Retrieve a transaction.
+#[utoipa::path(get,
+    path = "/api/v1/transactions/{id}",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the transaction.", content_type="application/json", body = TransactionResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_transaction(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_READ }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<TransactionResponse>)> {
+    let transaction =
+        transactions::Model::find_by_id_with_permissions(&ctx.db, principal.user_id, id.id, Permission::Read.into())
+            .await?;
+
+    Ok((StatusCode::OK, Json(TransactionResponse::from(transaction))))
+}
+
+/// Suggest categories for a transaction.
+///
+/// The suggestions are learned from the categorized transactions of the current User.
+/// Returns up to three categories, the most likely one first.
+#[utoipa::path(get,
+    path = "/api/v1/transactions/{id}/category-suggestions",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully suggested categories.", content_type="application/json", body = Vec<CategorySuggestionResponse>),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn suggest_categories(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_READ }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<Vec<CategorySuggestionResponse>>)> {
+    let transaction =
+        transactions::Model::find_by_id_with_permissions(&ctx.db, principal.user_id, id.id, Permission::Read.into())
+            .await?;
+    let classifier = category_classifiers::Model::load_classifier(&ctx.db, principal.user_id).await?;
+
+    let suggestions = suggest(&classifier, &transaction)
+        .into_iter()
+        .map(CategorySuggestionResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(suggestions)))
+}
+
+/// List the split lines of a transaction.
+///
+/// Returns an empty list if the transaction is not split.
+#[utoipa::path(get,
+    path = "/api/v1/transactions/{id}/splits",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the split lines.", content_type="application/json", body = Vec<TransactionSplitResponse>),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn get_splits(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_READ }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<Vec<TransactionSplitResponse>>)> {
+    let transaction =
+        transactions::Model::find_by_id_with_permissions(&ctx.db, principal.user_id, id.id, Permission::Read.into())
+            .await?;
+    let splits = transaction_splits::Model::find_by_transaction_id(&ctx.db, transaction.id).await?;
+
+    Ok((StatusCode::OK, Json(split_responses(&ctx.db, splits).await?)))
+}
+
+/// Split a transaction across multiple categories.
+///
+/// Replaces all split lines of the transaction.
+/// Each line has its own amount, category and tags, reports count the lines instead of the transaction.
+#[utoipa::path(put,
+    path = "/api/v1/transactions/{id}/splits",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    request_body = SplitTransactionParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully split the transaction.", content_type="application/json", body = Vec<TransactionSplitResponse>),
+        GeneralValidationErrorResponse,
+        InvalidSplitAmountsResponse,
+        TransactionReconciledResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_splits(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<SplitTransactionParams>,
+) -> AppResult<(StatusCode, Json<Vec<TransactionSplitResponse>>)> {
+    params.validate()?;
+
+    let transaction = transactions::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        id.id,
+        Permission::Read | Permission::Write,
+    )
+    .await?;
+
+    let mut new_splits = Vec::with_capacity(params.splits.len());
+    for split in &params.splits {
+        if let Some(category_id) = &split.category_id {
+            categories::Model::find_accessible_by_id(&ctx.db, category_id.id, principal.user_id).await?;
+        }
+        let tag_ids = split.tag_ids();
+        tags::Model::ensure_owned_by_user(&ctx.db, tag_ids.clone(), principal.user_id).await?;
+
+        new_splits.push(NewTransactionSplit {
+            amount: split.amount,
+            category_id: split.category_id.as_ref().map(|category_id| category_id.id),
+            note: split.note.clone(),
+            tag_ids,
+        });
+    }
+
+    transaction.ensure_not_reconciled()?;
+
+    let txn = ctx.db.begin().await?;
+    let splits =
+        transaction_splits::Model::replace_for_transaction(&txn, &snowflake_generator, &transaction, new_splits)
+            .await?;
+    let responses = split_responses(&txn, splits).await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(responses)))
+}
+
+async fn split_responses(
+    db: &impl ConnectionTrait,
+    splits: Vec<transaction_splits::Model>,
+) -> AppResult<Vec<TransactionSplitResponse>> {
+    let mut tag_ids = taggings::Model::find_tag_ids_for_entities(
+        db,
+        transaction_splits::Entity.table_name(),
+        splits.iter().map(|split| split.id).collect(),
+    )
+    .await?;
+
+    Ok(splits
+        .into_iter()
+        .map(|split| {
+            let tag_ids = tag_ids.remove(&split.id).unwrap_or_default();
+            TransactionSplitResponse::from((split, tag_ids))
+        })
+        .collect())
+}
+
+/// Update a transaction.
+///
+/// The amount and the parties of a transaction can not be changed.
+/// Reconciled transactions are locked against edits.
+#[utoipa::path(put,
+    path = "/api/v1/transactions/{id}",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    request_body = UpdateTransactionParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully updated the transaction.", content_type="application/json", body = TransactionResponse),
+        GeneralValidationErrorResponse,
+        TransactionReconciledResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_transaction(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Path(id): Path<Snowflake>,
+    Json(params): Json<UpdateTransactionParams>,
+) -> AppResult<(StatusCode, Json<TransactionResponse>)> {
+    params.validate()?;
+
+    let transaction = transactions::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        id.id,
+        Permission::Read | Permission::Write,
+    )
+    .await?;
+    transaction
+        .ensure_permissions_on_all(&ctx.db, principal.user_id, Permission::Read | Permission::Write)
+        .await?;
+    transaction.ensure_not_reconciled()?;
+    let category_id = match params.category_id {
+        None => None,
+        Some(category_id) => Some(
+            categories::Model::find_accessible_by_id(&ctx.db, category_id.id, principal.user_id)
+                .await?
+                .id,
+        ),
+    };
+    let categorized = category_id.is_some() && category_id != transaction.category_id;
+    let previous_date = transaction.effective_date();
+
+    let mut active_model = transaction.into_active_model();
+    active_model.name = Set(params.name);
+    active_model.purpose = Set(params.purpose);
+    active_model.note = Set(params.note);
+    active_model.booking_date = Set(params.booking_date);
+    active_model.category_id = Set(category_id);
+    let transaction = active_model.update(&ctx.db).await?;
+
+    if transaction.effective_date() != previous_date {
+        transaction
+            .invalidate_balance_snapshots(&ctx.db, previous_date.min(transaction.effective_date()))
+            .await?;
+    }
+
+    if categorized {
+        category_classifiers::Model::record_categorizations(&ctx, &snowflake_generator, principal.user_id, 1).await?;
+    }
+
+    Ok((StatusCode::OK, Json(TransactionResponse::from(transaction))))
+}
+
+/// Delete a transaction.
+///
+/// The balances of the involved bank accounts are reverted.
+/// Reconciled transactions are locked and can not be deleted.
+#[utoipa::path(delete,
+    path = "/api/v1/transactions/{id}",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the transaction."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully deleted the transaction."),
+        TransactionReconciledResponse,
+        EntityNotFoundResponse,
+        MissingPermissionsResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_transaction(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    let transaction = transactions::Model::find_by_id_with_permissions(
+        &ctx.db,
+        principal.user_id,
+        id.id,
+        Permission::Read | Permission::Delete,
+    )
+    .await?;
+    transaction
+        .ensure_permissions_on_all(&ctx.db, principal.user_id, Permission::Read | Permission::Delete)
+        .await?;
+    transaction.ensure_not_reconciled()?;
+
+    let txn = ctx.db.begin().await?;
+    transaction.delete_with_balances(&txn).await?;
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List all possible duplicates that still have to be reviewed.
+///
+/// Sorted by score, the most likely duplicates come first.
+#[utoipa::path(get,
+    path = "/api/v1/transactions/duplicates",
+    tag = "Transaction",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved all possible duplicates.", content_type="application/json", body = Vec<PossibleDuplicateResponse>),
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn list_duplicates(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_READ }>,
+) -> AppResult<(StatusCode, Json<Vec<PossibleDuplicateResponse>>)> {
+    let duplicates = possible_duplicates::Model::find_open_by_user_id(&ctx.db, principal.user_id).await?;
+    let ids = duplicates
+        .iter()
+        .flat_map(|duplicate| [duplicate.transaction_id, duplicate.duplicate_id])
+        .collect();
+    let transactions = transactions::Model::find_by_ids(&ctx.db, ids).await?;
+
+    let mut responses = Vec::with_capacity(duplicates.len());
+    for duplicate in duplicates {
+        let transaction = transactions.get(&duplicate.transaction_id).cloned();
+        let other = transactions.get(&duplicate.duplicate_id).cloned();
+        if let (Some(transaction), Some(other)) = (transaction, other) {
+            responses.push(PossibleDuplicateResponse::from((duplicate, transaction, other)));
+        }
+    }
+
+    Ok((StatusCode::OK, Json(responses)))
+}
+
+/// Merge a possible duplicate.
+///
+/// The transaction with more information (tags, category, attachment, ...) is kept, the other one is deleted.
+/// The kept transaction takes over the tags and, if it has none, the category and attachment of the deleted one.
+/// A reconciled transaction is always kept and left unchanged.
+/// Returns the kept transaction.
+#[utoipa::path(post,
+    path = "/api/v1/transactions/duplicates/{id}/merge",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the possible duplicate."),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Successfully merged both transactions.", content_type="application/json", body = TransactionResponse),
+        TransactionReconciledResponse,
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn merge_duplicate(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<(StatusCode, Json<TransactionResponse>)> {
+    let txn = ctx.db.begin().await?;
+    let duplicate = possible_duplicates::Model::find_open_by_id_and_user_id(
+        &txn,
+        id.id,
+        principal.user_id,
+        Permission::Read | Permission::Write | Permission::Delete,
+    )
+    .await?;
+    let transaction = duplicate.merge(&txn).await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(TransactionResponse::from(transaction))))
+}
+
+/// Dismiss a possible duplicate.
+///
+/// Both transactions are kept and the pair is not suggested again.
+#[utoipa::path(post,
+    path = "/api/v1/transactions/duplicates/{id}/dismiss",
+    tag = "Transaction",
+    params(
+        ("id" = Snowflake, Path, description = "The id of the possible duplicate."),
+    ),
+    responses(
+        (status = StatusCode::NO_CONTENT, description = "Successfully dismissed the possible duplicate."),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        MissingScopeResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn dismiss_duplicate(
+    State(ctx): State<AppContext>,
+    Authenticated(principal): Authenticated<Principal, { scopes::TRANSACTIONS_WRITE }>,
+    Path(id): Path<Snowflake>,
+) -> AppResult<StatusCode> {
+    possible_duplicates::Model::find_open_by_id_and_user_id(
+        &ctx.db,
+        id.id,
+        principal.user_id,
+        Permission::Read | Permission::Write,
+    )
+    .await?
+    .dismiss(&ctx.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/transactions")
+        .add("/", post(create_transaction))
+        .add("/transfers", post(create_transfer))
+        .add("/search", get(search_transactions))
+        .add("/duplicates", get(list_duplicates))
+        .add("/duplicates/{id}/merge", post(merge_duplicate))
+        .add("/duplicates/{id}/dismiss", post(dismiss_duplicate))
+        .add(
+            "/{id}",
+            get(get_transaction).put(update_transaction).delete(delete_transaction),
+        )
+        .add("/{id}/category-suggestions", get(suggest_categories))
+        .add("/{id}/splits", get(get_splits).put(update_splits))
+}
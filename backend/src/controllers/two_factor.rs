@@ -0,0 +1,235 @@
+use crate::controllers::user::MAX_PASSWORD_LENGTH;
+use crate::error::app_error::{
+    AppError, AppResult, GeneralInternalServerErrorResponse, GeneralValidationErrorResponse,
+    InvalidBearerTokenResponse, InvalidPasswordResponse, InvalidTwoFactorCodeResponse, TwoFactorAlreadyEnabledResponse,
+    TwoFactorNotEnabledResponse, TwoFactorNotEnrolledResponse,
+};
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{recovery_codes, sessions};
+use crate::models::users;
+use crate::services::secret_generator::SecretGenerator;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::utils::totp;
+use crate::views::two_factor::{RecoveryCodesResponse, TwoFactorEnrollmentResponse};
+use crate::views::user::UserResponse;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{debug_handler, Extension, Json};
+use loco_rs::app::AppContext;
+use loco_rs::controller::Routes;
+use sea_orm::{IntoActiveModel, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct TwoFactorPasswordParams {
+    /// The current password of the User, to confirm the change.
+    #[validate(length(min = 1, max = "MAX_PASSWORD_LENGTH"))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct ConfirmTwoFactorParams {
+    /// The current TOTP code of the authenticator app.
+    #[validate(length(min = 1, max = 16))]
+    pub code: String,
+}
+
+/// Finds the current User and verifies the given password.
+async fn find_user_with_password(ctx: &AppContext, user_id: i64, password: &str) -> AppResult<users::Model> {
+    let user = users::Model::find_by_id(&ctx.db, user_id).await?;
+    if !user.verify_password(password) {
+        return Err(AppError::InvalidPassword());
+    }
+
+    Ok(user)
+}
+
+/// Start the enrollment of two-factor authentication for the current User.
+///
+/// Returns a new TOTP secret, which has to be added to an authenticator app.
+/// Two-factor authentication is enabled once a code of the secret is confirmed.
+/// Enrolling again replaces the secret of an unconfirmed enrollment.
+#[utoipa::path(post,
+    path = "/api/v1/users/me/two-factor",
+    tag = "Two-Factor Authentication",
+    request_body = TwoFactorPasswordParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully generated a new TOTP secret.", content_type="application/json", body = TwoFactorEnrollmentResponse),
+        InvalidPasswordResponse,
+        TwoFactorAlreadyEnabledResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn enroll(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<TwoFactorPasswordParams>,
+) -> AppResult<(StatusCode, Json<TwoFactorEnrollmentResponse>)> {
+    params.validate()?;
+
+    let user = find_user_with_password(&ctx, session.user_id, &params.password).await?;
+    if user.is_two_factor_enabled() {
+        return Err(AppError::TwoFactorAlreadyEnabled());
+    }
+
+    let secret = totp::generate_secret();
+    let totp = totp::build_totp(&secret, &user.email)?;
+    user.into_active_model()
+        .enroll_two_factor(&ctx.db, secret.clone())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(TwoFactorEnrollmentResponse {
+            secret,
+            otpauth_uri: totp.get_url(),
+            qr_code_png: totp::qr_code_png(&totp)?,
+        }),
+    ))
+}
+
+/// Confirm the enrollment with a TOTP code and enable two-factor authentication.
+///
+/// Returns the recovery codes, which can be used once each when the authenticator app is lost.
+/// They are only returned here.
+#[utoipa::path(post,
+    path = "/api/v1/users/me/two-factor/confirm",
+    tag = "Two-Factor Authentication",
+    request_body = ConfirmTwoFactorParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully enabled two-factor authentication.", content_type="application/json", body = RecoveryCodesResponse),
+        InvalidTwoFactorCodeResponse,
+        TwoFactorAlreadyEnabledResponse,
+        TwoFactorNotEnrolledResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn confirm(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<ConfirmTwoFactorParams>,
+) -> AppResult<(StatusCode, Json<RecoveryCodesResponse>)> {
+    params.validate()?;
+
+    let user = users::Model::find_by_id(&ctx.db, session.user_id).await?;
+    if user.is_two_factor_enabled() {
+        return Err(AppError::TwoFactorAlreadyEnabled());
+    }
+    if user.totp_secret.is_none() {
+        return Err(AppError::TwoFactorNotEnrolled());
+    }
+    if !user.verify_two_factor_code(&ctx.db, &params.code).await? {
+        return Err(AppError::InvalidTwoFactorCode());
+    }
+
+    let txn = ctx.db.begin().await?;
+    let user = user.into_active_model().enable_two_factor(&txn).await?;
+    let recovery_codes =
+        recovery_codes::Model::replace_for_user(&txn, &snowflake_generator, &secret_generator, user.id).await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(RecoveryCodesResponse { recovery_codes })))
+}
+
+/// Regenerate the recovery codes of the current User.
+///
+/// All previous recovery codes become invalid.
+#[utoipa::path(post,
+    path = "/api/v1/users/me/two-factor/recovery-codes",
+    tag = "Two-Factor Authentication",
+    request_body = TwoFactorPasswordParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully regenerated the recovery codes.", content_type="application/json", body = RecoveryCodesResponse),
+        InvalidPasswordResponse,
+        TwoFactorNotEnabledResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn regenerate_recovery_codes(
+    State(ctx): State<AppContext>,
+    Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(secret_generator): Extension<SecretGenerator>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<TwoFactorPasswordParams>,
+) -> AppResult<(StatusCode, Json<RecoveryCodesResponse>)> {
+    params.validate()?;
+
+    let user = find_user_with_password(&ctx, session.user_id, &params.password).await?;
+    if !user.is_two_factor_enabled() {
+        return Err(AppError::TwoFactorNotEnabled());
+    }
+
+    let txn = ctx.db.begin().await?;
+    let recovery_codes =
+        recovery_codes::Model::replace_for_user(&txn, &snowflake_generator, &secret_generator, user.id).await?;
+    txn.commit().await?;
+
+    Ok((StatusCode::OK, Json(RecoveryCodesResponse { recovery_codes })))
+}
+
+/// Disable two-factor authentication for the current User.
+///
+/// Removes the TOTP secret and all recovery codes.
+#[utoipa::path(delete,
+    path = "/api/v1/users/me/two-factor",
+    tag = "Two-Factor Authentication",
+    request_body = TwoFactorPasswordParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully disabled two-factor authentication.", content_type="application/json", body = UserResponse),
+        InvalidPasswordResponse,
+        TwoFactorNotEnabledResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn disable(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<TwoFactorPasswordParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate()?;
+
+    let user = find_user_with_password(&ctx, session.user_id, &params.password).await?;
+    if !user.is_two_factor_enabled() {
+        return Err(AppError::TwoFactorNotEnabled());
+    }
+    let user = user.into_active_model().disable_two_factor(&ctx.db).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/users/me/two-factor")
+        .add("/", post(enroll).delete(disable))
+        .add("/confirm", post(confirm))
+        .add("/recovery-codes", post(regenerate_recovery_codes))
+}
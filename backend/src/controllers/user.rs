@@ -1,21 +1,28 @@
 use crate::error::app_error::{AppError, AppResult};
 use crate::error::app_error::{
-    GeneralInternalServerErrorResponse, GeneralValidationErrorResponse, InvalidVerificationTokenResponse,
+    EmailAlreadyTakenResponse, EntityNotFoundResponse, GeneralInternalServerErrorResponse,
+    GeneralValidationErrorResponse, InvalidBearerTokenResponse, InvalidInviteCodeResponse, InvalidPasswordResponse,
+    InvalidVerificationTokenResponse, RegistrationDisabledResponse,
 };
+use crate::middlewares::authentication::Authenticated;
+use crate::models::_entities::{currencies, invite_codes, sessions};
 use crate::models::users;
 use crate::models::users::Model;
+use crate::services::custom_config::{CustomConfig, RegistrationMode};
 use crate::services::snowflake_generator::SnowflakeGenerator;
 use crate::services::user_verification::UserVerificationService;
+use crate::types::snowflake::Snowflake;
 use crate::utils::context::AdditionalAppContextMethods;
 use crate::validation::user::validate_email_uniqueness;
 use crate::views::user::UserResponse;
 use axum::extract::State;
 use axum::http::StatusCode;
-use axum::routing::post;
+use axum::routing::{get, post, put};
 use axum::{debug_handler, Extension, Form, Json};
 use loco_rs::app::AppContext;
 use loco_rs::prelude::Routes;
-use sea_orm::IntoActiveModel;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, IntoActiveModel, TransactionTrait};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::{Validate, ValidateArgs};
@@ -60,14 +67,60 @@ pub struct RegisterParams {
     pub password: String,
     #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
     pub name: String,
+    /// Required if the registration is invite-only.
+    #[validate(length(min = 1))]
+    pub invite_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PreferredCurrencyParams {
+    /// Reports are converted into this currency. `null` removes the preference.
+    pub currency_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct UpdateUserParams {
+    #[validate(length(min = "MIN_NAME_LENGTH", max = "MAX_NAME_LENGTH"))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct ChangePasswordParams {
+    #[validate(length(min = 1, max = "MAX_PASSWORD_LENGTH"))]
+    pub current_password: String,
+    #[validate(length(min = "MIN_PASSWORD_LENGTH", max = "MAX_PASSWORD_LENGTH"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+#[validate(context = AppContext)]
+pub struct ChangeEmailParams {
+    /// The new email address, which has to be verified before it replaces the current one.
+    #[validate(email)]
+    #[validate(custom(function = "validate_email_uniqueness", use_context))]
+    pub email: String,
+    /// The current password of the User, to confirm the change.
+    #[validate(length(min = 1, max = "MAX_PASSWORD_LENGTH"))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Validate)]
+pub struct DeleteAccountParams {
+    /// The current password of the User, to confirm the deletion.
+    #[validate(length(min = 1, max = "MAX_PASSWORD_LENGTH"))]
+    pub password: String,
 }
 
 /// Registers a new User
+///
+/// Depending on the configured registration mode, an invite code is required or registering is disabled.
 #[utoipa::path(post,
     path = "/api/v1/users/register",
     tag = "User",
     responses(
         (status = StatusCode::CREATED, description = "Successfully registered a new User.", content_type="application/json", body = UserResponse),
+        InvalidInviteCodeResponse,
+        RegistrationDisabledResponse,
         GeneralValidationErrorResponse,
         GeneralInternalServerErrorResponse,
     )
@@ -77,11 +130,24 @@ async fn register(
     State(ctx): State<AppContext>,
     Extension(user_verification_service): Extension<UserVerificationService>,
     Extension(snowflake_generator): Extension<SnowflakeGenerator>,
+    Extension(custom_config): Extension<CustomConfig>,
     Json(params): Json<RegisterParams>,
 ) -> Result<(StatusCode, Json<UserResponse>), AppError> {
+    if custom_config.registration.mode == RegistrationMode::Disabled {
+        return Err(AppError::RegistrationDisabled());
+    }
     params.validate_with_args(&ctx)?;
 
-    let res = Model::create_with_password(&ctx.db, &snowflake_generator, &params).await?;
+    // The invite code is only used up if the user is created.
+    let txn = ctx.db.begin().await?;
+    if custom_config.registration.mode == RegistrationMode::InviteOnly {
+        let invite_code = params.invite_code.as_deref().ok_or_else(AppError::InvalidInviteCode)?;
+        if !invite_codes::Model::redeem(&txn, invite_code, &params.email, chrono::Utc::now()).await? {
+            return Err(AppError::InvalidInviteCode());
+        }
+    }
+    let res = Model::create_with_password(&txn, &snowflake_generator, &params).await?;
+    txn.commit().await?;
     let active_model = res.into_active_model();
 
     let model = user_verification_service
@@ -95,12 +161,15 @@ async fn register(
 ///
 /// This endpoint is only used if an Email Server was configured.
 /// Otherwise, the User is automatically verified.
+///
+/// It also confirms a requested email change, with the new email address and the token sent to it.
 #[utoipa::path(post,
     path = "/api/v1/users/verify",
     tag = "User",
     responses(
         (status = StatusCode::OK, description = "Successfully verified a User.", content_type="application/json", body = UserResponse),
         InvalidVerificationTokenResponse,
+        EmailAlreadyTakenResponse,
         GeneralValidationErrorResponse,
         GeneralInternalServerErrorResponse,
     )
@@ -112,12 +181,21 @@ async fn verify(
 ) -> Result<(StatusCode, Json<UserResponse>), AppError> {
     params.validate()?;
 
-    let user = Model::find_by_verification_token(&ctx.db, &params.email, &params.token).await?;
+    if let Some(user) = Model::find_by_verification_token(&ctx.db, &params.email, &params.token).await? {
+        let user = user.into_active_model().verified(&ctx.db).await?;
+
+        return Ok((StatusCode::OK, Json(UserResponse::from(user))));
+    }
+
+    let user = Model::find_by_pending_email_verification_token(&ctx.db, &params.email, &params.token).await?;
     match user {
         None => Err(AppError::InvalidVerificationToken()),
         Some(user) => {
-            let active_model = user.into_active_model();
-            let user = active_model.verified(&ctx.db).await?;
+            // The email address may have been taken since the change was requested.
+            if !Model::is_email_unique(&ctx.db, &params.email).await? {
+                return Err(AppError::EmailAlreadyTaken());
+            }
+            let user = user.into_active_model().confirm_email_change(&ctx.db).await?;
 
             Ok((StatusCode::OK, Json(UserResponse::from(user))))
         }
@@ -185,6 +263,237 @@ async fn reset_password(
     Ok((StatusCode::OK, Json(UserResponse::from(user))))
 }
 
+/// Retrieve the current User.
+#[utoipa::path(get,
+    path = "/api/v1/users/me",
+    tag = "User",
+    responses(
+        (status = StatusCode::OK, description = "Successfully retrieved the current User.", content_type="application/json", body = UserResponse),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn me(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = users::Model::find_by_id(&ctx.db, session.user_id).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Update the profile of the current User.
+#[utoipa::path(patch,
+    path = "/api/v1/users/me",
+    tag = "User",
+    request_body = UpdateUserParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully updated the User.", content_type="application/json", body = UserResponse),
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_me(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<UpdateUserParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate()?;
+
+    let mut user = users::Model::find_by_id(&ctx.db, session.user_id)
+        .await?
+        .into_active_model();
+    user.name = Set(params.name);
+    let user = user.update(&ctx.db).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Change the password of the current User.
+///
+/// All other Sessions of the User are revoked.
+#[utoipa::path(put,
+    path = "/api/v1/users/me/password",
+    tag = "User",
+    request_body = ChangePasswordParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully changed the password.", content_type="application/json", body = UserResponse),
+        InvalidPasswordResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn change_password(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<ChangePasswordParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate()?;
+
+    let user = users::Model::find_by_id(&ctx.db, session.user_id).await?;
+    if !user.verify_password(&params.current_password) {
+        return Err(AppError::InvalidPassword());
+    }
+    let user = user
+        .into_active_model()
+        .change_password(&ctx.db, &params.new_password, session.id)
+        .await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Change the email address of the current User.
+///
+/// A verification is sent to the new email address, which replaces the current one once it is verified.
+/// Until then, the new email address is shown as `pending_email`.
+/// If no Email Server was configured, the email address is changed right away.
+#[utoipa::path(put,
+    path = "/api/v1/users/me/email",
+    tag = "User",
+    request_body = ChangeEmailParams,
+    responses(
+        (status = StatusCode::ACCEPTED, description = "Successfully requested the email change.", content_type="application/json", body = UserResponse),
+        InvalidPasswordResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn change_email(
+    State(ctx): State<AppContext>,
+    Extension(user_verification_service): Extension<UserVerificationService>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<ChangeEmailParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate_with_args(&ctx)?;
+
+    let user = users::Model::find_by_id(&ctx.db, session.user_id).await?;
+    if !user.verify_password(&params.password) {
+        return Err(AppError::InvalidPassword());
+    }
+    let user = user_verification_service
+        .send_email_change_verification_or_change_email(user.into_active_model(), params.email)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(UserResponse::from(user))))
+}
+
+/// Set the preferred currency of the current User.
+#[utoipa::path(put,
+    path = "/api/v1/users/me/preferred-currency",
+    tag = "User",
+    request_body = PreferredCurrencyParams,
+    responses(
+        (status = StatusCode::OK, description = "Successfully set the preferred currency.", content_type="application/json", body = UserResponse),
+        EntityNotFoundResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn update_preferred_currency(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<PreferredCurrencyParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    if let Some(currency_id) = &params.currency_id {
+        currencies::Model::find_accessible_by_id(&ctx.db, currency_id.id, session.user_id).await?;
+    }
+
+    let mut user = users::Model::find_by_id(&ctx.db, session.user_id)
+        .await?
+        .into_active_model();
+    user.preferred_currency_id = Set(params.currency_id.map(|currency_id| currency_id.id));
+    let user = user.update(&ctx.db).await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
+/// Schedule the deletion of the current User.
+///
+/// The account and all of its data are deleted after a grace period of 30 days.
+/// Bank accounts and budgets that are shared with other Users are kept for them.
+/// Until then, the deletion can be cancelled.
+#[utoipa::path(delete,
+    path = "/api/v1/users/me",
+    tag = "User",
+    request_body = DeleteAccountParams,
+    responses(
+        (status = StatusCode::ACCEPTED, description = "Successfully scheduled the deletion of the User.", content_type="application/json", body = UserResponse),
+        InvalidPasswordResponse,
+        GeneralValidationErrorResponse,
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn delete_account(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+    Json(params): Json<DeleteAccountParams>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    params.validate()?;
+
+    let user = users::Model::find_by_id(&ctx.db, session.user_id).await?;
+    if !user.verify_password(&params.password) {
+        return Err(AppError::InvalidPassword());
+    }
+    let user = user.into_active_model().schedule_deletion(&ctx.db).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(UserResponse::from(user))))
+}
+
+/// Cancel the scheduled deletion of the current User.
+#[utoipa::path(post,
+    path = "/api/v1/users/me/cancel-deletion",
+    tag = "User",
+    responses(
+        (status = StatusCode::OK, description = "Successfully cancelled the deletion of the User.", content_type="application/json", body = UserResponse),
+        InvalidBearerTokenResponse,
+        GeneralInternalServerErrorResponse,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+)]
+#[debug_handler]
+async fn cancel_account_deletion(
+    State(ctx): State<AppContext>,
+    Authenticated(session): Authenticated<sessions::Model>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = users::Model::find_by_id(&ctx.db, session.user_id)
+        .await?
+        .into_active_model()
+        .cancel_deletion(&ctx.db)
+        .await?;
+
+    Ok((StatusCode::OK, Json(UserResponse::from(user))))
+}
+
 pub fn routes() -> Routes {
     Routes::new()
         .prefix("/users")
@@ -192,4 +501,9 @@ pub fn routes() -> Routes {
         .add("/verify", post(verify))
         .add("/forgot", post(forgot_password))
         .add("/reset", post(reset_password))
+        .add("/me", get(me).patch(update_me).delete(delete_account))
+        .add("/me/password", put(change_password))
+        .add("/me/email", put(change_email))
+        .add("/me/cancel-deletion", post(cancel_account_deletion))
+        .add("/me/preferred-currency", put(update_preferred_currency))
 }
@@ -82,6 +82,17 @@ app_errors!(
     (StatusCode::BAD_REQUEST, ErrorCode::INVALID_HTTP_METHOD, InvalidHttpMethod, argument=String);
     (StatusCode::BAD_REQUEST, ErrorCode::INVALID_EMAIL_OR_PASSWORD, InvalidEmailOrPassword);
     (StatusCode::BAD_REQUEST, ErrorCode::EMAIL_NOT_VERIFIED, EmailNotVerified);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_IMPORT_FILE, InvalidImportFile, argument=String);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_SPLIT_AMOUNTS, InvalidSplitAmounts);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_TRANSFER, InvalidTransfer);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_EXCHANGE_RATE, InvalidExchangeRate);
+    (StatusCode::BAD_REQUEST, ErrorCode::RECONCILIATION_MISMATCH, ReconciliationMismatch);
+    (StatusCode::BAD_REQUEST, ErrorCode::OUTDATED_STATEMENT, OutdatedStatement);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_PASSWORD, InvalidPassword);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_TWO_FACTOR_CODE, InvalidTwoFactorCode);
+    (StatusCode::BAD_REQUEST, ErrorCode::EMAIL_ALREADY_TAKEN, EmailAlreadyTaken);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_INVITE_CODE, InvalidInviteCode);
+    (StatusCode::BAD_REQUEST, ErrorCode::INVALID_CURSOR, InvalidCursor);
 );
 
 #[derive(Debug, Clone, Default, Serialize)]
@@ -91,6 +102,15 @@ pub struct YamlFileErrorArgs(String, String);
 app_errors!(
     (StatusCode::UNAUTHORIZED, ErrorCode::UNAUTHORIZED, Unauthorized, argument=String);
     (StatusCode::NOT_FOUND, ErrorCode::NOT_FOUND, NotFound);
+    (StatusCode::FORBIDDEN, ErrorCode::MISSING_PERMISSIONS, MissingPermissions);
+    (StatusCode::CONFLICT, ErrorCode::TRANSACTION_RECONCILED, TransactionReconciled);
+    (StatusCode::CONFLICT, ErrorCode::EXPORT_NOT_FINISHED, ExportNotFinished);
+    (StatusCode::CONFLICT, ErrorCode::TWO_FACTOR_ALREADY_ENABLED, TwoFactorAlreadyEnabled);
+    (StatusCode::CONFLICT, ErrorCode::TWO_FACTOR_NOT_ENABLED, TwoFactorNotEnabled);
+    (StatusCode::CONFLICT, ErrorCode::TWO_FACTOR_NOT_ENROLLED, TwoFactorNotEnrolled);
+    (StatusCode::TOO_MANY_REQUESTS, ErrorCode::RATE_LIMIT_EXCEEDED, RateLimitExceeded, argument=RetryAfterArgs);
+    (StatusCode::CONFLICT, ErrorCode::OWN_ACCOUNT_NOT_ALLOWED, OwnAccountNotAllowed);
+    (StatusCode::FORBIDDEN, ErrorCode::REGISTRATION_DISABLED, RegistrationDisabled);
 );
 
 // Configuration error
@@ -130,8 +150,20 @@ app_errors!(
 app_errors!(
     (StatusCode::BAD_REQUEST, ErrorCode::AUTH_HEADER_MISSING, AuthHeaderMissing);
     (StatusCode::UNAUTHORIZED, ErrorCode::INVALID_BEARER_TOKEN, InvalidBearerToken);
+    (StatusCode::UNAUTHORIZED, ErrorCode::SESSION_EXPIRED, SessionExpired);
+    (StatusCode::UNAUTHORIZED, ErrorCode::ACCESS_TOKEN_EXPIRED, AccessTokenExpired);
+    (StatusCode::FORBIDDEN, ErrorCode::MISSING_SCOPE, MissingScope);
+    (StatusCode::UNAUTHORIZED, ErrorCode::INVALID_TWO_FACTOR_CHALLENGE, InvalidTwoFactorChallenge);
+    (StatusCode::TOO_MANY_REQUESTS, ErrorCode::TOO_MANY_LOGIN_ATTEMPTS, TooManyLoginAttempts, argument=RetryAfterArgs);
+    (StatusCode::FORBIDDEN, ErrorCode::ACCOUNT_DISABLED, AccountDisabled);
 );
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetryAfterArgs {
+    /// The number of seconds until the next attempt is allowed.
+    pub retry_after: u64,
+}
+
 impl From<LocoError> for AppError {
     fn from(value: LocoError) -> Self {
         match value {
@@ -57,12 +57,32 @@ error_codes!(
     (2010, INVALID_HTTP_METHOD, "A invalid http method was used.");
     (2011, INVALID_EMAIL_OR_PASSWORD, "Invalid E-Mail or Password given.");
     (2012, EMAIL_NOT_VERIFIED, "Email has not been verified yet.");
+    (2013, INVALID_IMPORT_FILE, "The given import file could not be parsed.");
+    (2014, INVALID_SPLIT_AMOUNTS, "The amounts of the splits do not sum up to the amount of the transaction.");
+    (2015, INVALID_TRANSFER, "Both parties of a transfer have to be different bank accounts.");
+    (2016, INVALID_EXCHANGE_RATE, "A transfer between bank accounts with different currencies requires a valid exchange rate.");
+    (2017, RECONCILIATION_MISMATCH, "The reconciled transactions do not match the statement balance.");
+    (2018, OUTDATED_STATEMENT, "The statement ends before the last reconciliation of the bank account.");
+    (2019, INVALID_PASSWORD, "The given password is wrong.");
+    (2020, INVALID_TWO_FACTOR_CODE, "The given two-factor authentication code is invalid.");
+    (2021, EMAIL_ALREADY_TAKEN, "The email address is already taken.");
+    (2022, INVALID_INVITE_CODE, "The invite code is invalid, has expired or has been used up.");
+    (2023, INVALID_CURSOR, "The cursor does not point to an existing item, please start from the first page.");
 );
 
 // User errors
 error_codes!(
     (3001, UNAUTHORIZED, "You are not authorized for this.");
     (3002, NOT_FOUND, "Requested resource could not be found.");
+    (3003, MISSING_PERMISSIONS, "You are missing the required permissions for this resource.");
+    (3004, TRANSACTION_RECONCILED, "The transaction was reconciled and can not be changed anymore.");
+    (3005, EXPORT_NOT_FINISHED, "The export has not been finished yet.");
+    (3006, TWO_FACTOR_ALREADY_ENABLED, "Two-factor authentication is already enabled.");
+    (3007, TWO_FACTOR_NOT_ENABLED, "Two-factor authentication is not enabled.");
+    (3008, TWO_FACTOR_NOT_ENROLLED, "Two-factor authentication has to be enrolled first.");
+    (3009, RATE_LIMIT_EXCEEDED, "Too many requests, please slow down.");
+    (3010, OWN_ACCOUNT_NOT_ALLOWED, "Admins can not revoke their own admin flag or disable their own account.");
+    (3011, REGISTRATION_DISABLED, "Registering new accounts is disabled.");
 );
 
 // Configuration error
@@ -102,6 +122,12 @@ error_codes!(
 error_codes!(
     (7002, AUTH_HEADER_MISSING, "Authorization header required but missing.");
     (7003, INVALID_BEARER_TOKEN, "Invalid bearer token.");
+    (7004, SESSION_EXPIRED, "The session has expired, please log in again.");
+    (7005, ACCESS_TOKEN_EXPIRED, "The personal access token has expired.");
+    (7006, MISSING_SCOPE, "The personal access token lacks the scope required for this request.");
+    (7007, INVALID_TWO_FACTOR_CHALLENGE, "The two-factor challenge is invalid or has expired, please log in again.");
+    (7008, TOO_MANY_LOGIN_ATTEMPTS, "Too many failed login attempts, please try again later.");
+    (7009, ACCOUNT_DISABLED, "The account has been disabled by an admin.");
 );
 
 // Misc Errors
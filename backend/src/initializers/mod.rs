@@ -1,3 +1,4 @@
 pub mod openapi;
 pub mod path_normalization;
+pub mod rate_limit;
 pub mod services;
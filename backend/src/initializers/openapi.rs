@@ -17,7 +17,17 @@ use utoipauto::utoipauto;
         (name = "OpenAPI", description = "Endpoints for OpenAPI documentation."),
         (name = "Metrics", description = "Endpoints for prometheus metrics."),
         (name = "Session", description = "Endpoints for session management."),
-        (name = "User", description = "Endpoints for user management.")
+        (name = "Two-Factor Authentication", description = "Endpoints for enrolling and managing TOTP two-factor authentication."),
+        (name = "Access Token", description = "Endpoints for personal access tokens of scripts and integrations."),
+        (name = "Import", description = "Endpoints for importing transactions from files."),
+        (name = "Transaction", description = "Endpoints for transactions and the review of possible duplicates."),
+        (name = "Rule", description = "Endpoints for rules that categorize, tag and rename transactions automatically."),
+        (name = "Reconciliation", description = "Endpoints for reconciling bank accounts with their statements."),
+        (name = "Exchange Rate", description = "Endpoints for the exchange rates used to convert reports."),
+        (name = "Report", description = "Endpoints for financial reports."),
+        (name = "Export", description = "Endpoints for exporting the data of the user."),
+        (name = "User", description = "Endpoints for user management."),
+        (name = "Admin", description = "Endpoints for admins to manage Users, every action is recorded in an audit log.")
     ),
     modifiers(&ApiKeyModifier)
 )]
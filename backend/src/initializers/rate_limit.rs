@@ -0,0 +1,33 @@
+use crate::middlewares::rate_limit::{rate_limit, RateLimiter};
+use crate::utils::context::AdditionalAppContextMethods;
+use async_trait::async_trait;
+use axum::middleware::from_fn_with_state;
+use axum::Router as AxumRouter;
+use loco_rs::prelude::{AppContext, Initializer};
+use std::sync::Arc;
+
+pub struct RateLimitInitializer;
+
+#[async_trait]
+impl Initializer for RateLimitInitializer {
+    fn name(&self) -> String {
+        "RateLimitInitializer".to_string()
+    }
+
+    async fn after_routes(&self, router: AxumRouter, ctx: &AppContext) -> loco_rs::Result<AxumRouter> {
+        let custom_config = ctx.get_custom_config().await?;
+        let config = custom_config.rate_limit.clone();
+        if !config.enabled {
+            return Ok(router);
+        }
+
+        let limiter = Arc::new(RateLimiter::new(
+            ctx.cache.clone(),
+            ctx.db.clone(),
+            config,
+            custom_config.proxy.trusted_proxies.clone(),
+        ));
+
+        Ok(router.layer(from_fn_with_state(limiter, rate_limit)))
+    }
+}
@@ -1,13 +1,18 @@
 // auth mailer
 #![allow(non_upper_case_globals)]
 
+use chrono::{DateTime, Utc};
 use loco_rs::prelude::*;
 use serde_json::json;
 
+use crate::models::_entities::invite_codes;
 use crate::models::users;
 
 static welcome: Dir<'_> = include_dir!("src/mailers/auth/welcome");
 static forgot: Dir<'_> = include_dir!("src/mailers/auth/forgot");
+static lockout: Dir<'_> = include_dir!("src/mailers/auth/lockout");
+static email_change: Dir<'_> = include_dir!("src/mailers/auth/email_change");
+static invite: Dir<'_> = include_dir!("src/mailers/auth/invite");
 // #[derive(Mailer)] // -- disabled for faster build speed. it works. but lets
 // move on for now.
 
@@ -62,4 +67,89 @@ impl AuthMailer {
 
         Ok(())
     }
+
+    /// Sending the verification of a requested email change to the new email address
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn send_email_change(ctx: &AppContext, user: &users::Model) -> Result<()> {
+        let Some(pending_email) = &user.pending_email else {
+            return Ok(());
+        };
+
+        Self::mail_template(
+            ctx,
+            &email_change,
+            mailer::Args {
+                to: pending_email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "verifyToken": user.email_verification_token,
+                  "domain": ctx.config.server.full_url()
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sending an invite code to the email address it is restricted to
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn send_invite(
+        ctx: &AppContext,
+        invite_code: &invite_codes::Model,
+        code: &str,
+        inviter: &users::Model,
+    ) -> Result<()> {
+        let Some(email) = &invite_code.email else {
+            return Ok(());
+        };
+
+        Self::mail_template(
+            ctx,
+            &invite,
+            mailer::Args {
+                to: email.to_string(),
+                locals: json!({
+                  "inviterName": inviter.name,
+                  "inviteCode": code,
+                  "expiresAt": invite_code.expires_at.map(|expires_at| expires_at.format("%Y-%m-%d %H:%M %:z").to_string()),
+                  "domain": ctx.config.server.full_url()
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sending the email that logging in is blocked after too many failed attempts
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn send_lockout(ctx: &AppContext, user: &users::Model, locked_until: DateTime<Utc>) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &lockout,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "lockedUntil": locked_until.format("%Y-%m-%d %H:%M UTC").to_string(),
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
 }
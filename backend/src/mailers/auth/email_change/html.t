@@ -0,0 +1,13 @@
+;<html>
+
+<body>
+  Hey {{name}},
+  Confirm that this is the new email address of your account by clicking the link below:
+  <a href="{{domain}}/verify#{{verifyToken}}">
+    Confirm Your Email Address
+  </a>
+  Your email address is not changed until it is confirmed.
+  <p>Best regards,<br>The financrr Team</p>
+</body>
+
+</html>
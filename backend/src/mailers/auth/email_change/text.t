@@ -0,0 +1,6 @@
+Hey {{name}},
+  confirm that this is the new email address of your account with the link below:
+
+  {{domain}}/verify#{{verifyToken}}
+
+  Your email address is not changed until it is confirmed.
@@ -0,0 +1,13 @@
+;<html>
+
+<body>
+  Hey,
+  {{inviterName}} invited you to financrr. Create your account by clicking the link below:
+  <a href="{{domain}}/register#{{inviteCode}}">
+    Create Your Account
+  </a>
+  {% if expiresAt %}The invite expires at {{expiresAt}}.{% endif %}
+  <p>Best regards,<br>The financrr Team</p>
+</body>
+
+</html>
@@ -0,0 +1 @@
+You have been invited to financrr
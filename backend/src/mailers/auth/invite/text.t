@@ -0,0 +1,6 @@
+Hey,
+  {{inviterName}} invited you to financrr. Create your account with the link below:
+
+  {{domain}}/register#{{inviteCode}}
+
+  {% if expiresAt %}The invite expires at {{expiresAt}}.{% endif %}
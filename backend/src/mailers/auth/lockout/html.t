@@ -0,0 +1,11 @@
+;<html>
+
+<body>
+  Hey {{name}},
+  There were too many failed login attempts for your account, so logging in is blocked until {{lockedUntil}}.
+  If this was not you, someone may be trying to guess your password.
+  Consider changing it and enabling two-factor authentication.
+  <p>Best regards,<br>The financrr Team</p>
+</body>
+
+</html>
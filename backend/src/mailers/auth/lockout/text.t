@@ -0,0 +1,5 @@
+Hey {{name}},
+  there were too many failed login attempts for your account, so logging in is blocked until {{lockedUntil}}.
+
+  If this was not you, someone may be trying to guess your password.
+  Consider changing it and enabling two-factor authentication.
@@ -1,8 +1,13 @@
 use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::{personal_access_tokens, sessions};
+use crate::models::personal_access_tokens::{Scope, ACCESS_TOKEN_PREFIX};
+use crate::models::users;
+use crate::workers::access_token_used::{AccessTokenUsedWorker, AccessTokenUsedWorkerArgs};
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use axum::http::HeaderMap;
-use loco_rs::prelude::AppContext;
+use enumflags2::BitFlags;
+use loco_rs::prelude::{AppContext, BackgroundWorker};
 use std::future::Future;
 
 const TOKEN_PREFIX: &str = "Bearer ";
@@ -10,23 +15,110 @@ const AUTH_HEADER: &str = "authorization";
 
 pub trait Authenticate: Sized {
     fn find_by_api_key(ctx: &AppContext, api_key: &str) -> impl Future<Output = AppResult<Self>> + Send;
+
+    /// Whether the credentials grant all of the required scopes.
+    /// Unrestricted credentials, like login sessions, grant every scope.
+    fn has_scopes(&self, _required: BitFlags<Scope>) -> bool {
+        true
+    }
 }
 
-pub struct Authenticated<T: Authenticate>(pub T);
+/// Extracts the authenticated credentials of a request.
+///
+/// `SCOPES` holds the bits of the scopes the handler requires (see `personal_access_tokens::scopes`),
+/// credentials that lack any of them are rejected with `MissingScope`.
+pub struct Authenticated<T: Authenticate, const SCOPES: u16 = 0>(pub T);
 
-impl<T: Authenticate> FromRequestParts<AppContext> for Authenticated<T> {
+impl<T: Authenticate, const SCOPES: u16> FromRequestParts<AppContext> for Authenticated<T, SCOPES> {
     type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, state: &AppContext) -> AppResult<Self> {
         let api_key = extract_api_key_from_header(&parts.headers)?;
 
         let inner = T::find_by_api_key(state, &api_key).await?;
+        if !inner.has_scopes(BitFlags::from_bits_truncate(SCOPES)) {
+            return Err(AppError::MissingScope());
+        }
 
         Ok(Self(inner))
     }
 }
 
-fn extract_api_key_from_header(headers: &HeaderMap) -> AppResult<String> {
+/// Extracts the user of a login session that has the `Admin` flag.
+///
+/// Users without the flag are rejected with `MissingPermissions`.
+pub struct Admin(pub users::Model);
+
+impl FromRequestParts<AppContext> for Admin {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppContext) -> AppResult<Self> {
+        let Authenticated(session) = Authenticated::<sessions::Model>::from_request_parts(parts, state).await?;
+
+        let user = users::Model::find_by_id(&state.db, session.user_id).await?;
+        if !user.is_admin() {
+            return Err(AppError::MissingPermissions());
+        }
+
+        Ok(Self(user))
+    }
+}
+
+/// Either a login session or a personal access token.
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    Session(sessions::Model),
+    AccessToken(personal_access_tokens::Model),
+}
+
+/// The user of a request, for endpoints that scripts may use with a personal access token.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub user_id: i64,
+    pub credentials: Credentials,
+}
+
+impl Authenticate for Principal {
+    async fn find_by_api_key(ctx: &AppContext, api_key: &str) -> AppResult<Self> {
+        if api_key.starts_with(ACCESS_TOKEN_PREFIX) {
+            if let Some(access_token) = personal_access_tokens::Model::find_by_token(&ctx.db, api_key).await? {
+                if access_token.is_expired(chrono::Utc::now()) {
+                    return Err(AppError::AccessTokenExpired());
+                }
+
+                AccessTokenUsedWorker::perform_later(
+                    ctx,
+                    AccessTokenUsedWorkerArgs {
+                        access_token_id: access_token.id,
+                    },
+                )
+                .await?;
+
+                return Ok(Self {
+                    user_id: access_token.user_id,
+                    credentials: Credentials::AccessToken(access_token),
+                });
+            }
+        }
+
+        // The api key of a session may start with the prefix by chance.
+        let session = sessions::Model::find_by_api_key(ctx, api_key).await?;
+
+        Ok(Self {
+            user_id: session.user_id,
+            credentials: Credentials::Session(session),
+        })
+    }
+
+    fn has_scopes(&self, required: BitFlags<Scope>) -> bool {
+        match &self.credentials {
+            Credentials::Session(_) => true,
+            Credentials::AccessToken(access_token) => access_token.get_scopes().contains(required),
+        }
+    }
+}
+
+pub(crate) fn extract_api_key_from_header(headers: &HeaderMap) -> AppResult<String> {
     Ok(headers
         .get(AUTH_HEADER)
         .ok_or_else(AppError::AuthHeaderMissing)?
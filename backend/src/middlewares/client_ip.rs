@@ -0,0 +1,148 @@
+use crate::error::app_error::AppError;
+use crate::utils::context::AdditionalAppContextMethods;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{Extensions, HeaderMap};
+use loco_rs::app::AppContext;
+use std::net::{IpAddr, SocketAddr};
+
+pub const X_FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+pub const FORWARDED_HEADER: &str = "forwarded";
+
+/// The IP address of the client.
+///
+/// It is `None` if the server does not know the peer, e.g. in tests.
+/// If the peer of the connection is one of the trusted proxies (see [`ProxyConfig`](crate::services::custom_config::ProxyConfig)),
+/// the address is taken from the `X-Forwarded-For` or `Forwarded` header instead.
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// Resolves the client address.
+    ///
+    /// The forwarded addresses are followed from the nearest hop as long as the current address is a trusted proxy,
+    /// so clients can not pretend to be someone else by sending the headers themselves.
+    pub fn resolve(headers: &HeaderMap, extensions: &Extensions, trusted_proxies: &[IpAddr]) -> Self {
+        let Some(mut ip) = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|connect_info| connect_info.0.ip())
+        else {
+            return Self(None);
+        };
+
+        let mut hops = forwarded_for(headers).into_iter().rev();
+        while trusted_proxies.contains(&ip) {
+            match hops.next().and_then(|hop| parse_hop(&hop)) {
+                Some(hop) => ip = hop,
+                None => break,
+            }
+        }
+
+        Self(Some(ip))
+    }
+}
+
+/// The forwarded addresses, the client first and the nearest proxy last.
+/// `X-Forwarded-For` is preferred, as it is the header most proxies set by default.
+fn forwarded_for(headers: &HeaderMap) -> Vec<String> {
+    let values = |name: &str| -> Vec<String> {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    };
+
+    let x_forwarded_for = values(X_FORWARDED_FOR_HEADER);
+    if !x_forwarded_for.is_empty() {
+        return x_forwarded_for;
+    }
+
+    // E.g. `for=192.0.2.60;proto=http, for="[2001:db8:cafe::17]:4711"`
+    values(FORWARDED_HEADER)
+        .iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                name.eq_ignore_ascii_case("for")
+                    .then(|| value.trim_matches('"').to_string())
+            })
+        })
+        .collect()
+}
+
+/// Parses a single hop, which may contain a port (`192.0.2.60:4711`, `[2001:db8::17]:4711`).
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    if let Ok(ip) = hop.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = hop.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+
+    hop.split_once(':')?.0.parse().ok()
+}
+
+impl FromRequestParts<AppContext> for ClientIp {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, ctx: &AppContext) -> Result<Self, Self::Rejection> {
+        let config = ctx.get_custom_config().await?;
+
+        Ok(Self::resolve(
+            &parts.headers,
+            &parts.extensions,
+            &config.proxy.trusted_proxies,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn resolve(peer: &str, headers: &[(&'static str, &'static str)], trusted_proxies: &[&str]) -> Option<IpAddr> {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4711)));
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.append(*name, HeaderValue::from_static(value));
+        }
+        let trusted_proxies: Vec<IpAddr> = trusted_proxies.iter().map(|ip| ip.parse().unwrap()).collect();
+
+        ClientIp::resolve(&header_map, &extensions, &trusted_proxies).0
+    }
+
+    #[test]
+    fn test_resolve() {
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+        let forwarded = [(X_FORWARDED_FOR_HEADER, "198.51.100.1, 203.0.113.7")];
+
+        // The headers of untrusted peers are ignored.
+        assert_eq!(resolve("203.0.113.7", &forwarded, &[]), ip("203.0.113.7"));
+        assert_eq!(resolve("10.0.0.2", &forwarded, &["10.0.0.2"]), ip("203.0.113.7"));
+        assert_eq!(
+            resolve("10.0.0.2", &forwarded, &["10.0.0.2", "203.0.113.7"]),
+            ip("198.51.100.1")
+        );
+        assert_eq!(resolve("10.0.0.2", &[], &["10.0.0.2"]), ip("10.0.0.2"));
+        assert_eq!(
+            resolve(
+                "10.0.0.2",
+                &[(
+                    FORWARDED_HEADER,
+                    "for=198.51.100.1;proto=https, for=\"[2001:db8::17]:4711\""
+                )],
+                &["10.0.0.2"]
+            ),
+            ip("2001:db8::17")
+        );
+        assert_eq!(
+            resolve("10.0.0.2", &[(FORWARDED_HEADER, "for=192.0.2.60:80")], &["10.0.0.2"]),
+            ip("192.0.2.60")
+        );
+    }
+}
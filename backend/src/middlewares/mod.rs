@@ -1 +1,3 @@
 pub mod authentication;
+pub mod client_ip;
+pub mod rate_limit;
@@ -0,0 +1,226 @@
+use crate::error::app_error::{AppError, AppResult, RetryAfterArgs};
+use crate::middlewares::authentication::extract_api_key_from_header;
+use crate::middlewares::client_ip::ClientIp;
+use crate::models::_entities::{personal_access_tokens, sessions};
+use crate::services::custom_config::{RateLimit, RateLimitConfig};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use loco_rs::cache::Cache;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "ratelimit-limit";
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "ratelimit-remaining";
+pub const RATE_LIMIT_RESET_HEADER: &str = "ratelimit-reset";
+pub const RETRY_AFTER_HEADER: &str = "retry-after";
+/// The number of locks the buckets are spread over, see [`RateLimiter::lock_for`].
+const LOCK_COUNT: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now: DateTime<Utc>) -> Self {
+        Self {
+            tokens: limit.capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Refills the bucket for the time since the last request and takes a token.
+    /// Returns whether a token was available.
+    pub fn try_take(&mut self, limit: &RateLimit, now: DateTime<Utc>) -> bool {
+        let elapsed_seconds = (now - self.updated_at).num_milliseconds().max(0) as f64 / 1000.0;
+        self.tokens = (self.tokens + elapsed_seconds * limit.refill_per_second).min(limit.capacity as f64);
+        self.updated_at = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+
+        true
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.tokens.floor() as u64
+    }
+
+    /// The number of seconds until the bucket is full again.
+    pub fn reset_seconds(&self, limit: &RateLimit) -> u64 {
+        ((limit.capacity as f64 - self.tokens).max(0.0) / limit.refill_per_second).ceil() as u64
+    }
+
+    /// The number of seconds until the next token is available.
+    pub fn retry_after_seconds(&self, limit: &RateLimit) -> u64 {
+        ((1.0 - self.tokens).max(0.0) / limit.refill_per_second).ceil() as u64
+    }
+}
+
+/// Limits the requests per client with a token bucket per route group.
+///
+/// Every request takes a token from the bucket of its IP address first, which needs no database access.
+/// Only then, requests with the bearer token of an existing session or personal access token also take a token
+/// from the bucket of their token. So random tokens neither get a fresh bucket nor cause unlimited database queries.
+/// The buckets are kept in the cache of the app, which is the in-memory cache by default.
+pub struct RateLimiter {
+    cache: Arc<Cache>,
+    db: DatabaseConnection,
+    config: RateLimitConfig,
+    trusted_proxies: Vec<IpAddr>,
+    locks: [Mutex<()>; LOCK_COUNT],
+}
+
+impl RateLimiter {
+    pub fn new(
+        cache: Arc<Cache>,
+        db: DatabaseConnection,
+        config: RateLimitConfig,
+        trusted_proxies: Vec<IpAddr>,
+    ) -> Self {
+        Self {
+            cache,
+            db,
+            config,
+            trusted_proxies,
+            locks: std::array::from_fn(|_| Mutex::new(())),
+        }
+    }
+
+    /// Takes a token from the buckets of the client for the requested route group.
+    /// Returns the stricter of both buckets.
+    async fn acquire(&self, request: &Request) -> AppResult<(RateLimit, TokenBucket, bool)> {
+        let (group, limit) = self.config.limit_for_path(request.uri().path());
+        let ip_key = match ClientIp::resolve(request.headers(), request.extensions(), &self.trusted_proxies).0 {
+            Some(ip) => format!("ip:{ip}"),
+            None => "unknown".to_string(),
+        };
+
+        let (bucket, allowed) = self.take(&format!("rate_limit:{}:{}", group, ip_key), limit).await?;
+        if !allowed {
+            return Ok((limit.clone(), bucket, false));
+        }
+        let Some(token_key) = self.token_key(request).await? else {
+            return Ok((limit.clone(), bucket, true));
+        };
+
+        let (token_bucket, allowed) = self.take(&format!("rate_limit:{}:{}", group, token_key), limit).await?;
+        let bucket = match !allowed || token_bucket.tokens < bucket.tokens {
+            true => token_bucket,
+            false => bucket,
+        };
+
+        Ok((limit.clone(), bucket, allowed))
+    }
+
+    /// Takes a token from the bucket and returns the bucket and whether a token was available.
+    async fn take(&self, key: &str, limit: &RateLimit) -> AppResult<(TokenBucket, bool)> {
+        // Reading and writing the bucket has to happen at once, otherwise concurrent requests would take the same token.
+        let _guard = self.lock_for(key).lock().await;
+        let now = Utc::now();
+        let mut bucket = match self.cache.get(key).await.map_err(cache_error)? {
+            Some(value) => serde_json::from_str(&value).map_err(cache_error)?,
+            None => TokenBucket::full(limit, now),
+        };
+        let allowed = bucket.try_take(limit, now);
+        let value = serde_json::to_string(&bucket).map_err(cache_error)?;
+        self.cache.insert(key, &value).await.map_err(cache_error)?;
+
+        Ok((bucket, allowed))
+    }
+
+    /// The key of the bearer token, `None` if there is none or it does not belong to a session or access token.
+    async fn token_key(&self, request: &Request) -> AppResult<Option<String>> {
+        let Ok(api_key) = extract_api_key_from_header(request.headers()) else {
+            return Ok(None);
+        };
+        if let Some(session) = sessions::Model::find_by_token(&self.db, &api_key).await? {
+            return Ok(Some(format!("session:{}", session.id)));
+        }
+        if let Some(access_token) = personal_access_tokens::Model::find_by_token(&self.db, &api_key).await? {
+            return Ok(Some(format!("access_token:{}", access_token.id)));
+        }
+
+        Ok(None)
+    }
+
+    /// Requests of the same client always get the same lock, so they take their tokens one after another.
+    fn lock_for(&self, key: &str) -> &Mutex<()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        &self.locks[hasher.finish() as usize % LOCK_COUNT]
+    }
+}
+
+pub async fn rate_limit(State(limiter): State<Arc<RateLimiter>>, request: Request, next: Next) -> Response {
+    let (limit, bucket, allowed) = match limiter.acquire(&request).await {
+        Ok(result) => result,
+        Err(err) => return err.into_response(),
+    };
+
+    let mut response = if allowed {
+        next.run(request).await
+    } else {
+        let retry_after = bucket.retry_after_seconds(&limit);
+        let mut response = AppError::RateLimitExceeded(RetryAfterArgs { retry_after }).into_response();
+        insert_header(response.headers_mut(), RETRY_AFTER_HEADER, retry_after);
+
+        response
+    };
+
+    let headers = response.headers_mut();
+    insert_header(headers, RATE_LIMIT_LIMIT_HEADER, limit.capacity as u64);
+    insert_header(headers, RATE_LIMIT_REMAINING_HEADER, bucket.remaining());
+    insert_header(headers, RATE_LIMIT_RESET_HEADER, bucket.reset_seconds(&limit));
+
+    response
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &'static str, value: u64) {
+    headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+}
+
+fn cache_error(err: impl ToString) -> AppError {
+    AppError::CacheError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_token_bucket() {
+        let limit = RateLimit {
+            capacity: 2,
+            refill_per_second: 0.5,
+        };
+        let now = Utc::now();
+        let mut bucket = TokenBucket::full(&limit, now);
+
+        assert!(bucket.try_take(&limit, now));
+        assert!(bucket.try_take(&limit, now));
+        assert!(!bucket.try_take(&limit, now));
+        assert_eq!(bucket.remaining(), 0);
+        assert_eq!(bucket.retry_after_seconds(&limit), 2);
+        assert_eq!(bucket.reset_seconds(&limit), 4);
+
+        assert!(bucket.try_take(&limit, now + Duration::seconds(2)));
+        assert!(!bucket.try_take(&limit, now + Duration::seconds(3)));
+
+        // The bucket does not refill beyond its capacity.
+        assert!(bucket.try_take(&limit, now + Duration::seconds(60)));
+        assert_eq!(bucket.remaining(), 1);
+    }
+}
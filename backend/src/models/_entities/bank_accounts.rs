@@ -25,6 +25,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::balance_snapshots::Entity")]
+    BalanceSnapshots,
     #[sea_orm(has_many = "super::budget_criteria_bank_accounts::Entity")]
     BudgetCriteriaBankAccounts,
     #[sea_orm(
@@ -43,10 +45,18 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     LinkedBackAccounts,
+    #[sea_orm(has_many = "super::reconciliations::Entity")]
+    Reconciliations,
     #[sea_orm(has_many = "super::transaction_parties::Entity")]
     TransactionParties,
 }
 
+impl Related<super::balance_snapshots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BalanceSnapshots.def()
+    }
+}
+
 impl Related<super::budget_criteria_bank_accounts::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::BudgetCriteriaBankAccounts.def()
@@ -65,6 +75,12 @@ impl Related<super::linked_back_accounts::Entity> for Entity {
     }
 }
 
+impl Related<super::reconciliations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reconciliations.def()
+    }
+}
+
 impl Related<super::transaction_parties::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::TransactionParties.def()
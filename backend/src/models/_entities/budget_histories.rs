@@ -26,4 +26,19 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::budgets::Entity",
+        from = "Column::BudgetId",
+        to = "super::budgets::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Budgets,
+}
+
+impl Related<super::budgets::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budgets.def()
+    }
+}
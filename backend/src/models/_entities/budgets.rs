@@ -31,9 +31,11 @@ pub enum Relation {
         from = "Column::CriteriaId",
         to = "super::budget_criteria::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     BudgetCriteria,
+    #[sea_orm(has_many = "super::budget_histories::Entity")]
+    BudgetHistories,
 }
 
 impl Related<super::budget_criteria::Entity> for Entity {
@@ -41,3 +43,9 @@ impl Related<super::budget_criteria::Entity> for Entity {
         Relation::BudgetCriteria.def()
     }
 }
+
+impl Related<super::budget_histories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BudgetHistories.def()
+    }
+}
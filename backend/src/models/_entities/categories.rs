@@ -38,6 +38,10 @@ pub enum Relation {
     RecurringTransactions,
     #[sea_orm(has_many = "super::transaction_templates::Entity")]
     TransactionTemplates,
+    #[sea_orm(has_many = "super::transaction_rules::Entity")]
+    TransactionRules,
+    #[sea_orm(has_many = "super::transaction_splits::Entity")]
+    TransactionSplits,
     #[sea_orm(has_many = "super::transactions::Entity")]
     Transactions,
     #[sea_orm(
@@ -86,6 +90,18 @@ impl Related<super::transaction_templates::Entity> for Entity {
     }
 }
 
+impl Related<super::transaction_rules::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionRules.def()
+    }
+}
+
+impl Related<super::transaction_splits::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionSplits.def()
+    }
+}
+
 impl Related<super::transactions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Transactions.def()
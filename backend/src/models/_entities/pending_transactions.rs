@@ -66,7 +66,7 @@ pub enum Relation {
         from = "Column::DestinationId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties2,
     #[sea_orm(
@@ -74,7 +74,7 @@ pub enum Relation {
         from = "Column::SourceId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties1,
 }
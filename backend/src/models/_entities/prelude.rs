@@ -1,5 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::audit_logs::Entity as AuditLogs;
+pub use super::balance_snapshots::Entity as BalanceSnapshots;
 pub use super::bank_accounts::Entity as BankAccounts;
 pub use super::budget_criteria::Entity as BudgetCriteria;
 pub use super::budget_criteria_bank_accounts::Entity as BudgetCriteriaBankAccounts;
@@ -9,21 +11,35 @@ pub use super::budget_criteria_tags::Entity as BudgetCriteriaTags;
 pub use super::budget_histories::Entity as BudgetHistories;
 pub use super::budgets::Entity as Budgets;
 pub use super::categories::Entity as Categories;
+pub use super::category_classifiers::Entity as CategoryClassifiers;
 pub use super::contracts::Entity as Contracts;
 pub use super::currencies::Entity as Currencies;
+pub use super::exchange_rates::Entity as ExchangeRates;
+pub use super::exports::Entity as Exports;
 pub use super::external_bank_account_ibans::Entity as ExternalBankAccountIbans;
 pub use super::external_bank_accounts::Entity as ExternalBankAccounts;
 pub use super::file_attachments::Entity as FileAttachments;
+pub use super::import_profiles::Entity as ImportProfiles;
 pub use super::inactive_contracts::Entity as InactiveContracts;
 pub use super::instances::Entity as Instances;
+pub use super::invite_codes::Entity as InviteCodes;
 pub use super::linked_back_accounts::Entity as LinkedBackAccounts;
 pub use super::pending_transactions::Entity as PendingTransactions;
+pub use super::personal_access_tokens::Entity as PersonalAccessTokens;
+pub use super::possible_duplicates::Entity as PossibleDuplicates;
+pub use super::reconciliation_transactions::Entity as ReconciliationTransactions;
+pub use super::reconciliations::Entity as Reconciliations;
+pub use super::recovery_codes::Entity as RecoveryCodes;
 pub use super::recurring_transactions::Entity as RecurringTransactions;
 pub use super::sessions::Entity as Sessions;
 pub use super::taggings::Entity as Taggings;
 pub use super::tags::Entity as Tags;
 pub use super::transaction_parties::Entity as TransactionParties;
+pub use super::transaction_rule_tags::Entity as TransactionRuleTags;
+pub use super::transaction_rules::Entity as TransactionRules;
+pub use super::transaction_splits::Entity as TransactionSplits;
 pub use super::transaction_templates::Entity as TransactionTemplates;
 pub use super::transactions::Entity as Transactions;
+pub use super::two_factor_challenges::Entity as TwoFactorChallenges;
 pub use super::user_permissions::Entity as UserPermissions;
 pub use super::users::Entity as Users;
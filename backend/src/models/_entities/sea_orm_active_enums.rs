@@ -2,7 +2,31 @@
 
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "audit_action")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "verify_email")]
+    VerifyEmail,
+    #[sea_orm(string_value = "grant_admin")]
+    GrantAdmin,
+    #[sea_orm(string_value = "revoke_admin")]
+    RevokeAdmin,
+    #[sea_orm(string_value = "force_password_reset")]
+    ForcePasswordReset,
+    #[sea_orm(string_value = "revoke_sessions")]
+    RevokeSessions,
+    #[sea_orm(string_value = "disable_account")]
+    DisableAccount,
+    #[sea_orm(string_value = "enable_account")]
+    EnableAccount,
+    #[sea_orm(string_value = "create_invite_code")]
+    CreateInviteCode,
+    #[sea_orm(string_value = "delete_invite_code")]
+    DeleteInviteCode,
+}
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "budget_type")]
 pub enum BudgetType {
@@ -11,6 +35,32 @@ pub enum BudgetType {
     #[sea_orm(string_value = "accumulating")]
     Accumulating,
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "export_format")]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[sea_orm(string_value = "csv")]
+    Csv,
+    #[sea_orm(string_value = "json")]
+    Json,
+    #[sea_orm(string_value = "ledger")]
+    Ledger,
+    #[sea_orm(string_value = "beancount")]
+    Beancount,
+    #[sea_orm(string_value = "archive")]
+    Archive,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "export_status")]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "finished")]
+    Finished,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "filter_transaction_type")]
 pub enum FilterTransactionType {
@@ -21,7 +71,27 @@ pub enum FilterTransactionType {
     #[sea_orm(string_value = "non-contracts")]
     NonContracts,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "import_sign_convention")]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSignConvention {
+    #[sea_orm(string_value = "signed")]
+    Signed,
+    #[sea_orm(string_value = "inverted")]
+    Inverted,
+    #[sea_orm(string_value = "separate_columns")]
+    SeparateColumns,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "possible_duplicate_status")]
+#[serde(rename_all = "snake_case")]
+pub enum PossibleDuplicateStatus {
+    #[sea_orm(string_value = "open")]
+    Open,
+    #[sea_orm(string_value = "dismissed")]
+    Dismissed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transaction_type")]
 pub enum TransactionType {
     #[sea_orm(string_value = "income")]
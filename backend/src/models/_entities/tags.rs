@@ -21,6 +21,8 @@ pub enum Relation {
     BudgetCriteriaTags,
     #[sea_orm(has_many = "super::taggings::Entity")]
     Taggings,
+    #[sea_orm(has_many = "super::transaction_rule_tags::Entity")]
+    TransactionRuleTags,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -43,6 +45,12 @@ impl Related<super::taggings::Entity> for Entity {
     }
 }
 
+impl Related<super::transaction_rule_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionRuleTags.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -21,7 +21,7 @@ pub enum Relation {
         from = "Column::BankAccountId",
         to = "super::bank_accounts::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     BankAccounts,
     #[sea_orm(
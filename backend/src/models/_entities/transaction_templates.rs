@@ -65,7 +65,7 @@ pub enum Relation {
         from = "Column::DestinationId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties2,
     #[sea_orm(
@@ -73,7 +73,7 @@ pub enum Relation {
         from = "Column::SourceId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties1,
 }
@@ -24,6 +24,7 @@ pub struct Model {
     pub destination_iban: Option<String>,
     pub r#type: TransactionType,
     pub amount: i64,
+    pub destination_amount: Option<i64>,
     #[sea_orm(column_type = "Text")]
     pub name: String,
     #[sea_orm(column_type = "Text", nullable)]
@@ -31,6 +32,9 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub note: Option<String>,
     pub booking_date: Option<DateTimeWithTimeZone>,
+    pub reconciled_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub external_id: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -63,12 +67,14 @@ pub enum Relation {
     FileAttachments,
     #[sea_orm(has_many = "super::inactive_contracts::Entity")]
     InactiveContracts,
+    #[sea_orm(has_many = "super::reconciliation_transactions::Entity")]
+    ReconciliationTransactions,
     #[sea_orm(
         belongs_to = "super::transaction_parties::Entity",
         from = "Column::DestinationId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties2,
     #[sea_orm(
@@ -76,9 +82,11 @@ pub enum Relation {
         from = "Column::SourceId",
         to = "super::transaction_parties::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     TransactionParties1,
+    #[sea_orm(has_many = "super::transaction_splits::Entity")]
+    TransactionSplits,
 }
 
 impl Related<super::categories::Entity> for Entity {
@@ -104,3 +112,24 @@ impl Related<super::inactive_contracts::Entity> for Entity {
         Relation::InactiveContracts.def()
     }
 }
+
+impl Related<super::reconciliation_transactions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReconciliationTransactions.def()
+    }
+}
+
+impl Related<super::transaction_splits::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionSplits.def()
+    }
+}
+
+impl Related<super::reconciliations::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::reconciliation_transactions::Relation::Reconciliations.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::reconciliation_transactions::Relation::Transactions.def().rev())
+    }
+}
@@ -22,6 +22,15 @@ pub struct Model {
     pub email_verification_token: Option<String>,
     pub email_verification_sent_at: Option<DateTimeWithTimeZone>,
     pub email_verified_at: Option<DateTimeWithTimeZone>,
+    pub preferred_currency_id: Option<i64>,
+    pub deletion_scheduled_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<DateTimeWithTimeZone>,
+    pub totp_last_step: Option<i64>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub pending_email: Option<String>,
+    pub disabled_at: Option<DateTimeWithTimeZone>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
 }
@@ -30,12 +39,30 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::categories::Entity")]
     Categories,
+    #[sea_orm(has_one = "super::category_classifiers::Entity")]
+    CategoryClassifiers,
     #[sea_orm(has_many = "super::currencies::Entity")]
     Currencies,
+    #[sea_orm(has_many = "super::exchange_rates::Entity")]
+    ExchangeRates,
+    #[sea_orm(has_many = "super::exports::Entity")]
+    Exports,
+    #[sea_orm(has_many = "super::import_profiles::Entity")]
+    ImportProfiles,
+    #[sea_orm(has_many = "super::invite_codes::Entity")]
+    InviteCodes,
+    #[sea_orm(has_many = "super::personal_access_tokens::Entity")]
+    PersonalAccessTokens,
+    #[sea_orm(has_many = "super::recovery_codes::Entity")]
+    RecoveryCodes,
     #[sea_orm(has_many = "super::sessions::Entity")]
     Sessions,
     #[sea_orm(has_many = "super::tags::Entity")]
     Tags,
+    #[sea_orm(has_many = "super::transaction_rules::Entity")]
+    TransactionRules,
+    #[sea_orm(has_many = "super::two_factor_challenges::Entity")]
+    TwoFactorChallenges,
     #[sea_orm(has_many = "super::user_permissions::Entity")]
     UserPermissions,
 }
@@ -46,12 +73,54 @@ impl Related<super::categories::Entity> for Entity {
     }
 }
 
+impl Related<super::category_classifiers::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CategoryClassifiers.def()
+    }
+}
+
 impl Related<super::currencies::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Currencies.def()
     }
 }
 
+impl Related<super::exchange_rates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExchangeRates.def()
+    }
+}
+
+impl Related<super::exports::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Exports.def()
+    }
+}
+
+impl Related<super::import_profiles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportProfiles.def()
+    }
+}
+
+impl Related<super::invite_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::InviteCodes.def()
+    }
+}
+
+impl Related<super::personal_access_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PersonalAccessTokens.def()
+    }
+}
+
+impl Related<super::recovery_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCodes.def()
+    }
+}
+
 impl Related<super::sessions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Sessions.def()
@@ -64,6 +133,18 @@ impl Related<super::tags::Entity> for Entity {
     }
 }
 
+impl Related<super::transaction_rules::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionRules.def()
+    }
+}
+
+impl Related<super::two_factor_challenges::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TwoFactorChallenges.def()
+    }
+}
+
 impl Related<super::user_permissions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserPermissions.def()
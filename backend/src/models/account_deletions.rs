@@ -0,0 +1,227 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{
+    bank_accounts, budget_criteria, budgets, exports, external_bank_accounts, file_attachments, pending_transactions,
+    recurring_transactions, taggings, transaction_parties, transaction_splits, transaction_templates, transactions,
+    user_permissions, users,
+};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Alias, Query, SelectStatement};
+use sea_orm::{Condition, QuerySelect};
+use std::collections::HashSet;
+
+/// Deletes the user together with everything that only belongs to the user.
+///
+/// Bank accounts and budgets are owned through user permissions, so the ones nobody else has access to are deleted
+/// explicitly. Transactions between a deleted and a remaining bank account are kept for the remaining one.
+/// Everything else that belongs to the user is removed by the cascades of the `users` table.
+///
+/// Returns the storage paths of the files that are no longer used. They have to be removed after the commit.
+pub async fn delete_account(db: &impl ConnectionTrait, user: &users::Model) -> AppResult<Vec<String>> {
+    let bank_account_ids =
+        user_permissions::Model::find_exclusively_owned_ids(db, user.id, bank_accounts::Entity.table_name()).await?;
+    let budget_ids =
+        user_permissions::Model::find_exclusively_owned_ids(db, user.id, budgets::Entity.table_name()).await?;
+
+    let mut file_paths: Vec<String> = exports::Model::find_all_by_user_id(db, user.id)
+        .await?
+        .into_iter()
+        .filter_map(|export| export.file_path)
+        .collect();
+    file_paths.extend(delete_bank_accounts(db, bank_account_ids).await?);
+    delete_budgets(db, budget_ids).await?;
+    users::Entity::delete_by_id(user.id).exec(db).await?;
+
+    Ok(file_paths)
+}
+
+/// Deletes the bank accounts with all transactions that do not involve any remaining bank account.
+/// Returns the storage paths of the attachments that are no longer used.
+async fn delete_bank_accounts(db: &impl ConnectionTrait, ids: Vec<i64>) -> AppResult<Vec<String>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let deleted_parties = Query::select()
+        .column(transaction_parties::Column::Id)
+        .from(transaction_parties::Entity)
+        .and_where(transaction_parties::Column::BankAccountId.is_in(ids.clone()))
+        .to_owned();
+    let remaining_parties = Query::select()
+        .column(transaction_parties::Column::Id)
+        .from(transaction_parties::Entity)
+        .and_where(transaction_parties::Column::BankAccountId.is_not_null())
+        .and_where(transaction_parties::Column::BankAccountId.is_not_in(ids.clone()))
+        .to_owned();
+    let orphaned = orphaned_condition(&deleted_parties, &remaining_parties);
+
+    let orphaned_transactions = transactions::Entity::find().filter(orphaned.clone()).all(db).await?;
+    let transaction_ids: Vec<i64> = orphaned_transactions.iter().map(|transaction| transaction.id).collect();
+    let split_ids: Vec<i64> = transaction_splits::Model::find_by_transaction_ids(db, transaction_ids.clone())
+        .await?
+        .into_values()
+        .flatten()
+        .map(|split| split.id)
+        .collect();
+
+    let mut attachment_ids: HashSet<i64> = orphaned_transactions
+        .iter()
+        .filter_map(|transaction| transaction.file_attachment_id)
+        .collect();
+    attachment_ids.extend(
+        transaction_templates::Entity::find()
+            .select_only()
+            .column(transaction_templates::Column::FileAttachmentId)
+            .filter(orphaned.clone())
+            .into_tuple::<Option<i64>>()
+            .all(db)
+            .await?
+            .into_iter()
+            .flatten(),
+    );
+    attachment_ids.extend(
+        recurring_transactions::Entity::find()
+            .select_only()
+            .column(recurring_transactions::Column::FileAttachmentId)
+            .filter(orphaned.clone())
+            .into_tuple::<Option<i64>>()
+            .all(db)
+            .await?
+            .into_iter()
+            .flatten(),
+    );
+    attachment_ids.extend(
+        pending_transactions::Entity::find()
+            .select_only()
+            .column(pending_transactions::Column::FileAttachmentId)
+            .filter(orphaned.clone())
+            .into_tuple::<Option<i64>>()
+            .all(db)
+            .await?
+            .into_iter()
+            .flatten(),
+    );
+
+    taggings::Model::delete_for_entities(db, transaction_splits::Entity.table_name(), split_ids).await?;
+    taggings::Model::delete_for_entities(db, transactions::Entity.table_name(), transaction_ids.clone()).await?;
+    transactions::Entity::delete_many()
+        .filter(transactions::Column::Id.is_in(transaction_ids))
+        .exec(db)
+        .await?;
+    transaction_templates::Entity::delete_many()
+        .filter(orphaned.clone())
+        .exec(db)
+        .await?;
+    recurring_transactions::Entity::delete_many()
+        .filter(orphaned.clone())
+        .exec(db)
+        .await?;
+    pending_transactions::Entity::delete_many()
+        .filter(orphaned)
+        .exec(db)
+        .await?;
+    // The parties of the bank accounts are removed by the cascade, which detaches the remaining transactions.
+    bank_accounts::Entity::delete_many()
+        .filter(bank_accounts::Column::Id.is_in(ids))
+        .exec(db)
+        .await?;
+
+    delete_unused_attachments(db, attachment_ids.into_iter().collect()).await
+}
+
+/// Matches the rows of the transaction tables that involve one of the deleted parties but none of the remaining ones.
+fn orphaned_condition(deleted_parties: &SelectStatement, remaining_parties: &SelectStatement) -> Condition {
+    let involves = |parties: &SelectStatement| {
+        let party = |column: &str| {
+            Condition::all()
+                .add(Expr::col(Alias::new(column)).is_not_null())
+                .add(Expr::col(Alias::new(column)).in_subquery(parties.clone()))
+        };
+
+        Condition::any().add(party("source_id")).add(party("destination_id"))
+    };
+
+    Condition::all()
+        .add(involves(deleted_parties))
+        .add(involves(remaining_parties).not())
+}
+
+/// Deletes the attachments that are not globally accessible and no longer referenced.
+/// Returns the storage paths of their files.
+async fn delete_unused_attachments(db: &impl ConnectionTrait, ids: Vec<i64>) -> AppResult<Vec<String>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let referenced = |table: Alias, column: Alias| {
+        Query::select()
+            .column(column.clone())
+            .from(table)
+            .and_where(Expr::col(column).is_not_null())
+            .to_owned()
+    };
+    let attachments = file_attachments::Entity::find()
+        .filter(file_attachments::Column::Id.is_in(ids))
+        .filter(file_attachments::Column::GloballyAccessible.eq(false))
+        .filter(file_attachments::Column::Id.not_in_subquery(referenced(
+            Alias::new(transactions::Entity.table_name()),
+            Alias::new("file_attachment_id"),
+        )))
+        .filter(file_attachments::Column::Id.not_in_subquery(referenced(
+            Alias::new(transaction_templates::Entity.table_name()),
+            Alias::new("file_attachment_id"),
+        )))
+        .filter(file_attachments::Column::Id.not_in_subquery(referenced(
+            Alias::new(recurring_transactions::Entity.table_name()),
+            Alias::new("file_attachment_id"),
+        )))
+        .filter(file_attachments::Column::Id.not_in_subquery(referenced(
+            Alias::new(pending_transactions::Entity.table_name()),
+            Alias::new("file_attachment_id"),
+        )))
+        .filter(file_attachments::Column::Id.not_in_subquery(referenced(
+            Alias::new(external_bank_accounts::Entity.table_name()),
+            Alias::new("logo_id"),
+        )))
+        .all(db)
+        .await?;
+
+    file_attachments::Entity::delete_many()
+        .filter(file_attachments::Column::Id.is_in(attachments.iter().map(|attachment| attachment.id)))
+        .exec(db)
+        .await?;
+
+    Ok(attachments.into_iter().map(|attachment| attachment.path).collect())
+}
+
+/// Deletes the budgets together with their criteria (unless another budget still uses them) and histories.
+async fn delete_budgets(db: &impl ConnectionTrait, ids: Vec<i64>) -> AppResult<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let criteria_ids: Vec<i64> = budgets::Entity::find()
+        .select_only()
+        .column(budgets::Column::CriteriaId)
+        .filter(budgets::Column::Id.is_in(ids.clone()))
+        .into_tuple()
+        .all(db)
+        .await?;
+    budgets::Entity::delete_many()
+        .filter(budgets::Column::Id.is_in(ids))
+        .exec(db)
+        .await?;
+    budget_criteria::Entity::delete_many()
+        .filter(budget_criteria::Column::Id.is_in(criteria_ids))
+        .filter(
+            budget_criteria::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(budgets::Column::CriteriaId)
+                    .from(budgets::Entity)
+                    .to_owned(),
+            ),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use super::_entities::audit_logs::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use crate::models::_entities::sea_orm_active_enums::AuditAction;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{IdSort, Page, Pagination};
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+
+pub type AuditLogs = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Records an action of an admin, on the account of another user if there is one.
+    pub async fn record(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        actor_id: i64,
+        target_user_id: Option<i64>,
+        action: AuditAction,
+        details: Option<Json>,
+    ) -> AppResult<Self> {
+        let audit_log = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            actor_id: Set(Some(actor_id)),
+            target_user_id: Set(target_user_id),
+            action: Set(action),
+            details: Set(details),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(db)
+        .await?;
+
+        Ok(audit_log)
+    }
+
+    /// Loads a page of the audit log, optionally only the entries of a single user.
+    pub async fn find_page(
+        db: &impl ConnectionTrait,
+        target_user_id: Option<i64>,
+        pagination: &Pagination<IdSort>,
+    ) -> AppResult<Page<Self>> {
+        let mut query = Entity::find();
+        if let Some(target_user_id) = target_user_id {
+            query = query.filter(Column::TargetUserId.eq(target_user_id));
+        }
+
+        pagination.load(db, query).await
+    }
+}
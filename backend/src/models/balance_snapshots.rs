@@ -0,0 +1,264 @@
+use super::_entities::balance_snapshots::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use crate::models::_entities::bank_accounts;
+use crate::models::reports::daily_balance_changes;
+use chrono::Datelike;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::ActiveValue::Set;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+pub type BalanceSnapshots = Entity;
+
+/// Snapshots are inserted in batches of this size.
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// The length of the periods of a balance history.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Interval {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+impl Interval {
+    fn same_period(&self, date: Date, other: Date) -> bool {
+        match self {
+            Self::Day => date == other,
+            Self::Week => date.iso_week() == other.iso_week(),
+            Self::Month => date.year() == other.year() && date.month() == other.month(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_latest(db: &impl ConnectionTrait, bank_account_id: i64) -> AppResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .order_by_desc(Column::Date)
+            .one(db)
+            .await?)
+    }
+
+    /// Finds the snapshots of the bank account from `from` to `to` and the latest one before `from`, ordered by date.
+    pub async fn find_for_range(
+        db: &impl ConnectionTrait,
+        bank_account_id: i64,
+        from: Date,
+        to: Date,
+    ) -> AppResult<Vec<Self>> {
+        let before = Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .filter(Column::Date.lt(from))
+            .order_by_desc(Column::Date)
+            .one(db)
+            .await?;
+        let snapshots = Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .filter(Column::Date.between(from, to))
+            .order_by_asc(Column::Date)
+            .all(db)
+            .await?;
+
+        Ok(before.into_iter().chain(snapshots).collect())
+    }
+
+    /// Removes the snapshots of the bank account from the date on, as they no longer match its history.
+    /// They are rebuilt by the next run of the `snapshot_balances` task.
+    pub async fn delete_since(db: &impl ConnectionTrait, bank_account_id: i64, date: Date) -> AppResult<()> {
+        Entity::delete_many()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .filter(Column::Date.gte(date))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reconstructs the balance of the bank account at the end of every day from `from` to `to`.
+    /// Only the days after the last snapshot are replayed from the transactions.
+    pub async fn daily_balances(
+        db: &impl ConnectionTrait,
+        bank_account: &bank_accounts::Model,
+        from: Date,
+        to: Date,
+    ) -> AppResult<Vec<(Date, i64)>> {
+        let snapshots = Self::find_for_range(db, bank_account.id, from, to).await?;
+        let last_snapshot = snapshots.last().map(|snapshot| snapshot.date);
+        let changes = daily_balance_changes(db, bank_account.id, last_snapshot, to).await?;
+
+        Ok(replay(bank_account.original_balance, &snapshots, &changes, from, to))
+    }
+
+    /// Snapshots the balance of the bank account for every day after its latest snapshot up to `until`.
+    /// The first snapshot is taken on the day the bank account was created or its first transaction was booked.
+    ///
+    /// Returns the number of new snapshots.
+    pub async fn snapshot(
+        db: &impl ConnectionTrait,
+        bank_account: &bank_accounts::Model,
+        until: Date,
+    ) -> AppResult<usize> {
+        let latest = Self::find_latest(db, bank_account.id).await?;
+        let changes =
+            daily_balance_changes(db, bank_account.id, latest.as_ref().map(|latest| latest.date), until).await?;
+        let from = match &latest {
+            Some(latest) => latest.date + chrono::Days::new(1),
+            None => {
+                let created_at = bank_account.created_at.naive_utc().date();
+                changes.first().map_or(created_at, |(date, _)| created_at.min(*date))
+            }
+        };
+        if from > until {
+            return Ok(0);
+        }
+
+        let balances = replay(bank_account.original_balance, latest.as_slice(), &changes, from, until);
+        for batch in balances.chunks(INSERT_BATCH_SIZE) {
+            let snapshots = batch.iter().map(|(date, balance)| ActiveModel {
+                bank_account_id: Set(bank_account.id),
+                date: Set(*date),
+                balance: Set(*balance),
+                created_at: Set(chrono::Utc::now().into()),
+                updated_at: Set(chrono::Utc::now().into()),
+            });
+            Entity::insert_many(snapshots)
+                .on_conflict(
+                    OnConflict::columns([Column::BankAccountId, Column::Date])
+                        .update_columns([Column::Balance, Column::UpdatedAt])
+                        .to_owned(),
+                )
+                .exec(db)
+                .await?;
+        }
+
+        Ok(balances.len())
+    }
+}
+
+/// Reconstructs the balance at the end of every day from `from` to `to` (inclusive).
+///
+/// Starts at the latest snapshot before `from` (or the original balance) and takes over the balance of every
+/// following snapshot. The daily changes are added on top, so they must only contain days after the last snapshot.
+pub fn replay(
+    original_balance: i64,
+    snapshots: &[Model],
+    changes: &[(Date, i64)],
+    from: Date,
+    to: Date,
+) -> Vec<(Date, i64)> {
+    let mut balance = snapshots
+        .iter()
+        .take_while(|snapshot| snapshot.date < from)
+        .last()
+        .map_or(original_balance, |snapshot| snapshot.balance);
+    let mut snapshots = snapshots.iter().skip_while(|snapshot| snapshot.date < from).peekable();
+    let mut changes = changes.iter().peekable();
+    while let Some((_, change)) = changes.next_if(|(date, _)| *date < from) {
+        balance = balance.saturating_add(*change);
+    }
+
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            if let Some(snapshot) = snapshots.next_if(|snapshot| snapshot.date == date) {
+                balance = snapshot.balance;
+            }
+            while let Some((_, change)) = changes.next_if(|(change_date, _)| *change_date == date) {
+                balance = balance.saturating_add(*change);
+            }
+
+            (date, balance)
+        })
+        .collect()
+}
+
+/// Keeps the balance at the end of every period, which is the last day of the period that is part of the history.
+pub fn sample(balances: &[(Date, i64)], interval: Interval) -> Vec<(Date, i64)> {
+    let mut samples: Vec<(Date, i64)> = Vec::new();
+    for &(date, balance) in balances {
+        match samples.last_mut() {
+            Some(last) if interval.same_period(last.0, date) => *last = (date, balance),
+            _ => samples.push((date, balance)),
+        }
+    }
+
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> Date {
+        Date::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn snapshot(date: Date, balance: i64) -> Model {
+        Model {
+            bank_account_id: 1,
+            date,
+            balance,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_replay() {
+        let changes = vec![(day(1), 50), (day(3), -20), (day(6), 5)];
+        assert_eq!(
+            replay(100, &[], &changes, day(2), day(4)),
+            vec![(day(2), 150), (day(3), 130), (day(4), 130)]
+        );
+
+        // The snapshots replace the original balance and all changes up to their date.
+        let snapshots = vec![snapshot(day(1), 1000), snapshot(day(2), 1010), snapshot(day(3), 990)];
+        let changes = vec![(day(5), 10)];
+        assert_eq!(
+            replay(100, &snapshots, &changes, day(2), day(6)),
+            vec![
+                (day(2), 1010),
+                (day(3), 990),
+                (day(4), 990),
+                (day(5), 1000),
+                (day(6), 1000)
+            ]
+        );
+        assert_eq!(replay(100, &snapshots, &changes, day(6), day(6)), vec![(day(6), 1000)]);
+    }
+
+    #[test]
+    fn test_sample() {
+        let balances: Vec<(Date, i64)> = (1..=12).map(|date| (day(date), date as i64)).collect();
+
+        assert_eq!(sample(&balances, Interval::Day), balances);
+        // The 4th and the 11th of October 2026 are Sundays.
+        assert_eq!(
+            sample(&balances, Interval::Week),
+            vec![(day(4), 4), (day(11), 11), (day(12), 12)]
+        );
+        assert_eq!(sample(&balances, Interval::Month), vec![(day(12), 12)]);
+    }
+}
@@ -1,5 +1,10 @@
-use super::_entities::bank_accounts::{ActiveModel, Entity};
+use super::_entities::bank_accounts::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::user_permissions;
+use crate::models::user_permissions::Permission;
+use enumflags2::BitFlags;
 use sea_orm::entity::prelude::*;
+use sea_orm::QueryOrder;
 pub type BankAccounts = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +24,62 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Option<Self>> {
+        Ok(Entity::find_by_id(id).one(db).await?)
+    }
+
+    /// Finds a bank account and ensures that the user holds the required permissions on it.
+    pub async fn find_by_id_with_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<Self> {
+        user_permissions::Model::ensure_permissions(db, user_id, Entity.table_name(), id, required).await?;
+
+        Self::find_by_id(db, id).await?.ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Finds all bank accounts on which the user holds the required permissions.
+    /// Only the given bank accounts are returned, unless the list is empty.
+    pub async fn find_all_with_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        ids: &[i64],
+        required: BitFlags<Permission>,
+    ) -> AppResult<Vec<Self>> {
+        let mut query = Entity::find().filter(Column::Id.in_subquery(user_permissions::Model::accessible_entity_ids(
+            user_id,
+            Entity.table_name(),
+            required,
+        )));
+        if !ids.is_empty() {
+            query = query.filter(Column::Id.is_in(ids.iter().copied()));
+        }
+
+        Ok(query.order_by_asc(Column::Id).all(db).await?)
+    }
+
+    /// Finds all bank accounts of all users.
+    pub async fn find_all(db: &impl ConnectionTrait) -> AppResult<Vec<Self>> {
+        Ok(Entity::find().order_by_asc(Column::Id).all(db).await?)
+    }
+
+    /// Adds the given (signed) delta to the current balance of the bank account.
+    pub async fn adjust_balance(db: &impl ConnectionTrait, id: i64, delta: i64) -> AppResult<()> {
+        if delta == 0 {
+            return Ok(());
+        }
+
+        Entity::update_many()
+            .col_expr(Column::Balance, Expr::col(Column::Balance).add(delta))
+            .col_expr(Column::UpdatedAt, Expr::value(chrono::Utc::now()))
+            .filter(Column::Id.eq(id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
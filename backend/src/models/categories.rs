@@ -1,5 +1,9 @@
-use super::_entities::categories::{ActiveModel, Entity};
+use super::_entities::categories::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::snowflake_generator::SnowflakeGenerator;
 use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::Condition;
 pub type Categories = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +23,132 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    /// Finds a category that is either owned by the user or available to everyone.
+    pub async fn find_accessible_by_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(
+                Condition::any()
+                    .add(Column::UserId.eq(user_id))
+                    .add(Column::UserId.is_null()),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Finds all categories that are either owned by the user or available to everyone.
+    pub async fn find_all_accessible(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::UserId.eq(user_id))
+                    .add(Column::UserId.is_null()),
+            )
+            .all(db)
+            .await?)
+    }
+
+    /// Finds the category of the user with the given name below the given parent or creates it.
+    pub async fn find_or_create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        parent_id: Option<i64>,
+        name: &str,
+    ) -> AppResult<Self> {
+        let parent_condition = match parent_id {
+            Some(parent_id) => Column::ParentId.eq(parent_id),
+            None => Column::ParentId.is_null(),
+        };
+        let category = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(parent_condition)
+            .filter(Column::Name.eq(name))
+            .one(db)
+            .await?;
+
+        if let Some(category) = category {
+            return Ok(category);
+        }
+
+        let category = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            parent_id: Set(parent_id),
+            user_id: Set(Some(user_id)),
+            name: Set(name.to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok(category.insert(db).await?)
+    }
+
+    /// Resolves a category path like `Food:Groceries`, creating all missing categories on the way.
+    /// Returns `None` if the path does not contain any name.
+    pub async fn find_or_create_by_path(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        path: &str,
+        separator: char,
+    ) -> AppResult<Option<Self>> {
+        let mut category: Option<Self> = None;
+        for name in path.split(separator).map(str::trim).filter(|name| !name.is_empty()) {
+            let parent_id = category.as_ref().map(|parent| parent.id);
+            category = Some(Self::find_or_create(db, snowflake_generator, user_id, parent_id, name).await?);
+        }
+
+        Ok(category)
+    }
+}
+
+/// Returns the id of the root category and the ids of all its (transitive) children.
+pub fn subtree_ids(categories: &[Model], root_id: i64) -> Vec<i64> {
+    let mut ids = vec![root_id];
+    let mut index = 0;
+    while index < ids.len() {
+        let parent_id = ids[index];
+        let children: Vec<i64> = categories
+            .iter()
+            .filter(|category| category.parent_id == Some(parent_id) && !ids.contains(&category.id))
+            .map(|category| category.id)
+            .collect();
+        ids.extend(children);
+        index += 1;
+    }
+
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i64, parent_id: Option<i64>) -> Model {
+        Model {
+            id,
+            parent_id,
+            user_id: Some(1),
+            name: format!("Category {}", id),
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_subtree_ids() {
+        let categories = [
+            category(1, None),
+            category(2, Some(1)),
+            category(3, Some(2)),
+            category(4, Some(1)),
+            category(5, None),
+        ];
+
+        assert_eq!(subtree_ids(&categories, 1), vec![1, 2, 4, 3]);
+        assert_eq!(subtree_ids(&categories, 2), vec![2, 3]);
+        assert_eq!(subtree_ids(&categories, 5), vec![5]);
+    }
+}
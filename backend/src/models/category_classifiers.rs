@@ -0,0 +1,181 @@
+use super::_entities::category_classifiers::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::transactions;
+use crate::models::user_permissions::Permission;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::utils::naive_bayes::{tokenize, NaiveBayes};
+use crate::workers::train_category_classifier::{TrainCategoryClassifierWorker, TrainCategoryClassifierWorkerArgs};
+use loco_rs::app::AppContext;
+use loco_rs::bgworker::BackgroundWorker;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Alias, OnConflict};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QueryOrder, QuerySelect};
+pub type CategoryClassifiers = Entity;
+
+/// The classifier is retrained in the background once this many transactions were categorized.
+pub const RETRAIN_AFTER_CATEGORIZATIONS: i32 = 20;
+/// Only the most recent categorized transactions are used for training.
+pub const MAX_TRAINING_TRANSACTIONS: u64 = 5000;
+pub const SUGGESTION_COUNT: usize = 3;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategorySuggestion {
+    pub category_id: i64,
+    /// How confident the classifier is (0 - 1).
+    pub confidence: f64,
+}
+
+impl Model {
+    pub async fn find_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Option<Self>> {
+        Ok(Entity::find().filter(Column::UserId.eq(user_id)).one(db).await?)
+    }
+
+    /// Loads the trained classifier of the user. Returns an empty classifier if it was never trained.
+    pub async fn load_classifier(db: &impl ConnectionTrait, user_id: i64) -> AppResult<NaiveBayes> {
+        match Self::find_by_user_id(db, user_id).await? {
+            None => Ok(NaiveBayes::default()),
+            Some(model) => serde_json::from_value(model.model).map_err(|err| AppError::DbParseJson(err.to_string())),
+        }
+    }
+
+    /// Trains a new classifier on the categorized transactions the user can see.
+    pub async fn train(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+    ) -> AppResult<Self> {
+        let transactions = transactions::Entity::find()
+            .filter(
+                transactions::Column::Id
+                    .in_subquery(transactions::Model::visible_ids(user_id, Permission::Read.into())),
+            )
+            .filter(transactions::Column::CategoryId.is_not_null())
+            .order_by_desc(transactions::Column::Id)
+            .limit(MAX_TRAINING_TRANSACTIONS)
+            .all(db)
+            .await?;
+
+        let transaction_count = transactions.len() as i32;
+        let classifier = NaiveBayes::train(transactions.iter().filter_map(|transaction| {
+            transaction
+                .category_id
+                .map(|category_id| (category_id, features(transaction)))
+        }));
+        let classifier = serde_json::to_value(&classifier).map_err(|err| AppError::JsonError(err.to_string()))?;
+
+        let existing = Self::find_by_user_id(db, user_id).await?;
+        let mut active_model = match &existing {
+            Some(model) => model.clone().into_active_model(),
+            None => ActiveModel {
+                id: Set(snowflake_generator.next_id()?),
+                user_id: Set(user_id),
+                created_at: Set(chrono::Utc::now().into()),
+                ..Default::default()
+            },
+        };
+        active_model.model = Set(classifier);
+        active_model.transaction_count = Set(transaction_count);
+        active_model.pending_categorizations = Set(0);
+        active_model.trained_at = Set(Some(chrono::Utc::now().into()));
+        active_model.updated_at = Set(chrono::Utc::now().into());
+
+        match existing {
+            Some(_) => Ok(active_model.update(db).await?),
+            None => Ok(active_model.insert(db).await?),
+        }
+    }
+
+    /// Counts newly categorized transactions of the user
+    /// and schedules a retraining once enough transactions were categorized.
+    pub async fn record_categorizations(
+        ctx: &AppContext,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        count: i32,
+    ) -> AppResult<()> {
+        if count <= 0 {
+            return Ok(());
+        }
+
+        // A single upsert, so concurrent categorizations can not overwrite each other's count.
+        let classifier =
+            serde_json::to_value(NaiveBayes::default()).map_err(|err| AppError::JsonError(err.to_string()))?;
+        let active_model = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            model: Set(classifier),
+            transaction_count: Set(0),
+            pending_categorizations: Set(count),
+            trained_at: Set(None),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+        let pending = Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .value(
+                        Column::PendingCategorizations,
+                        Expr::col((Entity, Column::PendingCategorizations))
+                            .add(Expr::col((Alias::new("excluded"), Column::PendingCategorizations))),
+                    )
+                    .update_column(Column::UpdatedAt)
+                    .to_owned(),
+            )
+            .exec_with_returning(&ctx.db)
+            .await?
+            .pending_categorizations;
+
+        if pending >= RETRAIN_AFTER_CATEGORIZATIONS {
+            TrainCategoryClassifierWorker::perform_later(ctx, TrainCategoryClassifierWorkerArgs { user_id }).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Suggests the most likely categories for the transaction.
+pub fn suggest(classifier: &NaiveBayes, transaction: &transactions::Model) -> Vec<CategorySuggestion> {
+    classifier
+        .predict(&features(transaction), SUGGESTION_COUNT)
+        .into_iter()
+        .map(|(category_id, confidence)| CategorySuggestion {
+            category_id,
+            confidence,
+        })
+        .collect()
+}
+
+/// The tokens of the name and purpose and the counterparty (prefixed, so they are not mixed up with words).
+fn features(transaction: &transactions::Model) -> Vec<String> {
+    let mut features: Vec<String> = tokenize(&transaction.name)
+        .chain(transaction.purpose.as_deref().into_iter().flat_map(tokenize))
+        .collect();
+
+    if let Some(name) = transaction.counterparty_name() {
+        features.extend(tokenize(name).map(|token| format!("party:{}", token)));
+    }
+    if let Some(iban) = transaction.counterparty_iban() {
+        features.push(format!("iban:{}", iban));
+    }
+
+    features
+}
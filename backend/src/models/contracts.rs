@@ -1,4 +1,5 @@
-use super::_entities::contracts::{ActiveModel, Entity};
+use super::_entities::contracts::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
 use sea_orm::entity::prelude::*;
 pub type Contracts = Entity;
 
@@ -19,3 +20,15 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_all_for_recurring_transactions(
+        db: &impl ConnectionTrait,
+        recurring_transaction_ids: &[i64],
+    ) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::RecurringTransactionId.is_in(recurring_transaction_ids.iter().copied()))
+            .all(db)
+            .await?)
+    }
+}
@@ -1,5 +1,7 @@
-use super::_entities::currencies::{ActiveModel, Entity};
+use super::_entities::currencies::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
 use sea_orm::entity::prelude::*;
+use sea_orm::Condition;
 pub type Currencies = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +21,37 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Finds a currency that is either owned by the user or available to everyone.
+    pub async fn find_accessible_by_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(
+                Condition::any()
+                    .add(Column::UserId.eq(user_id))
+                    .add(Column::UserId.is_null()),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Finds all currencies that are either owned by the user or available to everyone.
+    pub async fn find_all_accessible(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::UserId.eq(user_id))
+                    .add(Column::UserId.is_null()),
+            )
+            .all(db)
+            .await?)
+    }
+}
@@ -0,0 +1,170 @@
+use super::_entities::exchange_rates::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::currencies;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::utils::exchange_rate::convert_amount;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::ActiveValue::Set;
+use sea_orm::QueryOrder;
+use std::collections::HashMap;
+pub type ExchangeRates = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Sets the rate from the source into the target currency, replacing the previous rate of the pair.
+    pub async fn set(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        source_currency_id: i64,
+        target_currency_id: i64,
+        rate: f64,
+    ) -> AppResult<Self> {
+        let exchange_rate = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            source_currency_id: Set(source_currency_id),
+            target_currency_id: Set(target_currency_id),
+            rate: Set(rate),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok(Entity::insert(exchange_rate)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::SourceCurrencyId, Column::TargetCurrencyId])
+                    .update_columns([Column::Rate, Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec_with_returning(db)
+            .await?)
+    }
+}
+
+/// Converts amounts of different currencies into a single currency with the exchange rates of a user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyConverter {
+    pub currency: currencies::Model,
+    /// The rate into the target currency and the decimal places of every convertible currency.
+    rates: HashMap<i64, (f64, i32)>,
+}
+
+impl CurrencyConverter {
+    pub async fn load(db: &impl ConnectionTrait, user_id: i64, currency: currencies::Model) -> AppResult<Self> {
+        let currencies = currencies::Model::find_all_accessible(db, user_id).await?;
+        let rates = Model::find_all_by_user_id(db, user_id).await?;
+
+        Ok(Self::new(currency, &currencies, &rates))
+    }
+
+    /// Uses the rate of each currency into the target currency or, if only the opposite rate is known, its inverse.
+    pub fn new(currency: currencies::Model, currencies: &[currencies::Model], rates: &[Model]) -> Self {
+        let mut known_rates = HashMap::new();
+        for rate in rates {
+            if rate.target_currency_id == currency.id {
+                known_rates.insert(rate.source_currency_id, rate.rate);
+            } else if rate.source_currency_id == currency.id {
+                known_rates.entry(rate.target_currency_id).or_insert(1.0 / rate.rate);
+            }
+        }
+
+        let rates = currencies
+            .iter()
+            .filter_map(|source| {
+                let rate = known_rates.get(&source.id)?;
+                Some((source.id, (*rate, source.decimal_places)))
+            })
+            .collect();
+
+        Self { currency, rates }
+    }
+
+    /// Converts the amount into the target currency.
+    /// Returns `None` if the exchange rate of the currency is unknown.
+    pub fn convert(&self, amount: i64, currency_id: i64) -> Option<i64> {
+        if currency_id == self.currency.id {
+            return Some(amount);
+        }
+
+        let (rate, decimal_places) = self.rates.get(&currency_id)?;
+        convert_amount(amount, *rate, *decimal_places, self.currency.decimal_places)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(id: i64, decimal_places: i32) -> currencies::Model {
+        currencies::Model {
+            id,
+            user_id: None,
+            name: format!("Currency {id}"),
+            symbol: "$".to_string(),
+            iso_code: None,
+            decimal_places,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    fn rate(source_currency_id: i64, target_currency_id: i64, rate: f64) -> Model {
+        Model {
+            id: source_currency_id * 10 + target_currency_id,
+            user_id: 1,
+            source_currency_id,
+            target_currency_id,
+            rate,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let currencies = vec![currency(1, 2), currency(2, 2), currency(3, 0), currency(4, 2)];
+        let rates = vec![rate(2, 1, 0.9), rate(1, 3, 160.0), rate(1, 2, 2.0)];
+        let converter = CurrencyConverter::new(currency(1, 2), &currencies, &rates);
+
+        assert_eq!(converter.convert(1000, 1), Some(1000));
+        // The direct rate wins over the inverse of the opposite rate.
+        assert_eq!(converter.convert(1000, 2), Some(900));
+        // 160 units without decimal places are 1.00 of the target currency.
+        assert_eq!(converter.convert(160, 3), Some(100));
+        assert_eq!(converter.convert(1000, 4), None);
+    }
+}
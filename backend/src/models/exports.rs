@@ -0,0 +1,132 @@
+use super::_entities::exports::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::sea_orm_active_enums::{ExportFormat, ExportStatus};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QueryOrder};
+pub type Exports = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Creates a pending export, which still has to be written by the export worker.
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        format: ExportFormat,
+    ) -> AppResult<Self> {
+        let export = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            format: Set(format),
+            status: Set(ExportStatus::Pending),
+            file_path: Set(None),
+            error: Set(None),
+            finished_at: Set(None),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok(export.insert(db).await?)
+    }
+
+    /// Marks the export as finished after its file was written into the storage.
+    pub async fn finish(self, db: &impl ConnectionTrait, file_path: String) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = Set(ExportStatus::Finished);
+        active_model.file_path = Set(Some(file_path));
+        active_model.finished_at = Set(Some(chrono::Utc::now().into()));
+
+        Ok(active_model.update(db).await?)
+    }
+
+    pub async fn fail(self, db: &impl ConnectionTrait, error: String) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = Set(ExportStatus::Failed);
+        active_model.error = Set(Some(error));
+        active_model.finished_at = Set(Some(chrono::Utc::now().into()));
+
+        Ok(active_model.update(db).await?)
+    }
+
+    /// The path of the file inside the storage.
+    pub fn storage_path(&self) -> String {
+        format!("exports/{}/{}.{}", self.user_id, self.id, self.format.extension())
+    }
+
+    /// The name of the downloaded file.
+    pub fn file_name(&self) -> String {
+        let kind = match self.format {
+            ExportFormat::Archive => "account",
+            _ => "export",
+        };
+
+        format!(
+            "financrr-{}-{}.{}",
+            kind,
+            self.created_at.format("%Y-%m-%d"),
+            self.format.extension()
+        )
+    }
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "zip",
+            Self::Json => "json",
+            Self::Ledger => "ledger",
+            Self::Beancount => "beancount",
+            Self::Archive => "zip",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv | Self::Archive => "application/zip",
+            Self::Json => "application/json",
+            Self::Ledger | Self::Beancount => "text/plain; charset=utf-8",
+        }
+    }
+}
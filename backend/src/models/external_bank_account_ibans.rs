@@ -1,4 +1,5 @@
-use super::_entities::external_bank_account_ibans::{ActiveModel, Entity};
+use super::_entities::external_bank_account_ibans::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
 use sea_orm::entity::prelude::*;
 pub type ExternalBankAccountIbans = Entity;
 
@@ -19,3 +20,10 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    /// Finds an iban entry by its normalized iban.
+    pub async fn find_by_iban(db: &impl ConnectionTrait, iban: &str) -> AppResult<Option<Self>> {
+        Ok(Entity::find().filter(Column::Iban.eq(iban)).one(db).await?)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{
+    bank_accounts, contracts, pending_transactions, recurring_transactions, transaction_parties,
+};
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+
+/// An expected change of the balance of a bank account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForecastItem {
+    pub bank_account_id: i64,
+    pub date: Date,
+    /// Negative if the money leaves the bank account.
+    pub amount: i64,
+    pub name: String,
+    pub recurring_transaction_id: Option<i64>,
+    pub contract_id: Option<i64>,
+    pub pending_transaction_id: Option<i64>,
+}
+
+/// The projected balance of a bank account at the end of a day with at least one expected change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForecastDay {
+    pub date: Date,
+    pub balance: i64,
+    pub items: Vec<ForecastItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountForecast {
+    pub bank_account: bank_accounts::Model,
+    pub days: Vec<ForecastDay>,
+}
+
+/// Projects the balances of the bank accounts from now up to `until`.
+///
+/// Every future execution of a recurring transaction is expected, named after its contract if it has one.
+/// Pending transactions are expected on their value date, or today if it is missing or already passed.
+pub async fn forecast(
+    db: &impl ConnectionTrait,
+    bank_accounts: Vec<bank_accounts::Model>,
+    until: DateTimeUtc,
+) -> AppResult<Vec<AccountForecast>> {
+    let now = Utc::now();
+    let bank_account_ids: Vec<i64> = bank_accounts.iter().map(|bank_account| bank_account.id).collect();
+    let parties: HashMap<i64, i64> = transaction_parties::Model::find_all_for_bank_accounts(db, &bank_account_ids)
+        .await?
+        .into_iter()
+        .filter_map(|party| Some((party.id, party.bank_account_id?)))
+        .collect();
+    let party_ids: Vec<i64> = parties.keys().copied().collect();
+
+    let recurring_transactions = recurring_transactions::Model::find_all_for_parties(db, &party_ids).await?;
+    let recurring_transaction_ids: Vec<i64> = recurring_transactions.iter().map(|recurring| recurring.id).collect();
+    let contracts: HashMap<i64, contracts::Model> =
+        contracts::Model::find_all_for_recurring_transactions(db, &recurring_transaction_ids)
+            .await?
+            .into_iter()
+            .map(|contract| (contract.recurring_transaction_id, contract))
+            .collect();
+
+    let mut items = Vec::new();
+    for recurring in recurring_transactions {
+        let occurrences = match recurring.occurrences(now, until) {
+            Ok(occurrences) => occurrences,
+            Err(err) => {
+                warn!(
+                    "Skipping recurring transaction {} with an invalid cron expression: {}",
+                    recurring.id, err
+                );
+                continue;
+            }
+        };
+        let contract = contracts.get(&recurring.id);
+        let name = contract.map_or(&recurring.name, |contract| &contract.name);
+        for occurrence in occurrences {
+            for (bank_account_id, amount) in legs(
+                &parties,
+                recurring.source_id,
+                recurring.destination_id,
+                recurring.amount,
+            ) {
+                items.push(ForecastItem {
+                    bank_account_id,
+                    date: occurrence.date_naive(),
+                    amount,
+                    name: name.clone(),
+                    recurring_transaction_id: Some(recurring.id),
+                    contract_id: contract.map(|contract| contract.id),
+                    pending_transaction_id: None,
+                });
+            }
+        }
+    }
+
+    for pending in pending_transactions::Model::find_all_for_parties(db, &party_ids).await? {
+        let value_date = pending.value_date.map(|value_date| value_date.with_timezone(&Utc));
+        let date = value_date.map_or(now, |value_date| value_date.max(now));
+        if date > until {
+            continue;
+        }
+        for (bank_account_id, amount) in legs(&parties, pending.source_id, pending.destination_id, pending.amount) {
+            items.push(ForecastItem {
+                bank_account_id,
+                date: date.date_naive(),
+                amount,
+                name: pending.name.clone(),
+                recurring_transaction_id: None,
+                contract_id: None,
+                pending_transaction_id: Some(pending.id),
+            });
+        }
+    }
+
+    Ok(project(bank_accounts, items))
+}
+
+/// The own bank accounts a transaction between the parties moves money out of and into, with the signed amount.
+fn legs(
+    parties: &HashMap<i64, i64>,
+    source_id: Option<i64>,
+    destination_id: Option<i64>,
+    amount: i64,
+) -> Vec<(i64, i64)> {
+    let source = source_id
+        .and_then(|id| parties.get(&id))
+        .map(|bank_account_id| (*bank_account_id, -amount));
+    let destination = destination_id
+        .and_then(|id| parties.get(&id))
+        .map(|bank_account_id| (*bank_account_id, amount));
+
+    source.into_iter().chain(destination).collect()
+}
+
+/// Groups the expected changes per bank account and day and adds them up, starting at the current balances.
+pub fn project(bank_accounts: Vec<bank_accounts::Model>, items: Vec<ForecastItem>) -> Vec<AccountForecast> {
+    let mut grouped: HashMap<i64, BTreeMap<Date, Vec<ForecastItem>>> = HashMap::new();
+    for item in items {
+        grouped
+            .entry(item.bank_account_id)
+            .or_default()
+            .entry(item.date)
+            .or_default()
+            .push(item);
+    }
+
+    bank_accounts
+        .into_iter()
+        .map(|bank_account| {
+            let mut balance = bank_account.balance;
+            let days = grouped
+                .remove(&bank_account.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(date, items)| {
+                    balance = items
+                        .iter()
+                        .fold(balance, |balance, item| balance.saturating_add(item.amount));
+                    ForecastDay { date, balance, items }
+                })
+                .collect();
+
+            AccountForecast { bank_account, days }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    fn bank_account(id: i64, balance: i64) -> bank_accounts::Model {
+        bank_accounts::Model {
+            id,
+            currency_id: 1,
+            linked_back_account_id: None,
+            name: format!("Bank account {id}"),
+            description: None,
+            iban: None,
+            balance,
+            original_balance: 0,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    fn item(bank_account_id: i64, day: u32, amount: i64) -> ForecastItem {
+        ForecastItem {
+            bank_account_id,
+            date: Date::from_ymd_opt(2026, 11, day).unwrap(),
+            amount,
+            name: "Item".to_string(),
+            recurring_transaction_id: Some(1),
+            contract_id: None,
+            pending_transaction_id: None,
+        }
+    }
+
+    #[test]
+    fn test_project() {
+        let items = vec![item(1, 15, -300), item(1, 1, 1000), item(1, 15, -900), item(2, 3, 50)];
+
+        let forecasts = project(vec![bank_account(1, 100), bank_account(3, 5)], items);
+        let balances: Vec<Vec<(u32, i64, usize)>> = forecasts
+            .iter()
+            .map(|forecast| {
+                forecast
+                    .days
+                    .iter()
+                    .map(|day| (day.date.day(), day.balance, day.items.len()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(balances, vec![vec![(1, 1100, 1), (15, -100, 2)], vec![]]);
+    }
+}
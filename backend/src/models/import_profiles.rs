@@ -0,0 +1,88 @@
+use super::_entities::import_profiles::{ActiveModel, Column, Entity, Model};
+use crate::controllers::import::ImportProfileParams;
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QueryOrder};
+pub type ImportProfiles = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Name)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        params: &ImportProfileParams,
+    ) -> AppResult<Self> {
+        let mut active_model = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        active_model.apply_params(params)?;
+
+        Ok(active_model.insert(db).await?)
+    }
+
+    pub async fn update_with_params(self, db: &impl ConnectionTrait, params: &ImportProfileParams) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.apply_params(params)?;
+
+        Ok(active_model.update(db).await?)
+    }
+}
+
+impl ActiveModel {
+    fn apply_params(&mut self, params: &ImportProfileParams) -> AppResult<()> {
+        let column_mapping =
+            serde_json::to_value(&params.column_mapping).map_err(|err| AppError::JsonError(err.to_string()))?;
+
+        self.name = Set(params.name.clone());
+        self.column_mapping = Set(column_mapping);
+        self.date_format = Set(params.date_format.clone());
+        self.decimal_separator = Set(params.decimal_separator.clone());
+        self.thousands_separator = Set(params.thousands_separator.clone());
+        self.delimiter = Set(params.delimiter.clone());
+        self.has_header = Set(params.has_header);
+        self.skip_rows = Set(params.skip_rows as i32);
+        self.sign_convention = Set(params.sign_convention.clone());
+        self.updated_at = Set(chrono::Utc::now().into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use super::_entities::invite_codes::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Func;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder};
+
+pub type InviteCodes = Entity;
+
+pub const INVITE_CODE_LENGTH: usize = 16;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub async fn find_all(db: &impl ConnectionTrait) -> AppResult<Vec<Self>> {
+        Ok(Entity::find().order_by_desc(Column::Id).all(db).await?)
+    }
+
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Creates a new invite code.
+    /// Returns the invite code together with its plain value, which is only stored as hash.
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        secret_generator: &SecretGenerator,
+        created_by_id: i64,
+        email: Option<String>,
+        max_uses: i32,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> AppResult<(Self, String)> {
+        let code = secret_generator.generate_token_with_length(INVITE_CODE_LENGTH);
+        let invite_code = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            code_hash: Set(SecretGeneratorInner::hash_token(&code)),
+            created_by_id: Set(Some(created_by_id)),
+            email: Set(email),
+            max_uses: Set(max_uses),
+            uses: Set(0),
+            expires_at: Set(expires_at),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok((invite_code.insert(db).await?, code))
+    }
+
+    /// Counts a use of the invite code for registering the given email address.
+    /// Returns whether the code was valid, i.e. it exists, has not expired, has uses left and,
+    /// if it is restricted to an email address, matches it.
+    pub async fn redeem(db: &impl ConnectionTrait, code: &str, email: &str, now: DateTime<Utc>) -> AppResult<bool> {
+        // A single conditional update, so concurrent registrations can not exceed the usage limit.
+        let result = Entity::update_many()
+            .col_expr(Column::Uses, Expr::col(Column::Uses).add(1))
+            .col_expr(Column::UpdatedAt, Expr::value(DateTimeWithTimeZone::from(now)))
+            .filter(Column::CodeHash.eq(SecretGeneratorInner::hash_token(code.trim())))
+            .filter(Expr::col(Column::Uses).lt(Expr::col(Column::MaxUses)))
+            .filter(
+                Condition::any()
+                    .add(Column::ExpiresAt.is_null())
+                    .add(Column::ExpiresAt.gt(now)),
+            )
+            .filter(
+                Condition::any()
+                    .add(Column::Email.is_null())
+                    .add(Expr::expr(Func::lower(Expr::col(Column::Email))).eq(email.to_lowercase())),
+            )
+            .exec(db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
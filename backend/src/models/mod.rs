@@ -1,4 +1,7 @@
 pub mod _entities;
+pub mod account_deletions;
+pub mod audit_logs;
+pub mod balance_snapshots;
 pub mod bank_accounts;
 pub mod budget_criteria;
 pub mod budget_criteria_bank_accounts;
@@ -8,21 +11,37 @@ pub mod budget_criteria_tags;
 pub mod budget_histories;
 pub mod budgets;
 pub mod categories;
+pub mod category_classifiers;
 pub mod contracts;
 pub mod currencies;
+pub mod exchange_rates;
+pub mod exports;
 pub mod external_bank_account_ibans;
 pub mod external_bank_accounts;
 pub mod file_attachments;
+pub mod forecasts;
+pub mod import_profiles;
 pub mod inactive_contracts;
 pub mod instances;
+pub mod invite_codes;
 pub mod linked_back_accounts;
 pub mod pending_transactions;
+pub mod personal_access_tokens;
+pub mod possible_duplicates;
+pub mod reconciliation_transactions;
+pub mod reconciliations;
+pub mod recovery_codes;
 pub mod recurring_transactions;
+pub mod reports;
 pub mod sessions;
 pub mod taggings;
 pub mod tags;
 pub mod transaction_parties;
+pub mod transaction_rule_tags;
+pub mod transaction_rules;
+pub mod transaction_splits;
 pub mod transaction_templates;
 pub mod transactions;
+pub mod two_factor_challenges;
 pub mod user_permissions;
 pub mod users;
@@ -1,5 +1,7 @@
-use super::_entities::pending_transactions::{ActiveModel, Entity};
+use super::_entities::pending_transactions::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
 use sea_orm::entity::prelude::*;
+use sea_orm::Condition;
 pub type PendingTransactions = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +21,17 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    /// Finds all pending transactions that move money into or out of one of the parties.
+    pub async fn find_all_for_parties(db: &impl ConnectionTrait, party_ids: &[i64]) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::SourceId.is_in(party_ids.iter().copied()))
+                    .add(Column::DestinationId.is_in(party_ids.iter().copied())),
+            )
+            .all(db)
+            .await?)
+    }
+}
@@ -0,0 +1,142 @@
+use super::_entities::personal_access_tokens::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use chrono::{DateTime, FixedOffset, Utc};
+use enumflags2::{bitflags, BitFlags};
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub type PersonalAccessTokens = Entity;
+
+/// Every personal access token starts with this prefix, which tells it apart from the api key of a session.
+pub const ACCESS_TOKEN_PREFIX: &str = "pat_";
+
+/// What a personal access token may be used for.
+/// Login sessions are not restricted by scopes.
+#[bitflags]
+#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum Scope {
+    #[serde(rename = "transactions:read")]
+    TransactionsRead = 0b001,
+    #[serde(rename = "transactions:write")]
+    TransactionsWrite = 0b010,
+    #[serde(rename = "import")]
+    Import = 0b100,
+}
+
+/// The scope bits that handlers can require through `Authenticated`.
+pub mod scopes {
+    use super::Scope;
+
+    pub const TRANSACTIONS_READ: u16 = Scope::TransactionsRead as u16;
+    pub const TRANSACTIONS_WRITE: u16 = Scope::TransactionsWrite as u16;
+    pub const IMPORT: u16 = Scope::Import as u16;
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub fn get_scopes(&self) -> BitFlags<Scope> {
+        BitFlags::from_bits_truncate(self.scopes as u16)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Option<Self>> {
+        Ok(Entity::find_by_id(id).one(db).await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    pub async fn find_by_token(db: &impl ConnectionTrait, token: &str) -> AppResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::TokenHash.eq(SecretGeneratorInner::hash_token(token)))
+            .one(db)
+            .await?)
+    }
+
+    /// Creates a new token for the user.
+    /// Returns the token together with its plain value, which is only stored as hash.
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        secret_generator: &SecretGenerator,
+        user_id: i64,
+        name: String,
+        scopes: BitFlags<Scope>,
+        expires_at: Option<DateTime<FixedOffset>>,
+    ) -> AppResult<(Self, String)> {
+        let token = format!("{ACCESS_TOKEN_PREFIX}{}", secret_generator.generate_token());
+        let access_token = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            name: Set(name),
+            token_hash: Set(SecretGeneratorInner::hash_token(&token)),
+            scopes: Set(scopes.bits() as i32),
+            expires_at: Set(expires_at),
+            last_used_at: Set(None),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok((access_token.insert(db).await?, token))
+    }
+
+    pub async fn update_last_used_at(self, db: &impl ConnectionTrait) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.last_used_at = Set(Some(chrono::Utc::now().into()));
+
+        Ok(active_model.update(db).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_serialization() {
+        assert_eq!(
+            serde_json::to_string(&Scope::TransactionsRead).unwrap(),
+            "\"transactions:read\""
+        );
+        assert_eq!(serde_json::from_str::<Scope>("\"import\"").unwrap(), Scope::Import);
+    }
+}
@@ -0,0 +1,227 @@
+use super::_entities::possible_duplicates::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::sea_orm_active_enums::PossibleDuplicateStatus;
+use crate::models::_entities::{taggings, transactions};
+use crate::models::user_permissions::Permission;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::utils::similarity::name_similarity;
+use enumflags2::BitFlags;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, IntoActiveModel, QueryOrder};
+pub type PossibleDuplicates = Entity;
+
+/// Transactions are only compared with transactions booked at most this many days apart.
+pub const DATE_WINDOW_DAYS: i64 = 3;
+/// Pairs with a lower score are not queued for review.
+pub const MIN_SCORE: i16 = 60;
+/// Without the same counterparty iban, the names have to be at least this similar.
+/// Otherwise every two payments of the same amount on the same day would be suggested.
+pub const MIN_NAME_SIMILARITY: f64 = 0.5;
+
+const AMOUNT_SCORE: i16 = 40;
+const DATE_SCORE: i16 = 30;
+const IBAN_SCORE: i16 = 15;
+const NAME_SCORE: i16 = 15;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Compares the transaction with similar existing transactions
+    /// and queues all likely duplicates for review.
+    ///
+    /// The ignored transactions are never compared. Imports pass all transactions of the batch,
+    /// because rows of the same file are distinct transactions even if they look alike.
+    pub async fn detect(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        transaction: &transactions::Model,
+        ignored_ids: &[i64],
+    ) -> AppResult<Vec<Self>> {
+        let candidates = transaction
+            .find_duplicate_candidates(db, chrono::Duration::days(DATE_WINDOW_DAYS))
+            .await?;
+
+        let mut duplicates = Vec::new();
+        for candidate in candidates {
+            if ignored_ids.contains(&candidate.id) {
+                continue;
+            }
+            let score = score(transaction, &candidate);
+            if score < MIN_SCORE {
+                continue;
+            }
+
+            let duplicate = ActiveModel {
+                id: Set(snowflake_generator.next_id()?),
+                transaction_id: Set(transaction.id),
+                duplicate_id: Set(candidate.id),
+                score: Set(score),
+                status: Set(PossibleDuplicateStatus::Open),
+                created_at: Set(chrono::Utc::now().into()),
+                updated_at: Set(chrono::Utc::now().into()),
+            };
+            duplicates.push(duplicate.insert(db).await?);
+        }
+
+        Ok(duplicates)
+    }
+
+    /// Finds all open pairs where the user can see both transactions.
+    pub async fn find_open_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::Status.eq(PossibleDuplicateStatus::Open))
+            .filter(visible_to(user_id, Permission::Read.into()))
+            .order_by_desc(Column::Score)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Finds an open pair on which the user holds the required permissions for both transactions.
+    pub async fn find_open_by_id_and_user_id(
+        db: &impl ConnectionTrait,
+        id: i64,
+        user_id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::Status.eq(PossibleDuplicateStatus::Open))
+            .filter(visible_to(user_id, required))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Marks the pair as not being a duplicate, so it is not suggested again.
+    pub async fn dismiss(self, db: &impl ConnectionTrait) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = Set(PossibleDuplicateStatus::Dismissed);
+
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Merges both transactions by keeping the richer one (with its tags, category and attachment)
+    /// and deleting the other one. Returns the kept transaction.
+    /// Tags of the deleted transaction are added to the kept one, its category and attachment
+    /// are only taken over if the kept transaction has none.
+    /// A reconciled transaction is never deleted, nor changed, so nothing is taken over if it is kept.
+    pub async fn merge(self, db: &impl ConnectionTrait) -> AppResult<transactions::Model> {
+        let transaction = transactions::Model::find_by_id(db, self.transaction_id)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)?;
+        let duplicate = transactions::Model::find_by_id(db, self.duplicate_id)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)?;
+
+        let keep_duplicate = match (transaction.reconciled_at, duplicate.reconciled_at) {
+            (Some(_), Some(_)) => return Err(AppError::TransactionReconciled()),
+            (Some(_), None) => false,
+            (None, Some(_)) => true,
+            (None, None) => richness(db, &duplicate).await? > richness(db, &transaction).await?,
+        };
+        let (kept, removed) = match keep_duplicate {
+            true => (duplicate, transaction),
+            false => (transaction, duplicate),
+        };
+
+        // Values the user entered on the removed transaction would otherwise get lost.
+        let kept = match kept.reconciled_at {
+            Some(_) => kept,
+            None => {
+                let removed_tags =
+                    taggings::Model::find_for_entity(db, transactions::Entity.table_name(), removed.id).await?;
+                for tagging in removed_tags {
+                    taggings::Model::add(db, tagging.tag_id, transactions::Entity.table_name(), kept.id).await?;
+                }
+                let mut active_model = kept.clone().into_active_model();
+                active_model.category_id = Set(kept.category_id.or(removed.category_id));
+                active_model.file_attachment_id = Set(kept.file_attachment_id.or(removed.file_attachment_id));
+
+                active_model.update(db).await?
+            }
+        };
+
+        // Deleting the transaction also removes this pair (and all other pairs of it).
+        removed.delete_with_balances(db).await?;
+
+        Ok(kept)
+    }
+}
+
+/// Scores how likely two transactions with the same amount are duplicates (0 - 100).
+pub fn score(transaction: &transactions::Model, candidate: &transactions::Model) -> i16 {
+    if transaction.amount != candidate.amount {
+        return 0;
+    }
+
+    let iban_score = match (transaction.counterparty_iban(), candidate.counterparty_iban()) {
+        (Some(a), Some(b)) if a == b => IBAN_SCORE,
+        // Different counterparties are never the same transaction.
+        (Some(_), Some(_)) => return 0,
+        _ => 0,
+    };
+
+    let days = (transaction.effective_date() - candidate.effective_date())
+        .num_days()
+        .abs();
+    let date_score = match days > DATE_WINDOW_DAYS {
+        true => 0,
+        false => DATE_SCORE * (DATE_WINDOW_DAYS - days + 1) as i16 / (DATE_WINDOW_DAYS + 1) as i16,
+    };
+
+    let name_similarity = name_similarity(&transaction.name, &candidate.name).max(
+        match (transaction.counterparty_name(), candidate.counterparty_name()) {
+            (Some(a), Some(b)) => name_similarity(a, b),
+            _ => 0.0,
+        },
+    );
+    if iban_score == 0 && name_similarity < MIN_NAME_SIMILARITY {
+        return 0;
+    }
+    let name_score = (name_similarity * NAME_SCORE as f64).round() as i16;
+
+    AMOUNT_SCORE + date_score + iban_score + name_score
+}
+
+/// How much information a transaction holds. Used to decide which transaction is kept on a merge.
+async fn richness(db: &impl ConnectionTrait, transaction: &transactions::Model) -> AppResult<usize> {
+    let tags = taggings::Model::find_for_entity(db, transactions::Entity.table_name(), transaction.id)
+        .await?
+        .len();
+    let fields = [
+        transaction.booking_date.is_some(),
+        transaction.purpose.is_some(),
+        transaction.note.is_some(),
+        transaction.counterparty_iban().is_some(),
+        transaction.counterparty_name().is_some(),
+    ];
+
+    // Tags, category and attachment are entered by the user and therefore weigh more.
+    Ok(tags * 2
+        + usize::from(transaction.category_id.is_some()) * 2
+        + usize::from(transaction.file_attachment_id.is_some()) * 2
+        + fields.iter().filter(|field| **field).count())
+}
+
+fn visible_to(user_id: i64, required: BitFlags<Permission>) -> Condition {
+    Condition::all()
+        .add(Column::TransactionId.in_subquery(transactions::Model::visible_ids(user_id, required)))
+        .add(Column::DuplicateId.in_subquery(transactions::Model::visible_ids(user_id, required)))
+}
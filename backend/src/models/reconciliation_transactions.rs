@@ -0,0 +1,62 @@
+use super::_entities::reconciliation_transactions::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::ActiveValue::Set;
+pub type ReconciliationTransactions = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_by_reconciliation_id(db: &impl ConnectionTrait, reconciliation_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::ReconciliationId.eq(reconciliation_id))
+            .all(db)
+            .await?)
+    }
+
+    pub async fn create_many(
+        db: &impl ConnectionTrait,
+        reconciliation_id: i64,
+        transaction_ids: &[i64],
+    ) -> AppResult<()> {
+        if transaction_ids.is_empty() {
+            return Ok(());
+        }
+
+        let entries = transaction_ids.iter().map(|transaction_id| ActiveModel {
+            reconciliation_id: Set(reconciliation_id),
+            transaction_id: Set(*transaction_id),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        });
+        Entity::insert_many(entries).exec(db).await?;
+
+        Ok(())
+    }
+
+    /// Builds a sub query that selects the ids of all transactions that were reconciled by one of the given reconciliations.
+    pub fn transaction_ids_for_reconciliations(reconciliation_ids: SelectStatement) -> SelectStatement {
+        Query::select()
+            .column(Column::TransactionId)
+            .from(Entity)
+            .and_where(Column::ReconciliationId.in_subquery(reconciliation_ids))
+            .to_owned()
+    }
+}
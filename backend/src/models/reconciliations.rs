@@ -0,0 +1,195 @@
+use super::_entities::reconciliations::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::{bank_accounts, reconciliation_transactions, transaction_parties, transactions};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Func, Query, SimpleExpr};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder};
+use std::collections::HashMap;
+pub type Reconciliations = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// The not yet reconciled transactions of a bank account up to the end of a statement.
+#[derive(Debug, Clone)]
+pub struct ReconciliationPreview {
+    /// The statement balance of the last reconciliation or, for the first reconciliation,
+    /// the original balance of the bank account.
+    pub opening_balance: i64,
+    pub last_statement_date: Option<DateTimeWithTimeZone>,
+    /// The uncleared transactions (oldest first) with the change they cause to the balance of the bank account.
+    pub uncleared: Vec<(transactions::Model, i64)>,
+}
+
+impl ReconciliationPreview {
+    /// The balance after reconciling all uncleared transactions.
+    pub fn cleared_balance(&self) -> i64 {
+        self.opening_balance + self.uncleared.iter().map(|(_, effect)| effect).sum::<i64>()
+    }
+}
+
+impl Model {
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Returns all reconciliations of the bank account, the latest first.
+    pub async fn find_all_by_bank_account_id(db: &impl ConnectionTrait, bank_account_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .order_by_desc(Column::StatementDate)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_latest_by_bank_account_id(
+        db: &impl ConnectionTrait,
+        bank_account_id: i64,
+    ) -> AppResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .order_by_desc(Column::StatementDate)
+            .order_by_desc(Column::Id)
+            .one(db)
+            .await?)
+    }
+
+    /// Compares the bank account with a statement that ends at the given date.
+    pub async fn preview(
+        db: &impl ConnectionTrait,
+        bank_account: &bank_accounts::Model,
+        statement_date: DateTimeWithTimeZone,
+    ) -> AppResult<ReconciliationPreview> {
+        let latest = Self::find_latest_by_bank_account_id(db, bank_account.id).await?;
+        let party_ids = transaction_parties::Model::find_ids_for_bank_account(db, bank_account.id).await?;
+        let reconciled = reconciliation_transactions::Model::transaction_ids_for_reconciliations(
+            Query::select()
+                .column(Column::Id)
+                .from(Entity)
+                .and_where(Column::BankAccountId.eq(bank_account.id))
+                .to_owned(),
+        );
+        let effective_date = || {
+            SimpleExpr::from(Func::coalesce([
+                Expr::col(transactions::Column::BookingDate).into(),
+                Expr::col(transactions::Column::CreatedAt).into(),
+            ]))
+        };
+
+        let transactions = transactions::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(transactions::Column::SourceId.is_in(party_ids.clone()))
+                    .add(transactions::Column::DestinationId.is_in(party_ids.clone())),
+            )
+            .filter(transactions::Column::Id.not_in_subquery(reconciled))
+            .filter(Expr::expr(effective_date()).lte(statement_date))
+            .order_by_asc(effective_date())
+            .order_by_asc(transactions::Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(ReconciliationPreview {
+            opening_balance: latest
+                .as_ref()
+                .map_or(bank_account.original_balance, |latest| latest.statement_balance),
+            last_statement_date: latest.map(|latest| latest.statement_date),
+            uncleared: transactions
+                .into_iter()
+                .map(|transaction| {
+                    let effect = transaction.balance_effect(&party_ids);
+                    (transaction, effect)
+                })
+                .collect(),
+        })
+    }
+
+    /// Reconciles the bank account with a statement by marking the given uncleared transactions as reconciled.
+    /// They have to change the opening balance (see [`ReconciliationPreview`]) exactly to the statement balance.
+    ///
+    /// Reconciled transactions are locked against edits.
+    pub async fn reconcile(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        bank_account: &bank_accounts::Model,
+        statement_date: DateTimeWithTimeZone,
+        statement_balance: i64,
+        transaction_ids: Vec<i64>,
+    ) -> AppResult<Self> {
+        let preview = Self::preview(db, bank_account, statement_date).await?;
+        if preview.last_statement_date.is_some_and(|date| statement_date < date) {
+            return Err(AppError::OutdatedStatement());
+        }
+
+        let uncleared: HashMap<i64, i64> = preview
+            .uncleared
+            .iter()
+            .map(|(transaction, effect)| (transaction.id, *effect))
+            .collect();
+        let mut transaction_ids = transaction_ids;
+        transaction_ids.sort_unstable();
+        transaction_ids.dedup();
+
+        let mut balance = preview.opening_balance;
+        for transaction_id in &transaction_ids {
+            balance += uncleared.get(transaction_id).ok_or_else(AppError::EntityNotFound)?;
+        }
+        if balance != statement_balance {
+            return Err(AppError::ReconciliationMismatch());
+        }
+
+        let reconciliation = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            bank_account_id: Set(bank_account.id),
+            statement_date: Set(statement_date),
+            statement_balance: Set(statement_balance),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(db)
+        .await?;
+        reconciliation_transactions::Model::create_many(db, reconciliation.id, &transaction_ids).await?;
+
+        // A transfer that was already reconciled with the other bank account keeps its first date.
+        transactions::Entity::update_many()
+            .col_expr(transactions::Column::ReconciledAt, Expr::value(chrono::Utc::now()))
+            .col_expr(transactions::Column::UpdatedAt, Expr::value(chrono::Utc::now()))
+            .filter(transactions::Column::Id.is_in(transaction_ids))
+            .filter(transactions::Column::ReconciledAt.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(reconciliation)
+    }
+
+    pub async fn transaction_ids(&self, db: &impl ConnectionTrait) -> AppResult<Vec<i64>> {
+        Ok(
+            reconciliation_transactions::Model::find_by_reconciliation_id(db, self.id)
+                .await?
+                .into_iter()
+                .map(|entry| entry.transaction_id)
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use super::_entities::recovery_codes::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+
+pub type RecoveryCodes = Entity;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+pub const RECOVERY_CODE_LENGTH: usize = 12;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Replaces all recovery codes of the user with new ones.
+    /// Returns the plain codes, which are only stored as hash.
+    pub async fn replace_for_user(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        secret_generator: &SecretGenerator,
+        user_id: i64,
+    ) -> AppResult<Vec<String>> {
+        Self::delete_all_by_user_id(db, user_id).await?;
+
+        let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        let mut models = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            let code = secret_generator.generate_token_with_length(RECOVERY_CODE_LENGTH);
+            models.push(ActiveModel {
+                id: Set(snowflake_generator.next_id()?),
+                user_id: Set(user_id),
+                code_hash: Set(SecretGeneratorInner::hash_token(&code)),
+                used_at: Set(None),
+                created_at: Set(chrono::Utc::now().into()),
+                updated_at: Set(chrono::Utc::now().into()),
+            });
+            codes.push(code);
+        }
+        Entity::insert_many(models).exec(db).await?;
+
+        Ok(codes)
+    }
+
+    /// Marks the unused recovery code of the user as used.
+    /// Returns whether the code was valid.
+    pub async fn redeem(db: &impl ConnectionTrait, user_id: i64, code: &str) -> AppResult<bool> {
+        // A single conditional update, so a code can not be redeemed twice by concurrent logins.
+        let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+        let result = Entity::update_many()
+            .col_expr(Column::UsedAt, Expr::value(now))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::CodeHash.eq(SecretGeneratorInner::hash_token(code.trim())))
+            .filter(Column::UsedAt.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn count_unused_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<u64> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::UsedAt.is_null())
+            .count(db)
+            .await?)
+    }
+
+    pub async fn delete_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<u64> {
+        Ok(Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?
+            .rows_affected)
+    }
+}
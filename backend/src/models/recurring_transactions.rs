@@ -1,7 +1,15 @@
-use super::_entities::recurring_transactions::{ActiveModel, Entity};
+use super::_entities::recurring_transactions::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use chrono::Utc;
+use croner::errors::CronError;
+use croner::Cron;
 use sea_orm::entity::prelude::*;
+use sea_orm::Condition;
 pub type RecurringTransactions = Entity;
 
+/// The cron expression of a recurring transaction is expanded into at most this many executions at once.
+pub const MAX_OCCURRENCES: usize = 1000;
+
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
     // extend activemodel below (keep comment for generators)
@@ -19,3 +27,97 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    /// Finds all recurring transactions that move money into or out of one of the parties.
+    pub async fn find_all_for_parties(db: &impl ConnectionTrait, party_ids: &[i64]) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::SourceId.is_in(party_ids.iter().copied()))
+                    .add(Column::DestinationId.is_in(party_ids.iter().copied())),
+            )
+            .all(db)
+            .await?)
+    }
+
+    /// Expands the cron expression into all executions after `after` up to `until` (inclusive).
+    /// Executions up to the last one that already happened are skipped.
+    ///
+    /// The expression may contain seconds. At most [`MAX_OCCURRENCES`] executions are returned.
+    pub fn occurrences(&self, after: DateTimeUtc, until: DateTimeUtc) -> Result<Vec<DateTimeUtc>, CronError> {
+        let cron = Cron::new(&self.cron).with_seconds_optional().parse()?;
+
+        let mut time = match self.last_executed_at {
+            Some(last_executed_at) => after.max(last_executed_at.with_timezone(&Utc)),
+            None => after,
+        };
+        let mut occurrences = Vec::new();
+        while occurrences.len() < MAX_OCCURRENCES {
+            time = cron.find_next_occurrence(&time, false)?;
+            if time > until {
+                break;
+            }
+            occurrences.push(time);
+        }
+
+        Ok(occurrences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::_entities::sea_orm_active_enums::TransactionType;
+    use chrono::TimeZone;
+
+    fn recurring_transaction(cron: &str, last_executed_at: Option<DateTimeUtc>) -> Model {
+        Model {
+            id: 1,
+            source_id: Some(1),
+            destination_id: None,
+            currency_id: 1,
+            category_id: None,
+            file_attachment_id: None,
+            source_name: None,
+            source_iban: None,
+            destination_name: Some("Landlord".to_string()),
+            destination_iban: None,
+            r#type: TransactionType::Expense,
+            amount: 80000,
+            name: "Rent".to_string(),
+            purpose: None,
+            note: None,
+            cron: cron.to_string(),
+            executions_per_year: 12.0,
+            last_executed_at: last_executed_at.map(Into::into),
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let date = |month, day| Utc.with_ymd_and_hms(2026, month, day, 0, 0, 0).unwrap();
+
+        let rent = recurring_transaction("0 0 1 * *", None);
+        assert_eq!(
+            rent.occurrences(date(10, 19), date(12, 31)).unwrap(),
+            vec![date(11, 1), date(12, 1)]
+        );
+
+        // The execution of November already happened.
+        let rent = recurring_transaction("0 0 1 * *", Some(date(11, 1)));
+        assert_eq!(rent.occurrences(date(10, 19), date(12, 1)).unwrap(), vec![date(12, 1)]);
+
+        let every_second = recurring_transaction("* * * * * *", None);
+        assert_eq!(
+            every_second.occurrences(date(10, 19), date(12, 31)).unwrap().len(),
+            MAX_OCCURRENCES
+        );
+
+        assert!(recurring_transaction("not a cron", None)
+            .occurrences(date(10, 19), date(12, 31))
+            .is_err());
+    }
+}
@@ -0,0 +1,510 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{
+    bank_accounts, categories, transaction_parties, transaction_splits, transactions, user_permissions,
+};
+use crate::models::exchange_rates::CurrencyConverter;
+use crate::models::user_permissions::Permission;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Alias, Expr, Func, SelectStatement, SimpleExpr};
+use sea_orm::{Condition, FromQueryResult, JoinType, QueryOrder, QueryResult, QuerySelect, Select, TryGetable};
+use std::collections::{BTreeMap, HashMap};
+
+/// Restricts the transactions of a report.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportFilter {
+    pub user_id: i64,
+    /// Only counts money that flows into or out of these bank accounts.
+    /// All bank accounts the user can read are used if empty.
+    pub bank_account_ids: Vec<i64>,
+    /// Matches transactions with at least one of the tags, also on one of their split lines.
+    pub tag_ids: Vec<i64>,
+    /// Matches the booking date or, if the transaction was not booked yet, the date it was created at.
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+}
+
+/// The incoming and outgoing money of a group of transactions, both as positive amounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub income: i64,
+    pub expense: i64,
+    /// The number of transactions (or split lines).
+    pub count: i64,
+}
+
+impl Totals {
+    fn add(&mut self, other: Self) {
+        self.income = self.income.saturating_add(other.income);
+        self.expense = self.expense.saturating_add(other.expense);
+        self.count += other.count;
+    }
+
+    fn convert(&self, converter: &CurrencyConverter, currency_id: i64) -> Option<Self> {
+        Some(Self {
+            income: converter.convert(self.income, currency_id)?,
+            expense: converter.convert(self.expense, currency_id)?,
+            count: self.count,
+        })
+    }
+}
+
+/// The totals of a group (e.g. a month) in a single currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow<K> {
+    pub key: K,
+    pub currency_id: i64,
+    pub totals: Totals,
+}
+
+/// The totals of a category, once for the category itself and once including all of its children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryReportRow {
+    pub category_id: Option<i64>,
+    pub currency_id: i64,
+    pub own: Totals,
+    pub total: Totals,
+}
+
+/// The sum of the balances of all bank accounts at the end of a day in a single currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetWorthRow {
+    pub date: Date,
+    pub currency_id: i64,
+    pub balance: i64,
+}
+
+impl<K: TryGetable> FromQueryResult for ReportRow<K> {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            key: res.try_get(pre, "key")?,
+            currency_id: res.try_get(pre, "currency_id")?,
+            totals: Totals {
+                income: res.try_get(pre, "income")?,
+                expense: res.try_get(pre, "expense")?,
+                count: res.try_get(pre, "count")?,
+            },
+        })
+    }
+}
+
+/// Sums up the income and expenses per month.
+pub async fn cash_flow(
+    db: &impl ConnectionTrait,
+    filter: &ReportFilter,
+) -> AppResult<Vec<ReportRow<DateTimeWithTimeZone>>> {
+    let month = Expr::cust_with_expr("date_trunc('month', $1)", effective_date());
+
+    aggregate(
+        db,
+        filter_transactions(filter),
+        filter,
+        month,
+        Expr::col((transactions::Entity, transactions::Column::Amount)).into(),
+    )
+    .await
+}
+
+/// Sums up the income and expenses per category.
+/// Split transactions are counted per split line.
+pub async fn category_spending(
+    db: &impl ConnectionTrait,
+    filter: &ReportFilter,
+) -> AppResult<Vec<ReportRow<Option<i64>>>> {
+    let query = filter_transactions(filter).join(JoinType::LeftJoin, transactions::Relation::TransactionSplits.def());
+    let category_id = Func::coalesce([
+        Expr::col((transaction_splits::Entity, transaction_splits::Column::CategoryId)).into(),
+        Expr::col((transactions::Entity, transactions::Column::CategoryId)).into(),
+    ]);
+    let amount = Func::coalesce([
+        Expr::col((transaction_splits::Entity, transaction_splits::Column::Amount)).into(),
+        Expr::col((transactions::Entity, transactions::Column::Amount)).into(),
+    ]);
+
+    aggregate(db, query, filter, category_id.into(), amount.into()).await
+}
+
+/// Sums up the income and expenses per counterparty name.
+pub async fn counterparties(
+    db: &impl ConnectionTrait,
+    filter: &ReportFilter,
+) -> AppResult<Vec<ReportRow<Option<String>>>> {
+    let name = Expr::case(
+        outgoing(filter),
+        Expr::col((transactions::Entity, transactions::Column::DestinationName)),
+    )
+    .finally(Expr::col((transactions::Entity, transactions::Column::SourceName)));
+
+    aggregate(
+        db,
+        filter_transactions(filter),
+        filter,
+        name.into(),
+        Expr::col((transactions::Entity, transactions::Column::Amount)).into(),
+    )
+    .await
+}
+
+/// Sums up the changes to the balance of the bank account per day, in the order of the days.
+/// Only days after `after` (exclusive) up to `until` (inclusive) are included.
+pub async fn daily_balance_changes(
+    db: &impl ConnectionTrait,
+    bank_account_id: i64,
+    after: Option<Date>,
+    until: Date,
+) -> AppResult<Vec<(Date, i64)>> {
+    let parties = || transaction_parties::Model::ids_for_bank_account(bank_account_id);
+    let day = || Func::cast_as(effective_date(), Alias::new("date"));
+    let received = Expr::case(
+        transactions::Column::DestinationId.in_subquery(parties()),
+        Func::coalesce([
+            Expr::col((transactions::Entity, transactions::Column::DestinationAmount)).into(),
+            Expr::col((transactions::Entity, transactions::Column::Amount)).into(),
+        ]),
+    )
+    .finally(0);
+    let sent = Expr::case(
+        transactions::Column::SourceId.in_subquery(parties()),
+        Expr::col((transactions::Entity, transactions::Column::Amount)),
+    )
+    .finally(0);
+
+    let mut query = transactions::Entity::find()
+        .filter(
+            Condition::any()
+                .add(transactions::Column::SourceId.in_subquery(parties()))
+                .add(transactions::Column::DestinationId.in_subquery(parties())),
+        )
+        .filter(Expr::expr(day()).lte(until));
+    if let Some(after) = after {
+        query = query.filter(Expr::expr(day()).gt(after));
+    }
+
+    Ok(query
+        .select_only()
+        .column_as(day(), "day")
+        .column_as(
+            Func::cast_as(Func::sum(Expr::expr(received).sub(sent)), Alias::new("bigint")),
+            "change",
+        )
+        .group_by(Expr::col(Alias::new("day")))
+        .order_by_asc(Expr::col(Alias::new("day")))
+        .into_tuple::<(Date, i64)>()
+        .all(db)
+        .await?)
+}
+
+/// Groups the transactions by the key and their currency and sums up the incoming and outgoing amounts.
+async fn aggregate<K: TryGetable>(
+    db: &impl ConnectionTrait,
+    query: Select<transactions::Entity>,
+    filter: &ReportFilter,
+    key: SimpleExpr,
+    amount: SimpleExpr,
+) -> AppResult<Vec<ReportRow<K>>> {
+    let sum_if = |condition: Condition| -> SimpleExpr {
+        Func::cast_as(
+            Func::sum(Expr::case(condition, amount.clone()).finally(0)),
+            Alias::new("bigint"),
+        )
+        .into()
+    };
+
+    // The key is grouped by its alias, as the expression may contain parameters.
+    Ok(query
+        .select_only()
+        .column_as(key, "key")
+        .column_as(transactions::Column::CurrencyId, "currency_id")
+        .column_as(sum_if(incoming(filter)), "income")
+        .column_as(sum_if(outgoing(filter)), "expense")
+        .column_as(
+            Func::count(Expr::col((transactions::Entity, transactions::Column::Id))),
+            "count",
+        )
+        .group_by(Expr::col(Alias::new("key")))
+        .group_by(transactions::Column::CurrencyId)
+        .into_model::<ReportRow<K>>()
+        .all(db)
+        .await?)
+}
+
+/// All transactions that move money into or out of the bank accounts of the filter.
+/// Transfers between two of these bank accounts are skipped, as they do not change the total.
+fn filter_transactions(filter: &ReportFilter) -> Select<transactions::Entity> {
+    let mut query = transactions::Entity::find().filter(Condition::any().add(incoming(filter)).add(outgoing(filter)));
+
+    if !filter.tag_ids.is_empty() {
+        query = query.filter(transactions::Model::tagged_with(filter.tag_ids.clone()));
+    }
+    let date = || Expr::expr(effective_date());
+    if let Some(from) = filter.from {
+        query = query.filter(date().gte(from));
+    }
+    if let Some(to) = filter.to {
+        query = query.filter(date().lte(to));
+    }
+
+    query
+}
+
+fn effective_date() -> SimpleExpr {
+    Func::coalesce([
+        Expr::col((transactions::Entity, transactions::Column::BookingDate)).into(),
+        Expr::col((transactions::Entity, transactions::Column::CreatedAt)).into(),
+    ])
+    .into()
+}
+
+/// Selects the parties of all bank accounts of the filter.
+fn own_parties(filter: &ReportFilter) -> SelectStatement {
+    let mut bank_account_ids = user_permissions::Model::accessible_entity_ids(
+        filter.user_id,
+        bank_accounts::Entity.table_name(),
+        Permission::Read.into(),
+    );
+    if !filter.bank_account_ids.is_empty() {
+        bank_account_ids.and_where(user_permissions::Column::EntityId.is_in(filter.bank_account_ids.clone()));
+    }
+
+    transaction_parties::Model::ids_for_bank_accounts(bank_account_ids)
+}
+
+fn incoming(filter: &ReportFilter) -> Condition {
+    Condition::all()
+        .add(transactions::Column::DestinationId.in_subquery(own_parties(filter)))
+        .add(
+            Condition::any()
+                .add(transactions::Column::SourceId.is_null())
+                .add(transactions::Column::SourceId.not_in_subquery(own_parties(filter))),
+        )
+}
+
+fn outgoing(filter: &ReportFilter) -> Condition {
+    Condition::all()
+        .add(transactions::Column::SourceId.in_subquery(own_parties(filter)))
+        .add(
+            Condition::any()
+                .add(transactions::Column::DestinationId.is_null())
+                .add(transactions::Column::DestinationId.not_in_subquery(own_parties(filter))),
+        )
+}
+
+/// Converts the totals into the currency of the converter and merges rows that end up with the same key.
+/// Rows in a currency without a known exchange rate keep their currency.
+pub fn convert_rows<K: Ord + Clone>(
+    rows: Vec<ReportRow<K>>,
+    converter: Option<&CurrencyConverter>,
+) -> Vec<ReportRow<K>> {
+    let mut merged: BTreeMap<(K, i64), Totals> = BTreeMap::new();
+    for row in rows {
+        let converted = converter.and_then(|converter| {
+            let totals = row.totals.convert(converter, row.currency_id)?;
+            Some((converter.currency.id, totals))
+        });
+        let (currency_id, totals) = converted.unwrap_or((row.currency_id, row.totals));
+        merged.entry((row.key, currency_id)).or_default().add(totals);
+    }
+
+    merged
+        .into_iter()
+        .map(|((key, currency_id), totals)| ReportRow {
+            key,
+            currency_id,
+            totals,
+        })
+        .collect()
+}
+
+/// Adds the totals of every category to all of its parents.
+/// Categories without any transactions in their subtree are left out.
+pub fn roll_up(categories: &[categories::Model], rows: Vec<ReportRow<Option<i64>>>) -> Vec<CategoryReportRow> {
+    let parents: HashMap<i64, Option<i64>> = categories
+        .iter()
+        .map(|category| (category.id, category.parent_id))
+        .collect();
+
+    let mut report = BTreeMap::new();
+    for row in rows {
+        let category = report_entry(&mut report, row.key, row.currency_id);
+        category.own.add(row.totals);
+        category.total.add(row.totals);
+
+        // The visited list guards against cycles in the tree.
+        let mut visited = vec![];
+        let mut parent_id = row.key.and_then(|id| parents.get(&id).copied().flatten());
+        while let Some(id) = parent_id.filter(|id| !visited.contains(id)) {
+            visited.push(id);
+            report_entry(&mut report, Some(id), row.currency_id)
+                .total
+                .add(row.totals);
+            parent_id = parents.get(&id).copied().flatten();
+        }
+    }
+
+    report.into_values().collect()
+}
+
+/// Sums up the daily balances of multiple bank accounts, given with the currency of each bank account.
+/// Balances in a currency without a known exchange rate keep their currency.
+pub fn sum_balances(balances: Vec<(i64, Vec<(Date, i64)>)>, converter: Option<&CurrencyConverter>) -> Vec<NetWorthRow> {
+    let mut sums: BTreeMap<(Date, i64), i64> = BTreeMap::new();
+    for (currency_id, daily_balances) in balances {
+        for (date, balance) in daily_balances {
+            let converted = converter.and_then(|converter| {
+                let balance = converter.convert(balance, currency_id)?;
+                Some((converter.currency.id, balance))
+            });
+            let (currency_id, balance) = converted.unwrap_or((currency_id, balance));
+            let sum = sums.entry((date, currency_id)).or_default();
+            *sum = sum.saturating_add(balance);
+        }
+    }
+
+    sums.into_iter()
+        .map(|((date, currency_id), balance)| NetWorthRow {
+            date,
+            currency_id,
+            balance,
+        })
+        .collect()
+}
+
+fn report_entry(
+    report: &mut BTreeMap<(Option<i64>, i64), CategoryReportRow>,
+    category_id: Option<i64>,
+    currency_id: i64,
+) -> &mut CategoryReportRow {
+    report
+        .entry((category_id, currency_id))
+        .or_insert_with(|| CategoryReportRow {
+            category_id,
+            currency_id,
+            own: Totals::default(),
+            total: Totals::default(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::_entities::currencies;
+
+    fn category(id: i64, parent_id: Option<i64>) -> categories::Model {
+        categories::Model {
+            id,
+            parent_id,
+            user_id: Some(1),
+            name: format!("Category {id}"),
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    fn row<K>(key: K, currency_id: i64, income: i64, expense: i64) -> ReportRow<K> {
+        ReportRow {
+            key,
+            currency_id,
+            totals: Totals {
+                income,
+                expense,
+                count: 1,
+            },
+        }
+    }
+
+    fn currency(id: i64) -> currencies::Model {
+        currencies::Model {
+            id,
+            user_id: None,
+            name: format!("Currency {id}"),
+            symbol: "$".to_string(),
+            iso_code: None,
+            decimal_places: 2,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    /// Converts from currency 2 into currency 1 at a rate of 2, currency 3 has no rate.
+    fn converter() -> CurrencyConverter {
+        let rate = crate::models::_entities::exchange_rates::Model {
+            id: 1,
+            user_id: 1,
+            source_currency_id: 2,
+            target_currency_id: 1,
+            rate: 2.0,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        };
+        CurrencyConverter::new(currency(1), &[currency(1), currency(2), currency(3)], &[rate])
+    }
+
+    #[test]
+    fn test_convert_rows() {
+        let converter = converter();
+
+        let rows = vec![
+            row("a", 1, 100, 50),
+            row("a", 2, 10, 0),
+            row("a", 3, 7, 0),
+            row("b", 2, 0, 5),
+        ];
+        assert_eq!(
+            convert_rows(rows.clone(), Some(&converter)),
+            vec![
+                ReportRow {
+                    key: "a",
+                    currency_id: 1,
+                    totals: Totals {
+                        income: 120,
+                        expense: 50,
+                        count: 2
+                    }
+                },
+                row("a", 3, 7, 0),
+                row("b", 1, 0, 10),
+            ]
+        );
+        assert_eq!(convert_rows(rows.clone(), None).len(), rows.len());
+    }
+
+    #[test]
+    fn test_sum_balances() {
+        let day = |day| Date::from_ymd_opt(2026, 10, day).unwrap();
+        let balances = vec![
+            (1, vec![(day(1), 100), (day(2), 150)]),
+            (2, vec![(day(1), 10), (day(2), -20)]),
+            (3, vec![(day(1), 7), (day(2), 7)]),
+        ];
+
+        let rows = sum_balances(balances, Some(&converter()));
+        let sums: Vec<(Date, i64, i64)> = rows
+            .iter()
+            .map(|row| (row.date, row.currency_id, row.balance))
+            .collect();
+        assert_eq!(
+            sums,
+            vec![(day(1), 1, 120), (day(1), 3, 7), (day(2), 1, 110), (day(2), 3, 7)]
+        );
+    }
+
+    #[test]
+    fn test_roll_up() {
+        let categories = vec![
+            category(1, None),
+            category(2, Some(1)),
+            category(3, Some(2)),
+            category(4, None),
+        ];
+        let rows = vec![row(Some(3), 1, 0, 30), row(Some(1), 1, 0, 10), row(None, 1, 5, 0)];
+
+        let report = roll_up(&categories, rows);
+        let totals: Vec<(Option<i64>, i64, i64)> = report
+            .iter()
+            .map(|row| (row.category_id, row.own.expense, row.total.expense))
+            .collect();
+        assert_eq!(
+            totals,
+            vec![(None, 0, 0), (Some(1), 10, 40), (Some(2), 0, 30), (Some(3), 30, 30)]
+        );
+    }
+}
@@ -1,16 +1,21 @@
 use super::_entities::sessions::{ActiveModel, Column, Entity};
-use crate::controllers::session::CreateSessionParams;
 use crate::error::app_error::{AppError, AppResult};
 use crate::middlewares::authentication::Authenticate;
 use crate::models::_entities::sessions;
 use crate::models::users;
-use crate::services::secret_generator::SecretGenerator;
+use crate::services::custom_config::SessionConfig;
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
 use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{Page, Pagination};
+use crate::utils::context::AdditionalAppContextMethods;
 use crate::workers::session_used::{SessionUsedWorker, SessionUsedWorkerArgs};
+use chrono::{DateTime, Duration, Utc};
 use loco_rs::app::AppContext;
 use loco_rs::prelude::BackgroundWorker;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Func;
 use sea_orm::ActiveValue::Set;
+use sea_orm::Condition;
 
 pub type Sessions = Entity;
 
@@ -38,6 +43,11 @@ impl Authenticate for sessions::Model {
             .await?
             .ok_or_else(AppError::InvalidBearerToken)?;
 
+        let config = ctx.get_custom_config().await?;
+        if session.is_expired(&config.session, Utc::now()) {
+            return Err(AppError::SessionExpired());
+        }
+
         SessionUsedWorker::perform_later(ctx, SessionUsedWorkerArgs { session_id: session.id }).await?;
 
         Ok(session)
@@ -45,25 +55,29 @@ impl Authenticate for sessions::Model {
 }
 
 impl sessions::Model {
+    /// Creates a new session for the user.
+    /// Returns the session together with its plain api key, which is only stored as hash.
     pub async fn create_session(
         db: &DatabaseConnection,
         snowflake_generator: &SnowflakeGenerator,
         secret_generator: &SecretGenerator,
         user: &users::Model,
-        params: &CreateSessionParams,
-    ) -> AppResult<Self> {
+        name: Option<String>,
+        user_agent: Option<String>,
+    ) -> AppResult<(Self, String)> {
+        let api_key = secret_generator.generate_token();
         let session = sessions::ActiveModel {
             id: Set(snowflake_generator.next_id()?),
             user_id: Set(user.id),
-            api_key: Set(secret_generator.generate_token()),
-            name: Set(params.name.clone()),
-            user_agent: Set(params.user_agent.clone()),
+            api_key_hash: Set(SecretGeneratorInner::hash_token(&api_key)),
+            name: Set(name),
+            user_agent: Set(user_agent),
             last_accessed_at: Set(None),
             created_at: Set(chrono::Utc::now().into()),
             updated_at: Set(chrono::Utc::now().into()),
         };
 
-        Ok(session.insert(db).await?)
+        Ok((session.insert(db).await?, api_key))
     }
 
     pub async fn find_by_id(db: &DatabaseConnection, id: i64) -> AppResult<Option<Self>> {
@@ -71,7 +85,78 @@ impl sessions::Model {
     }
 
     pub async fn find_by_token(db: &DatabaseConnection, token: &str) -> AppResult<Option<Self>> {
-        Ok(Entity::find().filter(Column::ApiKey.eq(token)).one(db).await?)
+        Ok(Entity::find()
+            .filter(Column::ApiKeyHash.eq(SecretGeneratorInner::hash_token(token)))
+            .one(db)
+            .await?)
+    }
+
+    /// Loads a page of the sessions of the user.
+    pub async fn find_page_by_user_id(
+        db: &DatabaseConnection,
+        user_id: i64,
+        pagination: &Pagination,
+    ) -> AppResult<Page<Self>> {
+        pagination
+            .load(db, Entity::find().filter(Column::UserId.eq(user_id)))
+            .await
+    }
+
+    pub async fn find_by_id_and_user_id(db: &DatabaseConnection, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    /// Whether the session outlived its lifetime or was not used for longer than the idle timeout.
+    pub fn is_expired(&self, config: &SessionConfig, now: DateTime<Utc>) -> bool {
+        let last_used_at = self.last_accessed_at.unwrap_or(self.created_at);
+
+        config
+            .lifetime_hours
+            .is_some_and(|hours| self.created_at + Duration::hours(hours) <= now)
+            || config
+                .idle_timeout_hours
+                .is_some_and(|hours| last_used_at + Duration::hours(hours) <= now)
+    }
+
+    /// Deletes all sessions that are expired according to the config.
+    /// Returns the number of deleted sessions.
+    pub async fn delete_expired(db: &DatabaseConnection, config: &SessionConfig, now: DateTime<Utc>) -> AppResult<u64> {
+        let mut expired = Condition::any();
+        if let Some(hours) = config.lifetime_hours {
+            expired = expired.add(Column::CreatedAt.lte(now - Duration::hours(hours)));
+        }
+        if let Some(hours) = config.idle_timeout_hours {
+            let last_used_at = Func::coalesce([
+                Expr::col(Column::LastAccessedAt).into(),
+                Expr::col(Column::CreatedAt).into(),
+            ]);
+            expired = expired.add(Expr::expr(last_used_at).lte(now - Duration::hours(hours)));
+        }
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        Ok(Entity::delete_many().filter(expired).exec(db).await?.rows_affected)
+    }
+
+    /// Revokes all sessions of the user, except for the given one.
+    /// Returns the number of revoked sessions.
+    pub async fn revoke_all_by_user_id(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        except_session_id: Option<i64>,
+    ) -> AppResult<u64> {
+        let mut query = Entity::delete_many().filter(Column::UserId.eq(user_id));
+        if let Some(except_session_id) = except_session_id {
+            query = query.filter(Column::Id.ne(except_session_id));
+        }
+
+        Ok(query.exec(db).await?.rows_affected)
     }
 }
 
@@ -81,4 +166,50 @@ impl sessions::ActiveModel {
 
         Ok(self.update(db).await?)
     }
+
+    pub async fn rename(mut self, db: &DatabaseConnection, name: Option<String>) -> AppResult<sessions::Model> {
+        self.name = Set(name);
+
+        Ok(self.update(db).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(created_hours_ago: i64, accessed_hours_ago: Option<i64>, now: DateTime<Utc>) -> sessions::Model {
+        sessions::Model {
+            id: 1,
+            user_id: 1,
+            api_key_hash: "hash".to_string(),
+            name: None,
+            user_agent: None,
+            last_accessed_at: accessed_hours_ago.map(|hours| (now - Duration::hours(hours)).into()),
+            created_at: (now - Duration::hours(created_hours_ago)).into(),
+            updated_at: now.into(),
+        }
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let now = Utc::now();
+        let config = SessionConfig {
+            lifetime_hours: Some(100),
+            idle_timeout_hours: Some(10),
+        };
+
+        assert!(!session(50, Some(5), now).is_expired(&config, now));
+        assert!(session(100, Some(1), now).is_expired(&config, now));
+        assert!(session(50, Some(10), now).is_expired(&config, now));
+        // Sessions that were never used are idle since their creation.
+        assert!(!session(5, None, now).is_expired(&config, now));
+        assert!(session(20, None, now).is_expired(&config, now));
+
+        let unlimited = SessionConfig {
+            lifetime_hours: None,
+            idle_timeout_hours: None,
+        };
+        assert!(!session(10_000, None, now).is_expired(&unlimited, now));
+    }
 }
@@ -1,5 +1,9 @@
-use super::_entities::taggings::{ActiveModel, Entity};
+use super::_entities::taggings::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{OnConflict, Query, SelectStatement};
+use sea_orm::ActiveValue::Set;
+use std::collections::HashMap;
 pub type Taggings = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +23,90 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_for_entity(db: &impl ConnectionTrait, entity_type: &str, entity_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.eq(entity_id))
+            .all(db)
+            .await?)
+    }
+
+    /// Taggings reference their entity without a foreign key, so they have to be removed manually.
+    pub async fn delete_for_entity(db: &impl ConnectionTrait, entity_type: &str, entity_id: i64) -> AppResult<()> {
+        Entity::delete_many()
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.eq(entity_id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_for_entities(
+        db: &impl ConnectionTrait,
+        entity_type: &str,
+        entity_ids: Vec<i64>,
+    ) -> AppResult<()> {
+        Entity::delete_many()
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.is_in(entity_ids))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the tag ids of each of the given entities.
+    pub async fn find_tag_ids_for_entities(
+        db: &impl ConnectionTrait,
+        entity_type: &str,
+        entity_ids: Vec<i64>,
+    ) -> AppResult<HashMap<i64, Vec<i64>>> {
+        let taggings = Entity::find()
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.is_in(entity_ids))
+            .all(db)
+            .await?;
+
+        let mut tag_ids: HashMap<i64, Vec<i64>> = HashMap::new();
+        for tagging in taggings {
+            tag_ids.entry(tagging.entity_id).or_default().push(tagging.tag_id);
+        }
+
+        Ok(tag_ids)
+    }
+
+    /// Tags the entity. Returns `false` if the entity was already tagged with the tag.
+    /// Builds a sub query that selects the ids of all entities of the given type with at least one of the tags.
+    pub fn entity_ids_with_tags(entity_type: &str, tag_ids: Vec<i64>) -> SelectStatement {
+        Query::select()
+            .column(Column::EntityId)
+            .from(Entity)
+            .and_where(Column::EntityType.eq(entity_type))
+            .and_where(Column::TagId.is_in(tag_ids))
+            .to_owned()
+    }
+
+    pub async fn add(db: &impl ConnectionTrait, tag_id: i64, entity_type: &str, entity_id: i64) -> AppResult<bool> {
+        let tagging = ActiveModel {
+            tag_id: Set(tag_id),
+            entity_type: Set(entity_type.to_string()),
+            entity_id: Set(entity_id),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        let inserted = Entity::insert(tagging)
+            .on_conflict(
+                OnConflict::columns([Column::TagId, Column::EntityType, Column::EntityId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+
+        Ok(inserted > 0)
+    }
+}
@@ -1,5 +1,7 @@
-use super::_entities::tags::{ActiveModel, Entity};
+use super::_entities::tags::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
 use sea_orm::entity::prelude::*;
+use sea_orm::QueryOrder;
 pub type Tags = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +21,41 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_ids_and_user_id(
+        db: &impl ConnectionTrait,
+        ids: Vec<i64>,
+        user_id: i64,
+    ) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::Id.is_in(ids))
+            .filter(Column::UserId.eq(user_id))
+            .all(db)
+            .await?)
+    }
+
+    /// Ensures that all given tags exist and belong to the user.
+    pub async fn ensure_owned_by_user(db: &impl ConnectionTrait, ids: Vec<i64>, user_id: i64) -> AppResult<()> {
+        let mut ids = ids;
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let expected = ids.len();
+        match Self::find_by_ids_and_user_id(db, ids, user_id).await?.len() == expected {
+            true => Ok(()),
+            false => Err(AppError::EntityNotFound()),
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use super::_entities::transaction_parties::{ActiveModel, Entity};
+use super::_entities::transaction_parties::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use crate::services::snowflake_generator::SnowflakeGenerator;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::ActiveValue::Set;
 pub type TransactionParties = Entity;
 
 #[async_trait::async_trait]
@@ -19,3 +23,93 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub async fn find_or_create_for_bank_account(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        bank_account_id: i64,
+    ) -> AppResult<Self> {
+        let party = Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .one(db)
+            .await?;
+
+        match party {
+            Some(party) => Ok(party),
+            None => Self::create(db, snowflake_generator, Some(bank_account_id), None).await,
+        }
+    }
+
+    pub async fn find_or_create_for_external_bank_account(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        external_bank_account_id: i64,
+    ) -> AppResult<Self> {
+        let party = Entity::find()
+            .filter(Column::ExternalBankAccountId.eq(external_bank_account_id))
+            .one(db)
+            .await?;
+
+        match party {
+            Some(party) => Ok(party),
+            None => Self::create(db, snowflake_generator, None, Some(external_bank_account_id)).await,
+        }
+    }
+
+    async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        bank_account_id: Option<i64>,
+        external_bank_account_id: Option<i64>,
+    ) -> AppResult<Self> {
+        let party = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            bank_account_id: Set(bank_account_id),
+            external_bank_account_id: Set(external_bank_account_id),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok(party.insert(db).await?)
+    }
+
+    pub async fn find_ids_for_bank_account(db: &impl ConnectionTrait, bank_account_id: i64) -> AppResult<Vec<i64>> {
+        Ok(Entity::find()
+            .filter(Column::BankAccountId.eq(bank_account_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|party| party.id)
+            .collect())
+    }
+
+    /// Finds all parties that represent one of the bank accounts.
+    pub async fn find_all_for_bank_accounts(
+        db: &impl ConnectionTrait,
+        bank_account_ids: &[i64],
+    ) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::BankAccountId.is_in(bank_account_ids.iter().copied()))
+            .all(db)
+            .await?)
+    }
+
+    /// Builds a sub query that selects the ids of all parties that represent the given bank account.
+    pub fn ids_for_bank_account(bank_account_id: i64) -> SelectStatement {
+        Query::select()
+            .column(Column::Id)
+            .from(Entity)
+            .and_where(Column::BankAccountId.eq(bank_account_id))
+            .to_owned()
+    }
+
+    /// Builds a sub query that selects the ids of all parties whose bank account is part of the given sub query.
+    pub fn ids_for_bank_accounts(bank_account_ids: SelectStatement) -> SelectStatement {
+        Query::select()
+            .column(Column::Id)
+            .from(Entity)
+            .and_where(Column::BankAccountId.in_subquery(bank_account_ids))
+            .to_owned()
+    }
+}
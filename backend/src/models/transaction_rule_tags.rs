@@ -0,0 +1,69 @@
+use super::_entities::transaction_rule_tags::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::AppResult;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use std::collections::HashMap;
+pub type TransactionRuleTags = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Returns the tag ids of each of the given rules.
+    pub async fn find_tag_ids_by_rule_ids(
+        db: &impl ConnectionTrait,
+        rule_ids: Vec<i64>,
+    ) -> AppResult<HashMap<i64, Vec<i64>>> {
+        let rule_tags = Entity::find()
+            .filter(Column::TransactionRuleId.is_in(rule_ids))
+            .all(db)
+            .await?;
+
+        let mut tag_ids: HashMap<i64, Vec<i64>> = HashMap::new();
+        for rule_tag in rule_tags {
+            tag_ids
+                .entry(rule_tag.transaction_rule_id)
+                .or_default()
+                .push(rule_tag.tag_id);
+        }
+
+        Ok(tag_ids)
+    }
+
+    /// Replaces all tags of the rule with the given ones.
+    pub async fn replace_for_rule(db: &impl ConnectionTrait, rule_id: i64, tag_ids: &[i64]) -> AppResult<()> {
+        Entity::delete_many()
+            .filter(Column::TransactionRuleId.eq(rule_id))
+            .exec(db)
+            .await?;
+
+        if tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        let rule_tags = tag_ids.iter().map(|tag_id| ActiveModel {
+            transaction_rule_id: Set(rule_id),
+            tag_id: Set(*tag_id),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        });
+        Entity::insert_many(rule_tags).exec(db).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,392 @@
+use super::_entities::transaction_rules::{ActiveModel, Column, Entity, Model};
+use crate::controllers::rule::TransactionRuleParams;
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::{taggings, transaction_rule_tags, transactions};
+use crate::models::transactions::NewTransaction;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::utils::iban::normalize_iban;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QueryOrder};
+pub type TransactionRules = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// The values of a transaction that rules can match on.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleSubject<'a> {
+    pub name: &'a str,
+    pub purpose: Option<&'a str>,
+    pub source_iban: Option<&'a str>,
+    pub destination_iban: Option<&'a str>,
+    /// The (always positive) amount in the smallest unit of the currency.
+    pub amount: i64,
+    pub r#type: &'a TransactionType,
+}
+
+impl<'a> From<&'a NewTransaction> for RuleSubject<'a> {
+    fn from(value: &'a NewTransaction) -> Self {
+        Self {
+            name: &value.name,
+            purpose: value.purpose.as_deref(),
+            source_iban: value.source_iban.as_deref(),
+            destination_iban: value.destination_iban.as_deref(),
+            amount: value.amount,
+            r#type: &value.r#type,
+        }
+    }
+}
+
+impl<'a> From<&'a transactions::Model> for RuleSubject<'a> {
+    fn from(value: &'a transactions::Model) -> Self {
+        Self {
+            name: &value.name,
+            purpose: value.purpose.as_deref(),
+            source_iban: value.source_iban.as_deref(),
+            destination_iban: value.destination_iban.as_deref(),
+            amount: value.amount,
+            r#type: &value.r#type,
+        }
+    }
+}
+
+/// The combined actions of all rules that matched a transaction.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RuleEffects {
+    pub category_id: Option<i64>,
+    pub name: Option<String>,
+    pub tag_ids: Vec<i64>,
+}
+
+/// All rules of a user (with their tags) in the order they are applied.
+#[derive(Debug, Default, Clone)]
+pub struct RuleSet {
+    rules: Vec<(Model, Vec<i64>)>,
+}
+
+impl RuleSet {
+    pub async fn load(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Self> {
+        let rules = Model::find_all_by_user_id(db, user_id).await?;
+        let mut tag_ids =
+            transaction_rule_tags::Model::find_tag_ids_by_rule_ids(db, rules.iter().map(|rule| rule.id).collect())
+                .await?;
+
+        Ok(Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| {
+                    let tag_ids = tag_ids.remove(&rule.id).unwrap_or_default();
+                    (rule, tag_ids)
+                })
+                .collect(),
+        })
+    }
+
+    /// Runs all rules in order.
+    ///
+    /// Later rules overwrite the category and name set by earlier rules, tags are collected.
+    /// A renamed transaction is matched by its new name from then on.
+    /// Processing ends after the first matching rule that has `stop_processing` set.
+    pub fn evaluate(&self, subject: &RuleSubject) -> RuleEffects {
+        let mut effects = RuleEffects::default();
+
+        for (rule, tag_ids) in &self.rules {
+            let name = effects.name.as_deref().unwrap_or(subject.name);
+            if !rule.matches(&RuleSubject { name, ..*subject }) {
+                continue;
+            }
+
+            if rule.category_id.is_some() {
+                effects.category_id = rule.category_id;
+            }
+            if let Some(rename_to) = &rule.rename_to {
+                effects.name = Some(rename_to.clone());
+            }
+            for tag_id in tag_ids {
+                if !effects.tag_ids.contains(tag_id) {
+                    effects.tag_ids.push(*tag_id);
+                }
+            }
+
+            if rule.stop_processing {
+                break;
+            }
+        }
+
+        effects
+    }
+
+    /// Applies all matching rules to a transaction that is about to be created.
+    pub fn apply(&self, new_transaction: &mut NewTransaction) {
+        let effects = self.evaluate(&RuleSubject::from(&*new_transaction));
+
+        if effects.category_id.is_some() {
+            new_transaction.category_id = effects.category_id;
+        }
+        if let Some(name) = effects.name {
+            new_transaction.name = name;
+        }
+        for tag_id in effects.tag_ids {
+            if !new_transaction.tag_ids.contains(&tag_id) {
+                new_transaction.tag_ids.push(tag_id);
+            }
+        }
+    }
+
+    /// Applies all matching rules to an already existing transaction.
+    /// Returns whether the transaction was changed.
+    pub async fn apply_to_existing(
+        &self,
+        db: &impl ConnectionTrait,
+        transaction: transactions::Model,
+    ) -> AppResult<bool> {
+        let effects = self.evaluate(&RuleSubject::from(&transaction));
+        let mut changed = false;
+
+        for tag_id in &effects.tag_ids {
+            changed |= taggings::Model::add(db, *tag_id, transactions::Entity.table_name(), transaction.id).await?;
+        }
+
+        let category_changed = effects.category_id.is_some() && effects.category_id != transaction.category_id;
+        let name_changed = effects.name.as_ref().is_some_and(|name| *name != transaction.name);
+        if category_changed || name_changed {
+            let mut active_model = transaction.into_active_model();
+            if category_changed {
+                active_model.category_id = Set(effects.category_id);
+            }
+            if let Some(name) = effects.name.filter(|_| name_changed) {
+                active_model.name = Set(name);
+            }
+            active_model.update(db).await?;
+            changed = true;
+        }
+
+        Ok(changed)
+    }
+}
+
+impl Model {
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Position)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &impl ConnectionTrait, id: i64, user_id: i64) -> AppResult<Self> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::EntityNotFound)
+    }
+
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        user_id: i64,
+        params: &TransactionRuleParams,
+    ) -> AppResult<Self> {
+        let mut active_model = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        active_model.apply_params(params);
+
+        let rule = active_model.insert(db).await?;
+        transaction_rule_tags::Model::replace_for_rule(db, rule.id, &params.tag_ids()).await?;
+
+        Ok(rule)
+    }
+
+    pub async fn update_with_params(
+        self,
+        db: &impl ConnectionTrait,
+        params: &TransactionRuleParams,
+    ) -> AppResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.apply_params(params);
+
+        let rule = active_model.update(db).await?;
+        transaction_rule_tags::Model::replace_for_rule(db, rule.id, &params.tag_ids()).await?;
+
+        Ok(rule)
+    }
+
+    pub async fn tag_ids(&self, db: &impl ConnectionTrait) -> AppResult<Vec<i64>> {
+        let mut tag_ids = transaction_rule_tags::Model::find_tag_ids_by_rule_ids(db, vec![self.id]).await?;
+
+        Ok(tag_ids.remove(&self.id).unwrap_or_default())
+    }
+
+    /// Checks whether all conditions of the rule match. Text conditions are case-insensitive.
+    pub fn matches(&self, subject: &RuleSubject) -> bool {
+        contains_ignore_case(Some(subject.name), self.name_contains.as_deref())
+            && contains_ignore_case(subject.purpose, self.purpose_contains.as_deref())
+            && iban_matches(subject.source_iban, self.source_iban.as_deref())
+            && iban_matches(subject.destination_iban, self.destination_iban.as_deref())
+            && self.min_amount.is_none_or(|min_amount| subject.amount >= min_amount)
+            && self.max_amount.is_none_or(|max_amount| subject.amount <= max_amount)
+            && self
+                .transaction_type
+                .as_ref()
+                .is_none_or(|transaction_type| transaction_type == subject.r#type)
+    }
+}
+
+impl ActiveModel {
+    fn apply_params(&mut self, params: &TransactionRuleParams) {
+        self.name = Set(params.name.clone());
+        self.position = Set(params.position);
+        self.stop_processing = Set(params.stop_processing);
+        self.name_contains = Set(params.name_contains.clone());
+        self.purpose_contains = Set(params.purpose_contains.clone());
+        self.source_iban = Set(params.source_iban.as_deref().map(normalize_iban));
+        self.destination_iban = Set(params.destination_iban.as_deref().map(normalize_iban));
+        self.min_amount = Set(params.min_amount);
+        self.max_amount = Set(params.max_amount);
+        self.transaction_type = Set(params.transaction_type.clone());
+        self.category_id = Set(params.category_id.as_ref().map(|category_id| category_id.id));
+        self.rename_to = Set(params.rename_to.clone());
+        self.updated_at = Set(chrono::Utc::now().into());
+    }
+}
+
+fn contains_ignore_case(value: Option<&str>, pattern: Option<&str>) -> bool {
+    match pattern {
+        None => true,
+        Some(pattern) => value.is_some_and(|value| value.to_lowercase().contains(&pattern.to_lowercase())),
+    }
+}
+
+fn iban_matches(value: Option<&str>, iban: Option<&str>) -> bool {
+    match iban {
+        None => true,
+        Some(iban) => value.is_some_and(|value| normalize_iban(value) == iban),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: i64) -> Model {
+        Model {
+            id,
+            user_id: 1,
+            name: format!("Rule {}", id),
+            position: 0,
+            stop_processing: false,
+            name_contains: None,
+            purpose_contains: None,
+            source_iban: None,
+            destination_iban: None,
+            min_amount: None,
+            max_amount: None,
+            transaction_type: None,
+            category_id: None,
+            rename_to: None,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    fn subject<'a>(name: &'a str, r#type: &'a TransactionType) -> RuleSubject<'a> {
+        RuleSubject {
+            name,
+            purpose: Some("Card payment 1234"),
+            source_iban: None,
+            destination_iban: Some("DE89 3704 0044 0532 0130 00"),
+            amount: 1250,
+            r#type,
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let expense = TransactionType::Expense;
+        let subject = subject("REWE Markt", &expense);
+
+        let mut matching = rule(1);
+        matching.name_contains = Some("rewe".to_string());
+        matching.destination_iban = Some("DE89370400440532013000".to_string());
+        matching.min_amount = Some(1000);
+        matching.max_amount = Some(1250);
+        matching.transaction_type = Some(TransactionType::Expense);
+        assert!(matching.matches(&subject));
+
+        let mut wrong_type = matching.clone();
+        wrong_type.transaction_type = Some(TransactionType::Income);
+        assert!(!wrong_type.matches(&subject));
+
+        let mut missing_iban = rule(2);
+        missing_iban.source_iban = Some("DE89370400440532013000".to_string());
+        assert!(!missing_iban.matches(&subject));
+
+        let mut too_small = rule(3);
+        too_small.max_amount = Some(1000);
+        assert!(!too_small.matches(&subject));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let expense = TransactionType::Expense;
+
+        let mut rename = rule(1);
+        rename.name_contains = Some("rewe".to_string());
+        rename.rename_to = Some("Groceries".to_string());
+        let mut categorize = rule(2);
+        categorize.name_contains = Some("groceries".to_string());
+        categorize.category_id = Some(10);
+        categorize.stop_processing = true;
+        let mut never_reached = rule(3);
+        never_reached.category_id = Some(20);
+
+        let rules = RuleSet {
+            rules: vec![
+                (rename, vec![100]),
+                (categorize, vec![100, 200]),
+                (never_reached, vec![300]),
+            ],
+        };
+        let effects = rules.evaluate(&subject("REWE Markt", &expense));
+
+        assert_eq!(
+            effects,
+            RuleEffects {
+                category_id: Some(10),
+                name: Some("Groceries".to_string()),
+                tag_ids: vec![100, 200],
+            }
+        );
+        assert_eq!(
+            rules.evaluate(&subject("Landlord", &expense)),
+            RuleEffects {
+                category_id: Some(20),
+                name: None,
+                tag_ids: vec![300],
+            }
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use super::_entities::transaction_splits::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::{taggings, transactions};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::ActiveValue::Set;
+use sea_orm::QueryOrder;
+use std::collections::HashMap;
+pub type TransactionSplits = Entity;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// All values that are needed to create a new split line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewTransactionSplit {
+    /// The (positive) amount in the smallest unit of the currency.
+    pub amount: i64,
+    pub category_id: Option<i64>,
+    pub note: Option<String>,
+    pub tag_ids: Vec<i64>,
+}
+
+impl Model {
+    pub async fn find_by_transaction_id(db: &impl ConnectionTrait, transaction_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(Column::TransactionId.eq(transaction_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_transaction_ids(
+        db: &impl ConnectionTrait,
+        transaction_ids: Vec<i64>,
+    ) -> AppResult<HashMap<i64, Vec<Self>>> {
+        let splits = Entity::find()
+            .filter(Column::TransactionId.is_in(transaction_ids))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+
+        let mut by_transaction: HashMap<i64, Vec<Self>> = HashMap::new();
+        for split in splits {
+            by_transaction.entry(split.transaction_id).or_default().push(split);
+        }
+
+        Ok(by_transaction)
+    }
+
+    /// Replaces all split lines of the transaction.
+    /// The amounts of the new lines have to sum up to the absolute amount of the transaction.
+    /// An empty list removes the split.
+    pub async fn replace_for_transaction(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        transaction: &transactions::Model,
+        splits: Vec<NewTransactionSplit>,
+    ) -> AppResult<Vec<Self>> {
+        ensure_sums_up(transaction.amount, &splits)?;
+
+        Self::delete_for_transaction(db, transaction.id).await?;
+
+        let mut created = Vec::with_capacity(splits.len());
+        for split in splits {
+            let model = ActiveModel {
+                id: Set(snowflake_generator.next_id()?),
+                transaction_id: Set(transaction.id),
+                amount: Set(split.amount),
+                category_id: Set(split.category_id),
+                note: Set(split.note),
+                created_at: Set(chrono::Utc::now().into()),
+                updated_at: Set(chrono::Utc::now().into()),
+            }
+            .insert(db)
+            .await?;
+
+            for tag_id in split.tag_ids {
+                taggings::Model::add(db, tag_id, Entity.table_name(), model.id).await?;
+            }
+            created.push(model);
+        }
+
+        Ok(created)
+    }
+
+    /// Deletes all split lines of the transaction together with their tags.
+    pub async fn delete_for_transaction(db: &impl ConnectionTrait, transaction_id: i64) -> AppResult<()> {
+        let split_ids = Self::find_by_transaction_id(db, transaction_id)
+            .await?
+            .into_iter()
+            .map(|split| split.id)
+            .collect::<Vec<_>>();
+        if split_ids.is_empty() {
+            return Ok(());
+        }
+
+        taggings::Model::delete_for_entities(db, Entity.table_name(), split_ids.clone()).await?;
+        Entity::delete_many()
+            .filter(Column::Id.is_in(split_ids))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Builds a sub query that selects the ids of all transactions with a split line in one of the categories.
+    pub fn transaction_ids_with_categories(category_ids: Vec<i64>) -> SelectStatement {
+        Query::select()
+            .column(Column::TransactionId)
+            .from(Entity)
+            .and_where(Column::CategoryId.is_in(category_ids))
+            .to_owned()
+    }
+
+    /// Builds a sub query that selects the ids of all transactions with a split line that has one of the tags.
+    pub fn transaction_ids_with_tags(tag_ids: Vec<i64>) -> SelectStatement {
+        Query::select()
+            .column(Column::TransactionId)
+            .from(Entity)
+            .and_where(Column::Id.in_subquery(taggings::Model::entity_ids_with_tags(Entity.table_name(), tag_ids)))
+            .to_owned()
+    }
+}
+
+/// Checks that the amounts of the split lines sum up to the absolute amount of the transaction.
+/// An empty list always passes, as it removes the split.
+fn ensure_sums_up(transaction_amount: i64, splits: &[NewTransactionSplit]) -> AppResult<()> {
+    let sum = splits.iter().try_fold(0i64, |sum, split| sum.checked_add(split.amount));
+    match splits.is_empty() || sum == transaction_amount.checked_abs() {
+        true => Ok(()),
+        false => Err(AppError::InvalidSplitAmounts()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(amount: i64) -> NewTransactionSplit {
+        NewTransactionSplit {
+            amount,
+            category_id: None,
+            note: None,
+            tag_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ensure_sums_up() {
+        assert!(ensure_sums_up(1000, &[split(600), split(400)]).is_ok());
+        assert!(ensure_sums_up(-1000, &[split(600), split(400)]).is_ok());
+        assert!(ensure_sums_up(1000, &[]).is_ok());
+
+        assert!(ensure_sums_up(1000, &[split(600), split(300)]).is_err());
+        assert!(ensure_sums_up(1000, &[split(600), split(500)]).is_err());
+        assert!(ensure_sums_up(i64::MAX, &[split(i64::MAX), split(1)]).is_err());
+    }
+}
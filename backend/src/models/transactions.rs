@@ -1,7 +1,25 @@
-use super::_entities::transactions::{ActiveModel, Entity};
+use super::_entities::transactions::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::{
+    balance_snapshots, bank_accounts, external_bank_account_ibans, possible_duplicates, taggings, transaction_parties,
+    transaction_splits, user_permissions,
+};
+use crate::models::user_permissions::Permission;
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{Page, Pagination, SortField};
+use crate::utils::iban::normalize_iban;
+use enumflags2::BitFlags;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Func, Query, SelectStatement, SimpleExpr};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, IntoActiveModel};
+use std::collections::HashMap;
 pub type Transactions = Entity;
 
+/// The two legs of a transfer are only paired if they were booked at most this many days apart.
+pub const TRANSFER_PAIRING_WINDOW_DAYS: i64 = 3;
+
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
     // extend activemodel below (keep comment for generators)
@@ -19,3 +37,920 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+/// All values that are needed to create a new transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewTransaction {
+    pub source_id: Option<i64>,
+    pub destination_id: Option<i64>,
+    pub currency_id: i64,
+    pub category_id: Option<i64>,
+    pub file_attachment_id: Option<i64>,
+    pub source_name: Option<String>,
+    pub source_iban: Option<String>,
+    pub destination_name: Option<String>,
+    pub destination_iban: Option<String>,
+    pub r#type: TransactionType,
+    pub amount: i64,
+    pub destination_amount: Option<i64>,
+    pub name: String,
+    pub purpose: Option<String>,
+    pub note: Option<String>,
+    pub booking_date: Option<DateTimeWithTimeZone>,
+    /// The id of the transaction at the bank, like the FITID of an OFX file.
+    pub external_id: Option<String>,
+    /// The tags that are added to the transaction once it was created.
+    pub tag_ids: Vec<i64>,
+}
+
+/// A transaction between one of the users bank accounts and a counterparty,
+/// like it is entered by the user or read from a statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterpartyTransaction {
+    /// The amount in the smallest unit of the currency.
+    /// Positive amounts are incoming, negative amounts are outgoing.
+    pub amount: i64,
+    pub name: String,
+    pub purpose: Option<String>,
+    pub note: Option<String>,
+    pub booking_date: Option<DateTimeWithTimeZone>,
+    pub category_id: Option<i64>,
+    pub counterparty_name: Option<String>,
+    pub counterparty_iban: Option<String>,
+}
+
+/// A transfer between two bank accounts of the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferTransaction {
+    /// The (positive) amount in the smallest unit of the currency of the source bank account.
+    pub amount: i64,
+    /// The (positive) amount in the smallest unit of the currency of the destination bank account.
+    /// Required if both bank accounts use different currencies, ignored otherwise.
+    pub destination_amount: Option<i64>,
+    pub name: String,
+    pub purpose: Option<String>,
+    pub note: Option<String>,
+    pub booking_date: Option<DateTimeWithTimeZone>,
+    pub category_id: Option<i64>,
+}
+
+/// The filters of a transaction search. All given filters have to match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionSearch {
+    /// Free text that is matched against the name, purpose, note and the names of both parties.
+    /// Supports the web search syntax of Postgres (`"exact phrase"`, `-excluded`, `or`).
+    pub text: Option<String>,
+    /// Matches the booking date or, if the transaction was not booked yet, the date it was created at.
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub r#type: Option<TransactionType>,
+    /// Matches the category of the transaction or of one of its split lines.
+    pub category_ids: Option<Vec<i64>>,
+    /// Matches transactions with at least one of the tags, also on one of their split lines.
+    pub tag_ids: Vec<i64>,
+    /// Matches transactions from or to at least one of the bank accounts.
+    pub bank_account_ids: Vec<i64>,
+    pub has_attachment: Option<bool>,
+}
+
+impl NewTransaction {
+    /// Builds an income (positive amount) or expense (negative amount) of the bank account.
+    /// The counterparty is linked to a known external bank account if its iban is known.
+    pub async fn with_counterparty(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        bank_account: &bank_accounts::Model,
+        transaction: CounterpartyTransaction,
+    ) -> AppResult<Self> {
+        let own_party =
+            transaction_parties::Model::find_or_create_for_bank_account(db, snowflake_generator, bank_account.id)
+                .await?;
+        let counterparty_id = match &transaction.counterparty_iban {
+            None => None,
+            Some(iban) => match external_bank_account_ibans::Model::find_by_iban(db, iban).await? {
+                None => None,
+                Some(entry) => Some(
+                    transaction_parties::Model::find_or_create_for_external_bank_account(
+                        db,
+                        snowflake_generator,
+                        entry.external_bank_account_id,
+                    )
+                    .await?
+                    .id,
+                ),
+            },
+        };
+
+        let own = (
+            Some(own_party.id),
+            Some(bank_account.name.clone()),
+            bank_account.iban.clone(),
+        );
+        let counterparty = (
+            counterparty_id,
+            transaction.counterparty_name,
+            transaction.counterparty_iban,
+        );
+        let (r#type, source, destination) = match transaction.amount >= 0 {
+            true => (TransactionType::Income, counterparty, own),
+            false => (TransactionType::Expense, own, counterparty),
+        };
+
+        Ok(Self {
+            source_id: source.0,
+            destination_id: destination.0,
+            currency_id: bank_account.currency_id,
+            category_id: transaction.category_id,
+            file_attachment_id: None,
+            source_name: source.1,
+            source_iban: source.2,
+            destination_name: destination.1,
+            destination_iban: destination.2,
+            r#type,
+            amount: transaction.amount.abs(),
+            destination_amount: None,
+            name: transaction.name,
+            purpose: transaction.purpose,
+            note: transaction.note,
+            booking_date: transaction.booking_date,
+            external_id: None,
+            tag_ids: Vec::new(),
+        })
+    }
+
+    /// Builds a transfer between two different bank accounts.
+    /// The permissions of the user on both bank accounts have to be checked beforehand.
+    pub async fn transfer(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        source: &bank_accounts::Model,
+        destination: &bank_accounts::Model,
+        transfer: TransferTransaction,
+    ) -> AppResult<Self> {
+        if source.id == destination.id {
+            return Err(AppError::InvalidTransfer());
+        }
+
+        let destination_amount = match source.currency_id == destination.currency_id {
+            true => None,
+            false => Some(transfer.destination_amount.ok_or_else(AppError::InvalidExchangeRate)?),
+        };
+        let source_party =
+            transaction_parties::Model::find_or_create_for_bank_account(db, snowflake_generator, source.id).await?;
+        let destination_party =
+            transaction_parties::Model::find_or_create_for_bank_account(db, snowflake_generator, destination.id)
+                .await?;
+
+        Ok(Self {
+            source_id: Some(source_party.id),
+            destination_id: Some(destination_party.id),
+            currency_id: source.currency_id,
+            category_id: transfer.category_id,
+            file_attachment_id: None,
+            source_name: Some(source.name.clone()),
+            source_iban: source.iban.clone(),
+            destination_name: Some(destination.name.clone()),
+            destination_iban: destination.iban.clone(),
+            r#type: TransactionType::Transfer,
+            amount: transfer.amount,
+            destination_amount,
+            name: transfer.name,
+            purpose: transfer.purpose,
+            note: transfer.note,
+            booking_date: transfer.booking_date,
+            external_id: None,
+            tag_ids: Vec::new(),
+        })
+    }
+
+    pub fn into_active_model(self, id: i64) -> ActiveModel {
+        ActiveModel {
+            id: Set(id),
+            source_id: Set(self.source_id),
+            destination_id: Set(self.destination_id),
+            currency_id: Set(self.currency_id),
+            category_id: Set(self.category_id),
+            file_attachment_id: Set(self.file_attachment_id),
+            source_name: Set(self.source_name),
+            source_iban: Set(self.source_iban),
+            destination_name: Set(self.destination_name),
+            destination_iban: Set(self.destination_iban),
+            r#type: Set(self.r#type),
+            amount: Set(self.amount),
+            destination_amount: Set(self.destination_amount),
+            name: Set(self.name),
+            purpose: Set(self.purpose),
+            note: Set(self.note),
+            booking_date: Set(self.booking_date),
+            reconciled_at: Set(None),
+            external_id: Set(self.external_id),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+    }
+}
+
+impl Model {
+    pub async fn find_by_id(db: &impl ConnectionTrait, id: i64) -> AppResult<Option<Self>> {
+        Ok(Entity::find_by_id(id).one(db).await?)
+    }
+
+    pub async fn find_by_ids(db: &impl ConnectionTrait, ids: Vec<i64>) -> AppResult<HashMap<i64, Self>> {
+        let transactions = Entity::find().filter(Column::Id.is_in(ids)).all(db).await?;
+
+        Ok(transactions
+            .into_iter()
+            .map(|transaction| (transaction.id, transaction))
+            .collect())
+    }
+
+    /// Finds a transaction and ensures that the user holds the required permissions
+    /// on at least one of the involved bank accounts.
+    pub async fn find_by_id_with_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<Self> {
+        let transaction = Self::find_by_id(db, id).await?.ok_or_else(AppError::EntityNotFound)?;
+        transaction.ensure_permissions(db, user_id, required).await?;
+
+        Ok(transaction)
+    }
+
+    /// Ensures that the user holds the required permissions on at least one of the involved bank accounts.
+    pub async fn ensure_permissions(
+        &self,
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<()> {
+        let mut readable = false;
+        for bank_account_id in self.bank_account_ids(db).await? {
+            let permissions = user_permissions::Model::find_permissions(
+                db,
+                user_id,
+                bank_accounts::Entity.table_name(),
+                bank_account_id,
+            )
+            .await?;
+
+            if permissions.contains(required) {
+                return Ok(());
+            }
+            readable |= permissions.contains(Permission::Read);
+        }
+
+        match readable {
+            true => Err(AppError::MissingPermissions()),
+            false => Err(AppError::EntityNotFound()),
+        }
+    }
+
+    /// Ensures that the user holds the required permissions on every involved bank account of a transfer.
+    /// Changing a transfer changes the balances of both bank accounts, so one writable side is not enough.
+    /// Other transactions only involve one bank account, which [`Self::ensure_permissions`] already checks.
+    pub async fn ensure_permissions_on_all(
+        &self,
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<()> {
+        if self.r#type != TransactionType::Transfer {
+            return Ok(());
+        }
+
+        for bank_account_id in self.bank_account_ids(db).await? {
+            let permissions = user_permissions::Model::find_permissions(
+                db,
+                user_id,
+                bank_accounts::Entity.table_name(),
+                bank_account_id,
+            )
+            .await?;
+            if !permissions.contains(required) {
+                return Err(AppError::MissingPermissions());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds a sub query that selects the ids of all transactions of bank accounts
+    /// on which the user holds the required permissions.
+    pub fn visible_ids(user_id: i64, required: BitFlags<Permission>) -> SelectStatement {
+        let parties = || {
+            transaction_parties::Model::ids_for_bank_accounts(user_permissions::Model::accessible_entity_ids(
+                user_id,
+                bank_accounts::Entity.table_name(),
+                required,
+            ))
+        };
+
+        Query::select()
+            .column(Column::Id)
+            .from(Entity)
+            .cond_where(
+                Condition::any()
+                    .add(Column::SourceId.in_subquery(parties()))
+                    .add(Column::DestinationId.in_subquery(parties())),
+            )
+            .to_owned()
+    }
+
+    /// Matches transactions that have at least one of the tags, either themselves or on one of their split lines.
+    pub fn tagged_with(tag_ids: Vec<i64>) -> Condition {
+        Condition::any()
+            .add(Column::Id.in_subquery(taggings::Model::entity_ids_with_tags(
+                Entity.table_name(),
+                tag_ids.clone(),
+            )))
+            .add(Column::Id.in_subquery(transaction_splits::Model::transaction_ids_with_tags(tag_ids)))
+    }
+
+    /// Searches all transactions the user can see and loads a page of the results.
+    pub async fn search(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        search: &TransactionSearch,
+        pagination: &Pagination<impl SortField<Entity>>,
+    ) -> AppResult<Page<Self>> {
+        let mut query =
+            Entity::find().filter(Column::Id.in_subquery(Self::visible_ids(user_id, Permission::Read.into())));
+
+        if let Some(text) = search.text.as_deref().filter(|text| !text.trim().is_empty()) {
+            query = query.filter(Expr::cust_with_values(
+                "search_vector @@ websearch_to_tsquery('simple', $1)",
+                [text],
+            ));
+        }
+        let effective_date = || {
+            Expr::expr(Func::coalesce([
+                Expr::col(Column::BookingDate).into(),
+                Expr::col(Column::CreatedAt).into(),
+            ]))
+        };
+        if let Some(from) = search.from {
+            query = query.filter(effective_date().gte(from));
+        }
+        if let Some(to) = search.to {
+            query = query.filter(effective_date().lte(to));
+        }
+        if let Some(min_amount) = search.min_amount {
+            query = query.filter(Column::Amount.gte(min_amount));
+        }
+        if let Some(max_amount) = search.max_amount {
+            query = query.filter(Column::Amount.lte(max_amount));
+        }
+        if let Some(r#type) = &search.r#type {
+            query = query.filter(Column::Type.eq(r#type.clone()));
+        }
+        if let Some(category_ids) = &search.category_ids {
+            query = query.filter(
+                Condition::any()
+                    .add(Column::CategoryId.is_in(category_ids.clone()))
+                    .add(
+                        Column::Id.in_subquery(transaction_splits::Model::transaction_ids_with_categories(
+                            category_ids.clone(),
+                        )),
+                    ),
+            );
+        }
+        if !search.tag_ids.is_empty() {
+            query = query.filter(Self::tagged_with(search.tag_ids.clone()));
+        }
+        if !search.bank_account_ids.is_empty() {
+            let parties = || {
+                Query::select()
+                    .column(transaction_parties::Column::Id)
+                    .from(transaction_parties::Entity)
+                    .and_where(transaction_parties::Column::BankAccountId.is_in(search.bank_account_ids.clone()))
+                    .to_owned()
+            };
+            query = query.filter(
+                Condition::any()
+                    .add(Column::SourceId.in_subquery(parties()))
+                    .add(Column::DestinationId.in_subquery(parties())),
+            );
+        }
+        if let Some(has_attachment) = search.has_attachment {
+            query = query.filter(match has_attachment {
+                true => Column::FileAttachmentId.is_not_null(),
+                false => Column::FileAttachmentId.is_null(),
+            });
+        }
+
+        pagination.load(db, query).await
+    }
+
+    /// Creates a new transaction, tags it, updates the balances of all involved bank accounts
+    /// and queues possible duplicates for review.
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        new_transaction: NewTransaction,
+    ) -> AppResult<Self> {
+        let transaction = Self::create_without_detection(db, snowflake_generator, new_transaction).await?;
+        possible_duplicates::Model::detect(db, snowflake_generator, &transaction, &[]).await?;
+
+        Ok(transaction)
+    }
+
+    /// Like `create`, but does not look for possible duplicates.
+    /// Imports detect them once all transactions of the batch were created.
+    pub async fn create_without_detection(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        mut new_transaction: NewTransaction,
+    ) -> AppResult<Self> {
+        let tag_ids = std::mem::take(&mut new_transaction.tag_ids);
+        let transaction = new_transaction
+            .into_active_model(snowflake_generator.next_id()?)
+            .insert(db)
+            .await?;
+
+        for tag_id in tag_ids {
+            taggings::Model::add(db, tag_id, Entity.table_name(), transaction.id).await?;
+        }
+        transaction.apply_to_balances(db, 1).await?;
+
+        Ok(transaction)
+    }
+
+    /// Deletes the transaction and reverts its effect on the balances of the involved bank accounts.
+    pub async fn delete_with_balances(self, db: &impl ConnectionTrait) -> AppResult<()> {
+        self.apply_to_balances(db, -1).await?;
+        taggings::Model::delete_for_entity(db, Entity.table_name(), self.id).await?;
+        transaction_splits::Model::delete_for_transaction(db, self.id).await?;
+        self.delete(db).await?;
+
+        Ok(())
+    }
+
+    /// Pairs an income or expense with the opposite leg of the same transfer on another bank account
+    /// into a single transfer. This happens when the statements of both bank accounts are imported.
+    /// The user has to be allowed to write to both bank accounts.
+    ///
+    /// Returns the transfer or, if no opposite leg was found, the unchanged transaction.
+    pub async fn pair_transfer(self, db: &impl ConnectionTrait, user_id: i64) -> AppResult<Self> {
+        if self.reconciled_at.is_some() {
+            return Ok(self);
+        }
+        let (opposite_type, own_party) = match self.r#type {
+            TransactionType::Income => (TransactionType::Expense, self.destination_id),
+            TransactionType::Expense => (TransactionType::Income, self.source_id),
+            TransactionType::Transfer => return Ok(self),
+        };
+        let Some(bank_account) = Self::find_bank_account(db, own_party).await? else {
+            return Ok(self);
+        };
+        let required = Permission::Read | Permission::Write;
+        if !user_permissions::Model::has_permissions(
+            db,
+            user_id,
+            bank_accounts::Entity.table_name(),
+            bank_account.id,
+            required,
+        )
+        .await?
+        {
+            return Ok(self);
+        }
+
+        // The own side of the opposite leg: the source of an expense, the destination of an income.
+        let opposite_party = match opposite_type {
+            TransactionType::Expense => Column::SourceId,
+            _ => Column::DestinationId,
+        };
+        let writable_parties = transaction_parties::Model::ids_for_bank_accounts(
+            user_permissions::Model::accessible_entity_ids(user_id, bank_accounts::Entity.table_name(), required),
+        );
+        let date = self.effective_date();
+        let window = chrono::Duration::days(TRANSFER_PAIRING_WINDOW_DAYS);
+        let effective_date = Func::coalesce([
+            Expr::col(Column::BookingDate).into(),
+            Expr::col(Column::CreatedAt).into(),
+        ]);
+        let candidates = Entity::find()
+            .filter(Column::Id.ne(self.id))
+            .filter(Column::Type.eq(opposite_type))
+            .filter(Column::ReconciledAt.is_null())
+            .filter(opposite_party.in_subquery(writable_parties))
+            .filter(opposite_party.not_in_subquery(transaction_parties::Model::ids_for_bank_account(bank_account.id)))
+            .filter(Expr::expr(effective_date).between(date - window, date + window))
+            .all(db)
+            .await?;
+
+        let mut best: Option<(Self, bank_accounts::Model)> = None;
+        for candidate in candidates {
+            let party_id = match candidate.r#type {
+                TransactionType::Expense => candidate.source_id,
+                _ => candidate.destination_id,
+            };
+            let Some(other_account) = Self::find_bank_account(db, party_id).await? else {
+                continue;
+            };
+
+            let is_leg = match self.r#type {
+                TransactionType::Expense => is_transfer_pair(&self, &candidate, &bank_account, &other_account),
+                _ => is_transfer_pair(&candidate, &self, &other_account, &bank_account),
+            };
+            let distance = |transaction: &Self| (transaction.effective_date() - date).num_seconds().abs();
+            if is_leg
+                && best
+                    .as_ref()
+                    .is_none_or(|(best, _)| distance(&candidate) < distance(best))
+            {
+                best = Some((candidate, other_account));
+            }
+        }
+
+        let Some((other, other_account)) = best else {
+            return Ok(self);
+        };
+        let (expense, income, destination) = match self.r#type {
+            TransactionType::Expense => (self, other, other_account),
+            _ => (other, self, bank_account),
+        };
+
+        // The expense becomes the transfer, the income is removed.
+        expense.apply_to_balances(db, -1).await?;
+        let income_tags = taggings::Model::find_for_entity(db, Entity.table_name(), income.id).await?;
+
+        let mut active_model = expense.clone().into_active_model();
+        active_model.r#type = Set(TransactionType::Transfer);
+        active_model.destination_id = Set(income.destination_id);
+        active_model.destination_name = Set(Some(destination.name));
+        active_model.destination_iban = Set(destination.iban);
+        active_model.destination_amount = Set((expense.currency_id != income.currency_id).then_some(income.amount));
+        active_model.category_id = Set(expense.category_id.or(income.category_id));
+        active_model.purpose = Set(expense.purpose.clone().or(income.purpose.clone()));
+        active_model.note = Set(expense.note.clone().or(income.note.clone()));
+        active_model.file_attachment_id = Set(expense.file_attachment_id.or(income.file_attachment_id));
+
+        income.delete_with_balances(db).await?;
+        let transfer = active_model.update(db).await?;
+        for tagging in income_tags {
+            taggings::Model::add(db, tagging.tag_id, Entity.table_name(), transfer.id).await?;
+        }
+        transfer.apply_to_balances(db, 1).await?;
+
+        Ok(transfer)
+    }
+
+    /// Finds an already existing transaction of the bank account that has exactly the same values.
+    /// This is used to skip transactions that were already imported before.
+    ///
+    /// An income or expense is also a duplicate of a transfer it was paired into (see `pair_transfer`).
+    /// If the bank assigned an id to the transaction, a transaction with the same id is always a duplicate.
+    /// Otherwise only transactions without an id are compared by their values.
+    pub async fn find_exact_duplicate(
+        db: &impl ConnectionTrait,
+        bank_account_id: i64,
+        new_transaction: &NewTransaction,
+    ) -> AppResult<Option<Self>> {
+        let parties = || transaction_parties::Model::ids_for_bank_account(bank_account_id);
+        let of_bank_account = || {
+            Condition::any()
+                .add(Column::SourceId.in_subquery(parties()))
+                .add(Column::DestinationId.in_subquery(parties()))
+        };
+
+        if let Some(external_id) = &new_transaction.external_id {
+            let duplicate = Entity::find()
+                .filter(of_bank_account())
+                .filter(Column::ExternalId.eq(external_id.as_str()))
+                .one(db)
+                .await?;
+            if duplicate.is_some() {
+                return Ok(duplicate);
+            }
+        }
+
+        let mut query = Entity::find().filter(of_bank_account());
+        if new_transaction.external_id.is_some() {
+            query = query.filter(Column::ExternalId.is_null());
+        }
+        let duplicate = query
+            .filter(Column::Type.eq(new_transaction.r#type.clone()))
+            .filter(Column::Amount.eq(new_transaction.amount))
+            .filter(Column::Name.eq(new_transaction.name.as_str()))
+            .filter(eq_or_null(Column::BookingDate, new_transaction.booking_date))
+            .filter(eq_or_null(Column::SourceIban, new_transaction.source_iban.clone()))
+            .filter(eq_or_null(
+                Column::DestinationIban,
+                new_transaction.destination_iban.clone(),
+            ))
+            .one(db)
+            .await?;
+        if duplicate.is_some() {
+            return Ok(duplicate);
+        }
+
+        let received = Func::coalesce([
+            Expr::col(Column::DestinationAmount).into(),
+            Expr::col(Column::Amount).into(),
+        ]);
+        let (own_party, amount) = match new_transaction.r#type {
+            TransactionType::Expense => (Column::SourceId, Expr::col(Column::Amount)),
+            TransactionType::Income => (Column::DestinationId, Expr::expr(received)),
+            TransactionType::Transfer => return Ok(None),
+        };
+        // Both legs are rarely booked on the same day, the transfer keeps the date of the expense.
+        let date = match new_transaction.booking_date {
+            Some(date) => {
+                let window = chrono::Duration::days(TRANSFER_PAIRING_WINDOW_DAYS);
+                Column::BookingDate.between(date - window, date + window)
+            }
+            None => Column::BookingDate.is_null(),
+        };
+
+        Ok(Entity::find()
+            .filter(Column::Type.eq(TransactionType::Transfer))
+            .filter(own_party.in_subquery(parties()))
+            .filter(amount.eq(new_transaction.amount))
+            .filter(date)
+            .one(db)
+            .await?)
+    }
+
+    /// Finds other transactions with the same amount, type and currency between the same parties
+    /// whose booking date (or creation date) lies within the given window.
+    pub async fn find_duplicate_candidates(
+        &self,
+        db: &impl ConnectionTrait,
+        window: chrono::Duration,
+    ) -> AppResult<Vec<Self>> {
+        let mut parties = Condition::any();
+        if let Some(source_id) = self.source_id {
+            parties = parties.add(Column::SourceId.eq(source_id));
+        }
+        if let Some(destination_id) = self.destination_id {
+            parties = parties.add(Column::DestinationId.eq(destination_id));
+        }
+        if parties.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let date = self.effective_date();
+        let effective_date = Func::coalesce([
+            Expr::col(Column::BookingDate).into(),
+            Expr::col(Column::CreatedAt).into(),
+        ]);
+
+        Ok(Entity::find()
+            .filter(Column::Id.ne(self.id))
+            .filter(Column::Type.eq(self.r#type.clone()))
+            .filter(Column::CurrencyId.eq(self.currency_id))
+            .filter(Column::Amount.eq(self.amount))
+            .filter(parties)
+            .filter(Expr::expr(effective_date).between(date - window, date + window))
+            .all(db)
+            .await?)
+    }
+
+    /// The booking date or, if the transaction was not booked yet, the date it was created at.
+    pub fn effective_date(&self) -> DateTimeWithTimeZone {
+        self.booking_date.unwrap_or(self.created_at)
+    }
+
+    /// The iban of the other side of an income or expense.
+    pub fn counterparty_iban(&self) -> Option<&str> {
+        match self.r#type {
+            TransactionType::Income => self.source_iban.as_deref(),
+            TransactionType::Expense => self.destination_iban.as_deref(),
+            TransactionType::Transfer => None,
+        }
+    }
+
+    /// The name of the other side of an income or expense.
+    pub fn counterparty_name(&self) -> Option<&str> {
+        match self.r#type {
+            TransactionType::Income => self.source_name.as_deref(),
+            TransactionType::Expense => self.destination_name.as_deref(),
+            TransactionType::Transfer => None,
+        }
+    }
+
+    /// Reconciled transactions are locked against edits.
+    pub fn ensure_not_reconciled(&self) -> AppResult<()> {
+        match self.reconciled_at {
+            Some(_) => Err(AppError::TransactionReconciled()),
+            None => Ok(()),
+        }
+    }
+
+    /// The (signed) change of the balance of the bank account that is represented by the given parties.
+    pub fn balance_effect(&self, party_ids: &[i64]) -> i64 {
+        let mut effect = 0;
+        if self.source_id.is_some_and(|id| party_ids.contains(&id)) {
+            effect -= self.amount;
+        }
+        if self.destination_id.is_some_and(|id| party_ids.contains(&id)) {
+            effect += self.received_amount();
+        }
+
+        effect
+    }
+
+    /// The amount that arrives at the destination, in the currency of the destination.
+    pub fn received_amount(&self) -> i64 {
+        self.destination_amount.unwrap_or(self.amount)
+    }
+
+    /// Applies the amount of this transaction to the balances of the involved bank accounts.
+    /// Use a factor of `1` when a transaction was created and `-1` when it was removed.
+    pub async fn apply_to_balances(&self, db: &impl ConnectionTrait, factor: i64) -> AppResult<()> {
+        if let Some(bank_account_id) = Self::find_bank_account_id(db, self.source_id).await? {
+            bank_accounts::Model::adjust_balance(db, bank_account_id, -self.amount * factor).await?;
+        }
+
+        if let Some(bank_account_id) = Self::find_bank_account_id(db, self.destination_id).await? {
+            bank_accounts::Model::adjust_balance(db, bank_account_id, self.received_amount() * factor).await?;
+        }
+
+        self.invalidate_balance_snapshots(db, self.effective_date()).await
+    }
+
+    /// Removes the balance snapshots of the involved bank accounts from the given date on,
+    /// as the history of their balances changed.
+    pub async fn invalidate_balance_snapshots(
+        &self,
+        db: &impl ConnectionTrait,
+        since: DateTimeWithTimeZone,
+    ) -> AppResult<()> {
+        for bank_account_id in self.bank_account_ids(db).await? {
+            balance_snapshots::Model::delete_since(db, bank_account_id, since.naive_utc().date()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the ids of the own bank accounts that are involved in this transaction.
+    pub async fn bank_account_ids(&self, db: &impl ConnectionTrait) -> AppResult<Vec<i64>> {
+        let mut ids = Vec::new();
+        for party_id in [self.source_id, self.destination_id] {
+            if let Some(bank_account_id) = Self::find_bank_account_id(db, party_id).await? {
+                ids.push(bank_account_id);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    async fn find_bank_account(
+        db: &impl ConnectionTrait,
+        party_id: Option<i64>,
+    ) -> AppResult<Option<bank_accounts::Model>> {
+        match Self::find_bank_account_id(db, party_id).await? {
+            Some(bank_account_id) => bank_accounts::Model::find_by_id(db, bank_account_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn find_bank_account_id(db: &impl ConnectionTrait, party_id: Option<i64>) -> AppResult<Option<i64>> {
+        let Some(party_id) = party_id else {
+            return Ok(None);
+        };
+
+        let party = transaction_parties::Entity::find_by_id(party_id).one(db).await?;
+
+        Ok(party.and_then(|party| party.bank_account_id))
+    }
+}
+
+/// Checks whether an expense of the source and an income of the destination bank account
+/// are the two legs of the same transfer.
+///
+/// Known counterparty ibans have to match the other bank account.
+/// Legs in the same currency need the same amount, legs in different currencies can only be
+/// recognized by the ibans of both sides.
+pub fn is_transfer_pair(
+    expense: &Model,
+    income: &Model,
+    source: &bank_accounts::Model,
+    destination: &bank_accounts::Model,
+) -> bool {
+    let iban_matches = |counterparty_iban: Option<&str>, account: &bank_accounts::Model| match (
+        counterparty_iban,
+        account.iban.as_deref(),
+    ) {
+        (None, _) => None,
+        (Some(a), Some(b)) => Some(normalize_iban(a) == normalize_iban(b)),
+        (Some(_), None) => Some(false),
+    };
+    let destination_matches = iban_matches(expense.counterparty_iban(), destination);
+    let source_matches = iban_matches(income.counterparty_iban(), source);
+    if destination_matches == Some(false) || source_matches == Some(false) {
+        return false;
+    }
+
+    match expense.currency_id == income.currency_id {
+        true => expense.amount == income.amount,
+        false => destination_matches == Some(true) && source_matches == Some(true),
+    }
+}
+
+fn eq_or_null<T: Into<Value>>(column: Column, value: Option<T>) -> SimpleExpr {
+    match value {
+        Some(value) => column.eq(value),
+        None => column.is_null(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bank_account(id: i64, currency_id: i64, iban: Option<&str>) -> bank_accounts::Model {
+        bank_accounts::Model {
+            id,
+            currency_id,
+            linked_back_account_id: None,
+            name: format!("Account {}", id),
+            description: None,
+            iban: iban.map(str::to_string),
+            balance: 0,
+            original_balance: 0,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    fn transaction(r#type: TransactionType, currency_id: i64, amount: i64, counterparty_iban: Option<&str>) -> Model {
+        let counterparty_iban = counterparty_iban.map(str::to_string);
+        let (source_iban, destination_iban) = match r#type {
+            TransactionType::Income => (counterparty_iban, None),
+            _ => (None, counterparty_iban),
+        };
+
+        Model {
+            id: 1,
+            source_id: None,
+            destination_id: None,
+            currency_id,
+            category_id: None,
+            file_attachment_id: None,
+            source_name: None,
+            source_iban,
+            destination_name: None,
+            destination_iban,
+            r#type,
+            amount,
+            destination_amount: None,
+            name: "Transfer".to_string(),
+            purpose: None,
+            note: None,
+            booking_date: None,
+            reconciled_at: None,
+            external_id: None,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    #[test]
+    fn test_is_transfer_pair() {
+        let source = bank_account(1, 10, Some("DE89370400440532013000"));
+        let destination = bank_account(2, 10, Some("DE02120300000000202051"));
+
+        let expense = transaction(TransactionType::Expense, 10, 5000, Some("DE02 1203 0000 0000 2020 51"));
+        let income = transaction(TransactionType::Income, 10, 5000, None);
+        assert!(is_transfer_pair(&expense, &income, &source, &destination));
+
+        let other_amount = transaction(TransactionType::Income, 10, 4999, None);
+        assert!(!is_transfer_pair(&expense, &other_amount, &source, &destination));
+
+        let other_iban = transaction(TransactionType::Income, 10, 5000, Some("DE44500105175407324931"));
+        assert!(!is_transfer_pair(&expense, &other_iban, &source, &destination));
+    }
+
+    #[test]
+    fn test_balance_effect() {
+        let mut transfer = transaction(TransactionType::Transfer, 10, 10000, None);
+        transfer.source_id = Some(1);
+        transfer.destination_id = Some(2);
+        assert_eq!(transfer.balance_effect(&[1]), -10000);
+        assert_eq!(transfer.balance_effect(&[2]), 10000);
+        assert_eq!(transfer.balance_effect(&[3]), 0);
+
+        transfer.destination_amount = Some(8600);
+        assert_eq!(transfer.balance_effect(&[2]), 8600);
+    }
+
+    #[test]
+    fn test_is_transfer_pair_with_different_currencies() {
+        let source = bank_account(1, 10, Some("DE89370400440532013000"));
+        let destination = bank_account(2, 20, Some("GB29NWBK60161331926819"));
+
+        let expense = transaction(TransactionType::Expense, 10, 10000, Some("GB29NWBK60161331926819"));
+        let income = transaction(TransactionType::Income, 20, 8600, Some("DE89370400440532013000"));
+        assert!(is_transfer_pair(&expense, &income, &source, &destination));
+
+        // The amounts can not be compared, so both ibans are required.
+        let unknown_source = transaction(TransactionType::Income, 20, 8600, None);
+        assert!(!is_transfer_pair(&expense, &unknown_source, &source, &destination));
+    }
+}
@@ -0,0 +1,77 @@
+use super::_entities::two_factor_challenges::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
+use crate::services::snowflake_generator::SnowflakeGenerator;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+
+pub type TwoFactorChallenges = Entity;
+
+/// How long the user has to enter the code after the password was verified.
+pub const TWO_FACTOR_CHALLENGE_LIFETIME_MINUTES: i64 = 5;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Creates a new challenge for the user, which remembers the name and user agent of the session to create.
+    /// Returns the challenge together with its plain token, which is only stored as hash.
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        snowflake_generator: &SnowflakeGenerator,
+        secret_generator: &SecretGenerator,
+        user_id: i64,
+        name: Option<String>,
+        user_agent: Option<String>,
+    ) -> AppResult<(Self, String)> {
+        let token = secret_generator.generate_token();
+        let challenge = ActiveModel {
+            id: Set(snowflake_generator.next_id()?),
+            user_id: Set(user_id),
+            token_hash: Set(SecretGeneratorInner::hash_token(&token)),
+            name: Set(name),
+            user_agent: Set(user_agent),
+            expires_at: Set((Utc::now() + Duration::minutes(TWO_FACTOR_CHALLENGE_LIFETIME_MINUTES)).into()),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+
+        Ok((challenge.insert(db).await?, token))
+    }
+
+    /// Finds the challenge of the token, expired challenges are rejected.
+    pub async fn find_valid_by_token(db: &impl ConnectionTrait, token: &str, now: DateTime<Utc>) -> AppResult<Self> {
+        Entity::find()
+            .filter(Column::TokenHash.eq(SecretGeneratorInner::hash_token(token)))
+            .filter(Column::ExpiresAt.gt(now))
+            .one(db)
+            .await?
+            .ok_or_else(AppError::InvalidTwoFactorChallenge)
+    }
+
+    /// Deletes all expired challenges.
+    /// Returns the number of deleted challenges.
+    pub async fn delete_expired(db: &impl ConnectionTrait, now: DateTime<Utc>) -> AppResult<u64> {
+        Ok(Entity::delete_many()
+            .filter(Column::ExpiresAt.lte(now))
+            .exec(db)
+            .await?
+            .rows_affected)
+    }
+}
@@ -1,7 +1,22 @@
-use super::_entities::user_permissions::{ActiveModel, Entity};
+use super::_entities::user_permissions::{ActiveModel, Column, Entity, Model};
+use crate::error::app_error::{AppError, AppResult};
+use enumflags2::{bitflags, BitFlags};
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{IntoActiveModel, QuerySelect};
+
 pub type UserPermissions = Entity;
 
+#[bitflags]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Permission {
+    Read = 0b001,
+    Write = 0b010,
+    Delete = 0b100,
+}
+
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
     // extend activemodel below (keep comment for generators)
@@ -19,3 +34,143 @@ impl ActiveModelBehavior for ActiveModel {
         }
     }
 }
+
+impl Model {
+    pub fn get_permissions(&self) -> BitFlags<Permission> {
+        BitFlags::from_bits_truncate(self.permissions as u8)
+    }
+
+    /// Grants the given permissions on an entity to a user.
+    /// Already existing permissions of the user for this entity are extended, not replaced.
+    pub async fn grant(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        entity_type: &str,
+        entity_id: i64,
+        permissions: BitFlags<Permission>,
+    ) -> AppResult<Self> {
+        let existing = Entity::find_by_id((user_id, entity_type.to_string(), entity_id))
+            .one(db)
+            .await?;
+
+        let model = match existing {
+            Some(existing) => {
+                let permissions = existing.get_permissions() | permissions;
+                let mut active_model = existing.into_active_model();
+                active_model.permissions = Set(permissions.bits() as i32);
+
+                active_model.update(db).await?
+            }
+            None => {
+                ActiveModel {
+                    user_id: Set(user_id),
+                    entity_type: Set(entity_type.to_string()),
+                    entity_id: Set(entity_id),
+                    permissions: Set(permissions.bits() as i32),
+                    created_at: Set(chrono::Utc::now().into()),
+                    updated_at: Set(chrono::Utc::now().into()),
+                }
+                .insert(db)
+                .await?
+            }
+        };
+
+        Ok(model)
+    }
+
+    pub async fn find_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        entity_type: &str,
+        entity_id: i64,
+    ) -> AppResult<BitFlags<Permission>> {
+        let model = Entity::find_by_id((user_id, entity_type.to_string(), entity_id))
+            .one(db)
+            .await?;
+
+        Ok(model.map(|model| model.get_permissions()).unwrap_or_default())
+    }
+
+    pub async fn has_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        entity_type: &str,
+        entity_id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<bool> {
+        let permissions = Self::find_permissions(db, user_id, entity_type, entity_id).await?;
+
+        Ok(permissions.contains(required))
+    }
+
+    /// Ensures that the user holds all required permissions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EntityNotFound` when the user can not even read the entity (to not leak its existence)
+    /// and `MissingPermissions` when the user can read the entity but lacks any other required permission.
+    pub async fn ensure_permissions(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        entity_type: &str,
+        entity_id: i64,
+        required: BitFlags<Permission>,
+    ) -> AppResult<()> {
+        let permissions = Self::find_permissions(db, user_id, entity_type, entity_id).await?;
+
+        if !permissions.contains(Permission::Read) {
+            return Err(AppError::EntityNotFound());
+        }
+
+        if !permissions.contains(required) {
+            return Err(AppError::MissingPermissions());
+        }
+
+        Ok(())
+    }
+
+    /// Builds a sub query that selects the ids of all entities of the given type
+    /// on which the user holds the required permissions.
+    pub fn accessible_entity_ids(user_id: i64, entity_type: &str, required: BitFlags<Permission>) -> SelectStatement {
+        let bits = required.bits() as i32;
+
+        Query::select()
+            .column(Column::EntityId)
+            .from(Entity)
+            .and_where(Column::UserId.eq(user_id))
+            .and_where(Column::EntityType.eq(entity_type))
+            .and_where(Expr::col(Column::Permissions).bit_and(bits).eq(bits))
+            .to_owned()
+    }
+
+    /// Finds the ids of all entities of the given type that the user may delete and nobody else has access to.
+    pub async fn find_exclusively_owned_ids(
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        entity_type: &str,
+    ) -> AppResult<Vec<i64>> {
+        let shared = Query::select()
+            .column(Column::EntityId)
+            .from(Entity)
+            .and_where(Column::EntityType.eq(entity_type))
+            .and_where(Column::UserId.ne(user_id))
+            .to_owned();
+
+        let bits = BitFlags::from(Permission::Delete).bits() as i32;
+
+        Ok(Entity::find()
+            .select_only()
+            .column(Column::EntityId)
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Expr::col(Column::Permissions).bit_and(bits).eq(bits))
+            .filter(Column::EntityId.not_in_subquery(shared))
+            .into_tuple::<i64>()
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_all_by_user_id(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Vec<Self>> {
+        Ok(Entity::find().filter(Column::UserId.eq(user_id)).all(db).await?)
+    }
+}
@@ -1,16 +1,23 @@
 pub use super::_entities::users::{self, ActiveModel, Entity, Model};
 use crate::controllers::user::RegisterParams;
 use crate::error::app_error::{AppError, AppResult};
-use crate::models::_entities::sessions;
-use crate::services::secret_generator::SecretGenerator;
+use crate::models::_entities::{personal_access_tokens, recovery_codes, sessions, two_factor_challenges};
+use crate::services::secret_generator::{SecretGenerator, SecretGeneratorInner};
 use crate::services::snowflake_generator::SnowflakeGenerator;
+use crate::types::pagination::{IdSort, Page, Pagination};
+use crate::utils::totp;
 use chrono::offset::Local;
+use chrono::{DateTime, Duration, Utc};
 use enumflags2::_internal::RawBitFlags;
-use enumflags2::bitflags;
+use enumflags2::{bitflags, BitFlags};
 use loco_rs::{hash, prelude::*};
 use sea_orm::prelude::Expr;
+use sea_orm::sea_query::Func;
 use sea_orm::sea_query::IntoCondition;
-use sea_orm::{JoinType, PaginatorTrait, QuerySelect, RelationTrait};
+use sea_orm::{Condition, JoinType, PaginatorTrait, QuerySelect, RelationTrait};
+
+/// The number of days after which an account is deleted once the user requested its deletion.
+pub const ACCOUNT_DELETION_GRACE_PERIOD_DAYS: i64 = 30;
 
 #[bitflags(default = User)]
 #[repr(u8)]
@@ -82,6 +89,27 @@ impl super::_entities::users::Model {
             .await?)
     }
 
+    /// finds a user with a pending email change by the new email and the verification token sent to it
+    ///
+    /// # Errors
+    ///
+    /// When could not find user by the given token or DB query error
+    pub async fn find_by_pending_email_verification_token(
+        db: &DatabaseConnection,
+        pending_email: &str,
+        token: &str,
+    ) -> Result<Option<Self>, AppError> {
+        Ok(users::Entity::find()
+            .filter(
+                query::condition()
+                    .eq(users::Column::PendingEmail, pending_email)
+                    .eq(users::Column::EmailVerificationToken, token)
+                    .build(),
+            )
+            .one(db)
+            .await?)
+    }
+
     /// finds a user by the provided reset token
     ///
     /// # Errors
@@ -114,13 +142,13 @@ impl super::_entities::users::Model {
     ///
     /// When could not find user by the given token or DB query error
     pub async fn find_by_api_key(db: &DatabaseConnection, api_key: &str) -> ModelResult<Self> {
-        let cloned_api_key = api_key.to_string();
+        let api_key_hash = SecretGeneratorInner::hash_token(api_key);
         let user = users::Entity::find()
             .join(
                 JoinType::LeftJoin,
                 sessions::Relation::Users.def().rev().on_condition(move |_left, right| {
-                    Expr::col((right, sessions::Column::ApiKey))
-                        .eq(cloned_api_key.as_str())
+                    Expr::col((right, sessions::Column::ApiKeyHash))
+                        .eq(api_key_hash.as_str())
                         .into_condition()
                 }),
             )
@@ -129,6 +157,85 @@ impl super::_entities::users::Model {
         user.ok_or_else(|| ModelError::EntityNotFound)
     }
 
+    /// Finds all users whose scheduled account deletion is due.
+    pub async fn find_all_due_for_deletion(db: &DatabaseConnection, now: DateTime<Utc>) -> AppResult<Vec<Self>> {
+        Ok(users::Entity::find()
+            .filter(users::Column::DeletionScheduledAt.lte(now))
+            .all(db)
+            .await?)
+    }
+
+    /// Searches all users by their email address or name, for admins.
+    pub async fn search(
+        db: &DatabaseConnection,
+        text: Option<&str>,
+        pagination: &Pagination<IdSort>,
+    ) -> AppResult<Page<Self>> {
+        let mut query = users::Entity::find();
+        if let Some(text) = text.map(str::trim).filter(|text| !text.is_empty()) {
+            let pattern = format!("%{}%", text.to_lowercase());
+            query = query.filter(
+                Condition::any()
+                    .add(Expr::expr(Func::lower(Expr::col(users::Column::Email))).like(pattern.as_str()))
+                    .add(Expr::expr(Func::lower(Expr::col(users::Column::Name))).like(pattern.as_str())),
+            );
+        }
+
+        pagination.load(db, query).await
+    }
+
+    pub fn get_flags(&self) -> BitFlags<UserFlags> {
+        BitFlags::from_bits_truncate(self.flags as u8)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.get_flags().contains(UserFlags::Admin)
+    }
+
+    /// Whether an admin disabled the account, which prevents logging in.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    /// Whether the user has to enter a TOTP code, besides the password, to log in.
+    pub fn is_two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+
+    /// Verifies a TOTP code of the enrolled secret or, if two-factor authentication is enabled,
+    /// redeems a recovery code.
+    ///
+    /// Every TOTP code is only accepted once (RFC 6238, section 5.2): codes of the last accepted
+    /// or an earlier time step are rejected, so an observed code can not be used to log in again.
+    pub async fn verify_two_factor_code(&self, db: &DatabaseConnection, code: &str) -> AppResult<bool> {
+        let Some(secret) = &self.totp_secret else {
+            return Ok(false);
+        };
+
+        let totp = totp::build_totp(secret, &self.email)?;
+        if let Some(step) = totp::verify_code(&totp, code, Utc::now().timestamp() as u64) {
+            // A single conditional update, so concurrent logins can not both accept the same step.
+            let step = step as i64;
+            let result = users::Entity::update_many()
+                .col_expr(users::Column::TotpLastStep, Expr::value(step))
+                .filter(users::Column::Id.eq(self.id))
+                .filter(
+                    Condition::any()
+                        .add(users::Column::TotpLastStep.is_null())
+                        .add(users::Column::TotpLastStep.lt(step)),
+                )
+                .exec(db)
+                .await?;
+
+            return Ok(result.rows_affected > 0);
+        }
+        if !self.is_two_factor_enabled() {
+            return Ok(false);
+        }
+
+        recovery_codes::Model::redeem(db, self.id, code).await
+    }
+
     /// Verifies whether the provided plain password matches the hashed password
     ///
     /// # Errors
@@ -146,12 +253,10 @@ impl super::_entities::users::Model {
     ///
     /// When could not save the user into the DB
     pub async fn create_with_password(
-        db: &DatabaseConnection,
+        db: &impl ConnectionTrait,
         snowflake_generator: &SnowflakeGenerator,
         params: &RegisterParams,
     ) -> Result<Self, AppError> {
-        let txn = db.begin().await?;
-
         let password_hash = hash::hash_password(&params.password)?;
         let user = users::ActiveModel {
             id: ActiveValue::set(snowflake_generator.next_id().map_err(|e| ModelError::Any(e.into()))?),
@@ -162,16 +267,21 @@ impl super::_entities::users::Model {
             email_verification_token: Default::default(),
             email_verification_sent_at: Default::default(),
             email_verified_at: Default::default(),
+            preferred_currency_id: Default::default(),
+            deletion_scheduled_at: Default::default(),
+            totp_secret: Default::default(),
+            totp_enabled_at: Default::default(),
+            totp_last_step: Default::default(),
+            pending_email: Default::default(),
+            disabled_at: Default::default(),
             name: ActiveValue::set(params.name.to_string()),
             flags: ActiveValue::set(UserFlags::DEFAULT as i32),
             created_at: Default::default(),
             updated_at: Default::default(),
         }
-        .insert(&txn)
+        .insert(db)
         .await?;
 
-        txn.commit().await?;
-
         Ok(user)
     }
 }
@@ -231,7 +341,7 @@ impl super::_entities::users::ActiveModel {
     /// # Errors
     ///
     /// when has DB query error
-    pub async fn verified(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+    pub async fn verified(mut self, db: &impl ConnectionTrait) -> ModelResult<Model> {
         self.email_verified_at = ActiveValue::set(Some(Local::now().into()));
         self.email_verification_token = ActiveValue::Set(None);
         Ok(self.update(db).await?)
@@ -241,7 +351,7 @@ impl super::_entities::users::ActiveModel {
     /// updates it in the database.
     ///
     /// This method hashes the provided password and sets it as the new password
-    /// for the user.
+    /// for the user. All existing sessions and personal access tokens of the user are revoked.
     ///
     /// # Errors
     ///
@@ -250,6 +360,220 @@ impl super::_entities::users::ActiveModel {
         self.password = ActiveValue::set(hash::hash_password(password).map_err(|e| ModelError::Any(e.into()))?);
         self.reset_token = ActiveValue::Set(None);
         self.reset_sent_at = ActiveValue::Set(None);
+
+        let txn = db.begin().await?;
+        let user = self.update(&txn).await?;
+        sessions::Entity::delete_many()
+            .filter(sessions::Column::UserId.eq(user.id))
+            .exec(&txn)
+            .await?;
+        personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::UserId.eq(user.id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Changes the password and revokes all personal access tokens and sessions of the user, except for the given session.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error or could not hashed the given password
+    pub async fn change_password(
+        mut self,
+        db: &DatabaseConnection,
+        password: &str,
+        except_session_id: i64,
+    ) -> ModelResult<Model> {
+        self.password = ActiveValue::set(hash::hash_password(password).map_err(|e| ModelError::Any(e.into()))?);
+
+        let txn = db.begin().await?;
+        let user = self.update(&txn).await?;
+        sessions::Entity::delete_many()
+            .filter(sessions::Column::UserId.eq(user.id))
+            .filter(sessions::Column::Id.ne(except_session_id))
+            .exec(&txn)
+            .await?;
+        personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::UserId.eq(user.id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Stores the new email address of a requested email change.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_pending_email(mut self, db: &DatabaseConnection, pending_email: String) -> ModelResult<Model> {
+        self.pending_email = ActiveValue::set(Some(pending_email));
+        Ok(self.update(db).await?)
+    }
+
+    /// Replaces the email address with the pending one, which counts as verified.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error or the user has no pending email
+    pub async fn confirm_email_change(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        let pending_email = self
+            .pending_email
+            .take()
+            .flatten()
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        self.email = ActiveValue::set(pending_email);
+        self.pending_email = ActiveValue::Set(None);
+        self.email_verified_at = ActiveValue::set(Some(Local::now().into()));
+        self.email_verification_token = ActiveValue::Set(None);
+        Ok(self.update(db).await?)
+    }
+
+    /// Schedules the deletion of the account after the grace period.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn schedule_deletion(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        let deletion_at = Utc::now() + Duration::days(ACCOUNT_DELETION_GRACE_PERIOD_DAYS);
+        self.deletion_scheduled_at = ActiveValue::set(Some(deletion_at.into()));
+        Ok(self.update(db).await?)
+    }
+
+    /// Cancels a scheduled deletion of the account.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn cancel_deletion(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.deletion_scheduled_at = ActiveValue::Set(None);
+        Ok(self.update(db).await?)
+    }
+
+    /// Stores a new TOTP secret, which replaces the one of an unconfirmed enrollment.
+    /// Two-factor authentication stays disabled until a code of the secret is confirmed.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn enroll_two_factor(mut self, db: &DatabaseConnection, secret: String) -> ModelResult<Model> {
+        self.totp_secret = ActiveValue::set(Some(secret));
+        self.totp_enabled_at = ActiveValue::Set(None);
+        self.totp_last_step = ActiveValue::Set(None);
+        Ok(self.update(db).await?)
+    }
+
+    /// Enables two-factor authentication with the enrolled TOTP secret.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn enable_two_factor(mut self, db: &impl ConnectionTrait) -> ModelResult<Model> {
+        self.totp_enabled_at = ActiveValue::set(Some(Utc::now().into()));
+        Ok(self.update(db).await?)
+    }
+
+    /// Disables two-factor authentication and removes the TOTP secret with all recovery codes.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn disable_two_factor(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.totp_secret = ActiveValue::Set(None);
+        self.totp_enabled_at = ActiveValue::Set(None);
+        self.totp_last_step = ActiveValue::Set(None);
+
+        let txn = db.begin().await?;
+        let user = self.update(&txn).await?;
+        recovery_codes::Entity::delete_many()
+            .filter(recovery_codes::Column::UserId.eq(user.id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Grants or revokes the admin flag.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_admin(mut self, db: &impl ConnectionTrait, admin: bool) -> ModelResult<Model> {
+        let mut flags: BitFlags<UserFlags> = BitFlags::from_bits_truncate(*self.flags.as_ref() as u8);
+        flags.set(UserFlags::Admin, admin);
+        self.flags = ActiveValue::set(flags.bits() as i32);
+        Ok(self.update(db).await?)
+    }
+
+    /// Replaces the password with a random one, so the user has to reset it,
+    /// and revokes all sessions and personal access tokens.
+    /// The reset token is sent to the user like for a forgotten password.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error or could not hashed the generated password
+    pub async fn force_password_reset(
+        mut self,
+        db: &impl ConnectionTrait,
+        secret_generator: &SecretGenerator,
+    ) -> ModelResult<Model> {
+        const RESET_TOKEN_LENGTH: usize = 8;
+        const RANDOM_PASSWORD_LENGTH: usize = 64;
+        let password = secret_generator.generate_token_with_length(RANDOM_PASSWORD_LENGTH);
+        self.password = ActiveValue::set(hash::hash_password(&password).map_err(|e| ModelError::Any(e.into()))?);
+        self.reset_sent_at = ActiveValue::set(Some(Local::now().into()));
+        self.reset_token = ActiveValue::Set(Some(secret_generator.generate_token_with_length(RESET_TOKEN_LENGTH)));
+
+        let user = self.update(db).await?;
+        sessions::Entity::delete_many()
+            .filter(sessions::Column::UserId.eq(user.id))
+            .exec(db)
+            .await?;
+        personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::UserId.eq(user.id))
+            .exec(db)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Disables the account and revokes all of its sessions, personal access tokens and pending logins.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn disable(mut self, db: &impl ConnectionTrait) -> ModelResult<Model> {
+        self.disabled_at = ActiveValue::set(Some(Utc::now().into()));
+
+        let user = self.update(db).await?;
+        sessions::Entity::delete_many()
+            .filter(sessions::Column::UserId.eq(user.id))
+            .exec(db)
+            .await?;
+        personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::UserId.eq(user.id))
+            .exec(db)
+            .await?;
+        two_factor_challenges::Entity::delete_many()
+            .filter(two_factor_challenges::Column::UserId.eq(user.id))
+            .exec(db)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Enables a disabled account again.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn enable(mut self, db: &impl ConnectionTrait) -> ModelResult<Model> {
+        self.disabled_at = ActiveValue::Set(None);
         Ok(self.update(db).await?)
     }
 }
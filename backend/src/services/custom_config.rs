@@ -5,6 +5,7 @@ use loco_rs::Error;
 use serde::Deserialize;
 use std::env;
 use std::fs::read_to_string;
+use std::net::IpAddr;
 use std::sync::{Arc, OnceLock};
 use tera::{Context, Tera};
 use tracing::{debug, error};
@@ -14,8 +15,147 @@ pub const DEFAULT_CONFIG_FOLDER: &str = "config";
 
 pub type CustomConfig = Arc<CustomConfigInner>;
 
-#[derive(Debug, Deserialize)]
-pub struct CustomConfigInner {}
+#[derive(Debug, Default, Deserialize)]
+pub struct CustomConfigInner {
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub registration: RegistrationConfig,
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// The number of hours after its creation after which a session expires. `null` disables the limit.
+    pub lifetime_hours: Option<i64>,
+    /// The number of hours without any request after which a session expires. `null` disables the limit.
+    pub idle_timeout_hours: Option<i64>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            lifetime_hours: Some(24 * 90),
+            idle_timeout_hours: Some(24 * 30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// The addresses of the reverse proxies in front of the server.
+    /// Only requests from these addresses may set the client address with the `X-Forwarded-For` or `Forwarded` header.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RegistrationConfig {
+    pub mode: RegistrationMode,
+}
+
+/// Who is allowed to register a new account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationMode {
+    /// Everyone can register.
+    #[default]
+    Open,
+    /// Registering requires an invite code created by an admin.
+    InviteOnly,
+    /// Nobody can register, accounts have to exist already.
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// The limit of all routes that do not belong to a group.
+    pub default: RateLimit,
+    /// Limits for all routes below a path prefix. The group with the longest matching prefix applies.
+    pub groups: Vec<RateLimitGroup>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default: RateLimit {
+                capacity: 120,
+                refill_per_second: 2.0,
+            },
+            groups: vec![
+                RateLimitGroup {
+                    name: "sessions".to_string(),
+                    path_prefix: "/api/v1/sessions".to_string(),
+                    limit: RateLimit {
+                        capacity: 20,
+                        refill_per_second: 0.2,
+                    },
+                },
+                RateLimitGroup {
+                    name: "users".to_string(),
+                    path_prefix: "/api/v1/users".to_string(),
+                    limit: RateLimit {
+                        capacity: 20,
+                        refill_per_second: 0.2,
+                    },
+                },
+            ],
+        }
+    }
+}
+
+/// A token bucket. Every request takes a token, the bucket refills continuously up to its capacity.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RateLimit {
+    /// The number of requests that can be made in a burst.
+    pub capacity: u32,
+    /// The number of requests that become available again per second. Has to be greater than zero.
+    pub refill_per_second: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RateLimitGroup {
+    /// Keeps the buckets of the groups apart, has to be unique.
+    pub name: String,
+    pub path_prefix: String,
+    #[serde(flatten)]
+    pub limit: RateLimit,
+}
+
+impl RateLimitConfig {
+    /// Returns the name of the group and the limit that applies to the path.
+    pub fn limit_for_path(&self, path: &str) -> (&str, &RateLimit) {
+        self.groups
+            .iter()
+            .filter(|group| path.starts_with(group.path_prefix.as_str()))
+            .max_by_key(|group| group.path_prefix.len())
+            .map_or(("default", &self.default), |group| (group.name.as_str(), &group.limit))
+    }
+
+    /// Checks that every bucket refills, as the waiting times are divided by the refill rate.
+    pub fn validate(&self) -> Result<(), String> {
+        let limits = std::iter::once(("default", &self.default))
+            .chain(self.groups.iter().map(|group| (group.name.as_str(), &group.limit)));
+        for (name, limit) in limits {
+            if !(limit.refill_per_second > 0.0 && limit.refill_per_second.is_finite()) {
+                return Err(format!(
+                    "The refill_per_second of the rate limit '{}' has to be greater than zero.",
+                    name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
 
 impl Service for CustomConfigInner {
     async fn new(ctx: &AppContext) -> loco_rs::Result<Self> {
@@ -59,11 +199,18 @@ impl CustomConfigInner {
     fn load_from_string(yaml: String, path: String) -> loco_rs::Result<Self> {
         let rendered = Tera::one_off(yaml.as_str(), &Context::new(), false)?;
 
-        serde_yml::from_str(&rendered).map_err(|err| {
+        let config: Self = serde_yml::from_str(&rendered).map_err(|err| {
             error!("Yaml Error: {} | File: {}", err.to_string(), path);
 
             Error::Any(err.into())
-        })
+        })?;
+        config.rate_limit.validate().map_err(|err| {
+            error!("Config Error: {} | File: {}", err, path);
+
+            Error::Message(err)
+        })?;
+
+        Ok(config)
     }
 }
 
@@ -81,6 +228,87 @@ mod tests {
         let path = "test_path.yaml".to_string();
         let result = CustomConfigInner::load_from_string(yaml.to_string(), path);
         assert!(result.is_ok());
+        assert_eq!(result.unwrap().session, SessionConfig::default());
+    }
+
+    #[test]
+    fn test_load_session_config() {
+        let yaml = "session:\n  lifetime_hours: 12\n  idle_timeout_hours: null\n";
+        let path = "test_path.yaml".to_string();
+        let config = CustomConfigInner::load_from_string(yaml.to_string(), path).unwrap();
+        assert_eq!(config.session.lifetime_hours, Some(12));
+        assert_eq!(config.session.idle_timeout_hours, None);
+    }
+
+    #[test]
+    fn test_load_registration_config() {
+        let path = "test_path.yaml".to_string();
+        let config = CustomConfigInner::load_from_string(String::new(), path.clone()).unwrap();
+        assert_eq!(config.registration.mode, RegistrationMode::Open);
+
+        let yaml = "registration:\n  mode: invite_only\n";
+        let config = CustomConfigInner::load_from_string(yaml.to_string(), path).unwrap();
+        assert_eq!(config.registration.mode, RegistrationMode::InviteOnly);
+    }
+
+    #[test]
+    fn test_load_proxy_config() {
+        let path = "test_path.yaml".to_string();
+        let config = CustomConfigInner::load_from_string(String::new(), path.clone()).unwrap();
+        assert!(config.proxy.trusted_proxies.is_empty());
+
+        let yaml = "proxy:\n  trusted_proxies: [10.0.0.2, \"::1\"]\n";
+        let config = CustomConfigInner::load_from_string(yaml.to_string(), path).unwrap();
+        assert_eq!(config.proxy.trusted_proxies.len(), 2);
+    }
+
+    #[test]
+    fn test_load_rate_limit_config() {
+        let yaml = r#"
+rate_limit:
+  default:
+    capacity: 10
+    refill_per_second: 1
+  groups:
+    - name: import
+      path_prefix: /api/v1/import
+      capacity: 2
+      refill_per_second: 0.5
+    - name: import-profiles
+      path_prefix: /api/v1/import/profiles
+      capacity: 5
+      refill_per_second: 0.5
+"#;
+        let path = "test_path.yaml".to_string();
+        let config = CustomConfigInner::load_from_string(yaml.to_string(), path).unwrap();
+        let rate_limit = config.rate_limit;
+        assert!(rate_limit.enabled);
+        assert_eq!(
+            rate_limit.limit_for_path("/api/v1/transactions"),
+            ("default", &rate_limit.default)
+        );
+        assert_eq!(rate_limit.limit_for_path("/api/v1/import/csv").1.capacity, 2);
+        assert_eq!(
+            rate_limit.limit_for_path("/api/v1/import/profiles/1").0,
+            "import-profiles"
+        );
+    }
+
+    #[test]
+    fn test_reject_rate_limit_without_refill() {
+        let yaml = r#"
+rate_limit:
+  groups:
+    - name: import
+      path_prefix: /api/v1/import
+      capacity: 2
+      refill_per_second: 0
+"#;
+        let path = "test_path.yaml".to_string();
+        assert!(CustomConfigInner::load_from_string(yaml.to_string(), path.clone()).is_err());
+
+        let yaml = "rate_limit:\n  default:\n    capacity: 10\n    refill_per_second: -1\n";
+        assert!(CustomConfigInner::load_from_string(yaml.to_string(), path).is_err());
     }
 
     #[test]
@@ -0,0 +1,194 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{budget_criteria, budgets, file_attachments, sessions, user_permissions, users};
+use crate::models::user_permissions::Permission;
+use crate::services::export::json::write_json;
+use crate::services::export::{export_error, write_zip, ExportData};
+use crate::views::user::UserResponse;
+use chrono::{DateTime, FixedOffset};
+use loco_rs::app::AppContext;
+use sea_orm::{ColumnTrait, EntityName, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Everything that is stored about a user, for the full account export.
+pub struct ArchiveData {
+    pub user: users::Model,
+    pub sessions: Vec<sessions::Model>,
+    pub permissions: Vec<user_permissions::Model>,
+    pub budgets: Vec<budgets::Model>,
+    pub budget_criteria: Vec<budget_criteria::Model>,
+    pub data: ExportData,
+    /// The attachments of the transactions together with the content of their files.
+    pub attachments: Vec<(file_attachments::Model, Vec<u8>)>,
+}
+
+/// A session without its api key, which must never be part of an export.
+#[derive(Serialize)]
+struct ArchiveSession<'a> {
+    id: i64,
+    name: &'a Option<String>,
+    user_agent: &'a Option<String>,
+    last_accessed_at: Option<DateTime<FixedOffset>>,
+    created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize)]
+struct ArchiveBudget<'a> {
+    #[serde(flatten)]
+    budget: &'a budgets::Model,
+    criteria: Option<&'a budget_criteria::Model>,
+}
+
+#[derive(Serialize)]
+struct ArchiveAttachment<'a> {
+    #[serde(flatten)]
+    attachment: &'a file_attachments::Model,
+    /// The path of the file inside the archive.
+    file: String,
+}
+
+impl ArchiveData {
+    /// Loads everything of the user. Attachment files that are missing in the storage are skipped.
+    pub async fn load(ctx: &AppContext, user_id: i64) -> AppResult<Self> {
+        let db = &ctx.db;
+        let user = users::Model::find_by_id(db, user_id).await?;
+        let sessions = sessions::Entity::find()
+            .filter(sessions::Column::UserId.eq(user_id))
+            .order_by_asc(sessions::Column::CreatedAt)
+            .all(db)
+            .await?;
+        let budgets = budgets::Entity::find()
+            .filter(
+                budgets::Column::Id.in_subquery(user_permissions::Model::accessible_entity_ids(
+                    user_id,
+                    budgets::Entity.table_name(),
+                    Permission::Read.into(),
+                )),
+            )
+            .order_by_asc(budgets::Column::Id)
+            .all(db)
+            .await?;
+        let budget_criteria = budget_criteria::Entity::find()
+            .filter(budget_criteria::Column::Id.is_in(budgets.iter().map(|budget| budget.criteria_id)))
+            .all(db)
+            .await?;
+        let data = ExportData::load(db, user_id).await?;
+
+        let attachment_ids: HashSet<i64> = data
+            .transactions
+            .iter()
+            .filter_map(|transaction| transaction.file_attachment_id)
+            .collect();
+        let mut attachments = Vec::new();
+        for attachment in file_attachments::Entity::find()
+            .filter(file_attachments::Column::Id.is_in(attachment_ids))
+            .order_by_asc(file_attachments::Column::Id)
+            .all(db)
+            .await?
+        {
+            match ctx.storage.download::<Vec<u8>>(&PathBuf::from(&attachment.path)).await {
+                Ok(content) => attachments.push((attachment, content)),
+                Err(err) => warn!("Skipping attachment {} in the account export: {}", attachment.id, err),
+            }
+        }
+
+        Ok(Self {
+            user,
+            sessions,
+            permissions: user_permissions::Model::find_all_by_user_id(db, user_id).await?,
+            budgets,
+            budget_criteria,
+            data,
+            attachments,
+        })
+    }
+}
+
+/// Writes everything of the user into a zip archive of json documents and the attachment files.
+/// Passwords, tokens and api keys are left out.
+pub fn write_archive(archive: &ArchiveData) -> AppResult<Vec<u8>> {
+    let user = UserResponse::from(archive.user.clone());
+    let sessions: Vec<ArchiveSession> = archive
+        .sessions
+        .iter()
+        .map(|session| ArchiveSession {
+            id: session.id,
+            name: &session.name,
+            user_agent: &session.user_agent,
+            last_accessed_at: session.last_accessed_at,
+            created_at: session.created_at,
+        })
+        .collect();
+    let budgets: Vec<ArchiveBudget> = archive
+        .budgets
+        .iter()
+        .map(|budget| ArchiveBudget {
+            budget,
+            criteria: archive
+                .budget_criteria
+                .iter()
+                .find(|criteria| criteria.id == budget.criteria_id),
+        })
+        .collect();
+    let attachments: Vec<ArchiveAttachment> = archive
+        .attachments
+        .iter()
+        .map(|(attachment, _)| ArchiveAttachment {
+            attachment,
+            file: attachment_file(attachment),
+        })
+        .collect();
+
+    let mut files = vec![
+        ("user.json".to_string(), to_json_bytes(&user)?),
+        ("sessions.json".to_string(), to_json_bytes(&sessions)?),
+        ("permissions.json".to_string(), to_json_bytes(&archive.permissions)?),
+        ("budgets.json".to_string(), to_json_bytes(&budgets)?),
+        ("data.json".to_string(), write_json(&archive.data)?),
+        ("attachments.json".to_string(), to_json_bytes(&attachments)?),
+    ];
+    files.extend(
+        archive
+            .attachments
+            .iter()
+            .map(|(attachment, content)| (attachment_file(attachment), content.clone())),
+    );
+
+    write_zip(files)
+}
+
+fn to_json_bytes(value: &impl Serialize) -> AppResult<Vec<u8>> {
+    serde_json::to_vec_pretty(value).map_err(export_error)
+}
+
+/// The path of the attachment inside the archive. The id keeps equally named attachments apart.
+fn attachment_file(attachment: &file_attachments::Model) -> String {
+    format!(
+        "attachments/{}-{}",
+        attachment.id,
+        attachment.name.replace(['/', '\\'], "_")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_file() {
+        let attachment = file_attachments::Model {
+            id: 7,
+            globally_accessible: false,
+            name: "../receipts\\march.pdf".to_string(),
+            path: "attachments/7".to_string(),
+            r#type: "application/pdf".to_string(),
+            size: 3,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        };
+
+        assert_eq!(attachment_file(&attachment), "attachments/7-.._receipts_march.pdf");
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::services::export::{export_error, format_amount, write_zip, ExportData};
+use csv::Writer;
+
+/// Writes one csv file per kind of entity and bundles them into a zip archive.
+pub fn write_csv_archive(data: &ExportData) -> AppResult<Vec<u8>> {
+    write_zip([
+        ("transactions.csv", transactions_csv(data)?),
+        ("bank_accounts.csv", bank_accounts_csv(data)?),
+        ("categories.csv", categories_csv(data)?),
+        ("tags.csv", tags_csv(data)?),
+    ])
+}
+
+fn transactions_csv(data: &ExportData) -> AppResult<Vec<u8>> {
+    let category_paths = data.category_paths();
+    let category = |category_id: Option<i64>| {
+        category_id
+            .and_then(|id| category_paths.get(&id))
+            .map(|path| path.join(":"))
+            .unwrap_or_default()
+    };
+
+    let mut writer = Writer::from_writer(Vec::new());
+    writer
+        .write_record([
+            "id",
+            "date",
+            "type",
+            "name",
+            "amount",
+            "currency",
+            "destination_amount",
+            "source_bank_account",
+            "destination_bank_account",
+            "counterparty_name",
+            "counterparty_iban",
+            "category",
+            "split_id",
+            "split_amount",
+            "split_category",
+            "split_note",
+            "tags",
+            "purpose",
+            "note",
+        ])
+        .map_err(export_error)?;
+
+    for transaction in &data.transactions {
+        let currency = data.currency(transaction.currency_id);
+        let decimal_places = currency.map_or(0, |currency| currency.decimal_places);
+        let destination_decimal_places = data
+            .bank_account(transaction.destination_id)
+            .and_then(|bank_account| data.currency(bank_account.currency_id))
+            .map_or(decimal_places, |currency| currency.decimal_places);
+        let r#type = match transaction.r#type {
+            TransactionType::Income => "income",
+            TransactionType::Expense => "expense",
+            TransactionType::Transfer => "transfer",
+        };
+        let fields = [
+            transaction.id.to_string(),
+            transaction.effective_date().to_rfc3339(),
+            r#type.to_string(),
+            transaction.name.clone(),
+            format_amount(transaction.amount, decimal_places),
+            currency.map(|currency| currency.symbol.clone()).unwrap_or_default(),
+            transaction
+                .destination_amount
+                .map(|amount| format_amount(amount, destination_decimal_places))
+                .unwrap_or_default(),
+            data.bank_account(transaction.source_id)
+                .map(|bank_account| bank_account.name.clone())
+                .unwrap_or_default(),
+            data.bank_account(transaction.destination_id)
+                .map(|bank_account| bank_account.name.clone())
+                .unwrap_or_default(),
+            transaction.counterparty_name().unwrap_or_default().to_string(),
+            transaction.counterparty_iban().unwrap_or_default().to_string(),
+            category(transaction.category_id),
+        ];
+        let trailer = [
+            data.tag_names(transaction.id).join(","),
+            transaction.purpose.clone().unwrap_or_default(),
+            transaction.note.clone().unwrap_or_default(),
+        ];
+
+        // A split transaction is written as one row per split line.
+        let splits = data.splits.get(&transaction.id).map(Vec::as_slice).unwrap_or_default();
+        if splits.is_empty() {
+            let no_split: [String; 4] = Default::default();
+            writer
+                .write_record(fields.iter().chain(&no_split).chain(&trailer))
+                .map_err(export_error)?;
+        }
+        for split in splits {
+            let split_fields = [
+                split.id.to_string(),
+                format_amount(split.amount, decimal_places),
+                category(split.category_id),
+                split.note.clone().unwrap_or_default(),
+            ];
+            writer
+                .write_record(fields.iter().chain(&split_fields).chain(&trailer))
+                .map_err(export_error)?;
+        }
+    }
+
+    writer.into_inner().map_err(export_error)
+}
+
+fn bank_accounts_csv(data: &ExportData) -> AppResult<Vec<u8>> {
+    let mut writer = Writer::from_writer(Vec::new());
+    writer
+        .write_record([
+            "id",
+            "name",
+            "description",
+            "iban",
+            "currency",
+            "balance",
+            "original_balance",
+        ])
+        .map_err(export_error)?;
+
+    for bank_account in &data.bank_accounts {
+        let currency = data.currency(bank_account.currency_id);
+        let decimal_places = currency.map_or(0, |currency| currency.decimal_places);
+        writer
+            .write_record([
+                bank_account.id.to_string(),
+                bank_account.name.clone(),
+                bank_account.description.clone().unwrap_or_default(),
+                bank_account.iban.clone().unwrap_or_default(),
+                currency.map(|currency| currency.symbol.clone()).unwrap_or_default(),
+                format_amount(bank_account.balance, decimal_places),
+                format_amount(bank_account.original_balance, decimal_places),
+            ])
+            .map_err(export_error)?;
+    }
+
+    writer.into_inner().map_err(export_error)
+}
+
+fn categories_csv(data: &ExportData) -> AppResult<Vec<u8>> {
+    let category_paths = data.category_paths();
+    let mut writer = Writer::from_writer(Vec::new());
+    writer
+        .write_record(["id", "parent_id", "name", "path"])
+        .map_err(export_error)?;
+
+    for category in &data.categories {
+        writer
+            .write_record([
+                category.id.to_string(),
+                category.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+                category.name.clone(),
+                category_paths
+                    .get(&category.id)
+                    .map(|path| path.join(":"))
+                    .unwrap_or_default(),
+            ])
+            .map_err(export_error)?;
+    }
+
+    writer.into_inner().map_err(export_error)
+}
+
+fn tags_csv(data: &ExportData) -> AppResult<Vec<u8>> {
+    let mut writer = Writer::from_writer(Vec::new());
+    writer.write_record(["id", "name"]).map_err(export_error)?;
+
+    for tag in &data.tags {
+        writer
+            .write_record([tag.id.to_string(), tag.name.clone()])
+            .map_err(export_error)?;
+    }
+
+    writer.into_inner().map_err(export_error)
+}
@@ -0,0 +1,461 @@
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::{bank_accounts, currencies, transactions};
+use crate::services::export::{format_amount, ExportData};
+use chrono::NaiveDate;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+const OPENING_BALANCES_ACCOUNT: &str = "Equity:Opening-Balances";
+/// The other side of a transfer from or to a bank account that is not part of the export.
+const TRANSFERS_ACCOUNT: &str = "Equity:Transfers";
+const UNCATEGORIZED: &str = "Uncategorized";
+
+/// The plain-text accounting syntax of a journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalSyntax {
+    /// Understood by ledger and hledger.
+    Ledger,
+    Beancount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    date: NaiveDate,
+    cleared: bool,
+    payee: Option<String>,
+    narration: String,
+    tags: Vec<String>,
+    metadata: Vec<(&'static str, String)>,
+    postings: Vec<Posting>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Posting {
+    account: String,
+    /// The amount with its commodity, e.g. `-12.34 EUR`.
+    amount: String,
+    /// The total cost in another commodity, for postings that convert between currencies.
+    total_price: Option<String>,
+}
+
+/// Writes a double-entry journal of all transactions.
+///
+/// Bank accounts become `Assets` accounts and categories become `Expenses` or `Income` accounts, depending on
+/// the type of the transaction. The original balances of the bank accounts are booked against the opening balances.
+pub fn write_journal(data: &ExportData, syntax: JournalSyntax) -> String {
+    let category_paths = data.category_paths();
+    let commodities: HashMap<i64, String> = data
+        .currencies
+        .iter()
+        .map(|currency| (currency.id, commodity(currency)))
+        .collect();
+    let decimal_places = |currency_id: i64| data.currency(currency_id).map_or(0, |currency| currency.decimal_places);
+    let amount = |amount: i64, currency_id: i64| {
+        let commodity = commodities
+            .get(&currency_id)
+            .cloned()
+            .unwrap_or_else(|| format!("C{currency_id}"));
+        format!("{} {}", format_amount(amount, decimal_places(currency_id)), commodity)
+    };
+    let asset_accounts = asset_accounts(&data.bank_accounts);
+    let asset_account = |bank_account: &bank_accounts::Model| {
+        asset_accounts
+            .get(&bank_account.id)
+            .cloned()
+            .unwrap_or_else(|| account(["Assets", &bank_account.name]))
+    };
+    let category_account = |root: &str, category_id: Option<i64>| {
+        let path = category_id.and_then(|id| category_paths.get(&id));
+        match path {
+            Some(path) => account([root].into_iter().chain(path.iter().map(String::as_str))),
+            None => account([root, UNCATEGORIZED]),
+        }
+    };
+
+    let first_date = data
+        .transactions
+        .iter()
+        .map(|transaction| transaction.effective_date().date_naive())
+        .chain(
+            data.bank_accounts
+                .iter()
+                .map(|bank_account| bank_account.created_at.date_naive()),
+        )
+        .min();
+
+    let mut entries = Vec::new();
+    for bank_account in &data.bank_accounts {
+        if bank_account.original_balance == 0 {
+            continue;
+        }
+        let original_balance = amount(bank_account.original_balance, bank_account.currency_id);
+        entries.push(Entry {
+            date: first_date.unwrap_or_else(|| bank_account.created_at.date_naive()),
+            cleared: true,
+            payee: None,
+            narration: format!("Opening balance of {}", bank_account.name),
+            tags: Vec::new(),
+            metadata: Vec::new(),
+            postings: vec![
+                Posting {
+                    account: asset_account(bank_account),
+                    amount: original_balance,
+                    total_price: None,
+                },
+                Posting {
+                    account: OPENING_BALANCES_ACCOUNT.to_string(),
+                    amount: amount(-bank_account.original_balance, bank_account.currency_id),
+                    total_price: None,
+                },
+            ],
+        });
+    }
+
+    for transaction in &data.transactions {
+        let source = data.bank_account(transaction.source_id);
+        let destination = data.bank_account(transaction.destination_id);
+        let splits = data.splits.get(&transaction.id).map(Vec::as_slice).unwrap_or_default();
+        let root = match transaction.r#type {
+            TransactionType::Income => "Income",
+            _ => "Expenses",
+        };
+        // The side of a transfer or of an income/expense that is not one of the exported bank accounts.
+        let counter_postings = |sign: i64| -> Vec<Posting> {
+            if transaction.r#type == TransactionType::Transfer {
+                return vec![Posting {
+                    account: TRANSFERS_ACCOUNT.to_string(),
+                    amount: amount(sign * transaction.amount, transaction.currency_id),
+                    total_price: None,
+                }];
+            }
+            if splits.is_empty() {
+                return vec![Posting {
+                    account: category_account(root, transaction.category_id),
+                    amount: amount(sign * transaction.amount, transaction.currency_id),
+                    total_price: None,
+                }];
+            }
+
+            splits
+                .iter()
+                .map(|split| Posting {
+                    account: category_account(root, split.category_id),
+                    amount: amount(sign * split.amount, transaction.currency_id),
+                    total_price: None,
+                })
+                .collect()
+        };
+
+        let mut postings = match source {
+            Some(source) => vec![Posting {
+                account: asset_account(source),
+                amount: amount(-transaction.amount, transaction.currency_id),
+                total_price: None,
+            }],
+            None => counter_postings(-1),
+        };
+        match destination {
+            Some(destination) => postings.push(destination_posting(
+                transaction,
+                asset_account(destination),
+                destination.currency_id,
+                &amount,
+            )),
+            None => postings.extend(counter_postings(1)),
+        }
+
+        let mut metadata = vec![("id", transaction.id.to_string())];
+        if let Some(iban) = transaction.counterparty_iban() {
+            metadata.push(("iban", iban.to_string()));
+        }
+        if let Some(purpose) = &transaction.purpose {
+            metadata.push(("purpose", purpose.clone()));
+        }
+        if let Some(note) = &transaction.note {
+            metadata.push(("note", note.clone()));
+        }
+        entries.push(Entry {
+            date: transaction.effective_date().date_naive(),
+            cleared: transaction.booking_date.is_some(),
+            payee: transaction.counterparty_name().map(str::to_string),
+            narration: transaction.name.clone(),
+            tags: data.tag_names(transaction.id).into_iter().map(tag).collect(),
+            metadata,
+            postings,
+        });
+    }
+
+    let mut journal = String::new();
+    let _ = writeln!(
+        journal,
+        "; Exported from financrr on {}",
+        chrono::Utc::now().date_naive()
+    );
+    let _ = writeln!(journal);
+
+    let accounts: BTreeSet<&str> = entries
+        .iter()
+        .flat_map(|entry| entry.postings.iter().map(|posting| posting.account.as_str()))
+        .collect();
+    for account in &accounts {
+        match syntax {
+            JournalSyntax::Ledger => {
+                let _ = writeln!(journal, "account {account}");
+            }
+            JournalSyntax::Beancount => {
+                let date = first_date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+                let _ = writeln!(journal, "{date} open {account}");
+            }
+        }
+    }
+
+    for entry in &entries {
+        let _ = writeln!(journal);
+        journal.push_str(&format_entry(entry, syntax));
+    }
+
+    journal
+}
+
+/// The `Assets` account of every bank account. Bank accounts whose names end up as the same account
+/// get their id appended, so their postings are not merged.
+fn asset_accounts(bank_accounts: &[bank_accounts::Model]) -> HashMap<i64, String> {
+    let accounts: Vec<(i64, String)> = bank_accounts
+        .iter()
+        .map(|bank_account| (bank_account.id, account(["Assets", &bank_account.name])))
+        .collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (_, account) in &accounts {
+        *counts.entry(account.as_str()).or_default() += 1;
+    }
+
+    accounts
+        .iter()
+        .map(|(id, account)| match counts[account.as_str()] > 1 {
+            true => (*id, format!("{account}-{id}")),
+            false => (*id, account.clone()),
+        })
+        .collect()
+}
+
+/// The posting of the bank account that receives the money. Money that arrives in a different currency is
+/// priced at the amount that left the source.
+fn destination_posting(
+    transaction: &transactions::Model,
+    account: String,
+    currency_id: i64,
+    amount: &impl Fn(i64, i64) -> String,
+) -> Posting {
+    match transaction.destination_amount {
+        Some(destination_amount) if currency_id != transaction.currency_id => Posting {
+            account,
+            amount: amount(destination_amount, currency_id),
+            total_price: Some(amount(transaction.amount, transaction.currency_id)),
+        },
+        _ => Posting {
+            account,
+            amount: amount(transaction.received_amount(), transaction.currency_id),
+            total_price: None,
+        },
+    }
+}
+
+fn format_entry(entry: &Entry, syntax: JournalSyntax) -> String {
+    let flag = if entry.cleared { "*" } else { "!" };
+    let mut text = String::new();
+
+    match syntax {
+        JournalSyntax::Ledger => {
+            let description = match &entry.payee {
+                Some(payee) => format!("{} | {}", single_line(payee), single_line(&entry.narration)),
+                None => single_line(&entry.narration),
+            };
+            let _ = writeln!(text, "{} {} {}", entry.date, flag, description);
+            if !entry.tags.is_empty() {
+                let _ = writeln!(text, "    ; :{}:", entry.tags.join(":"));
+            }
+            for (key, value) in &entry.metadata {
+                let _ = writeln!(text, "    ; {}: {}", key, single_line(value));
+            }
+        }
+        JournalSyntax::Beancount => {
+            let _ = write!(text, "{} {}", entry.date, flag);
+            if let Some(payee) = &entry.payee {
+                let _ = write!(text, " {}", quote(payee));
+            }
+            let _ = write!(text, " {}", quote(&entry.narration));
+            for tag in &entry.tags {
+                let _ = write!(text, " #{tag}");
+            }
+            let _ = writeln!(text);
+            for (key, value) in &entry.metadata {
+                let _ = writeln!(text, "  {}: {}", key, quote(value));
+            }
+        }
+    }
+
+    for posting in &entry.postings {
+        let _ = write!(text, "    {}  {}", posting.account, posting.amount);
+        if let Some(total_price) = &posting.total_price {
+            let _ = write!(text, " @@ {total_price}");
+        }
+        let _ = writeln!(text);
+    }
+
+    text
+}
+
+/// Joins the names into an account, e.g. `Expenses:Food-Drinks` for `["Expenses", "food & drinks"]`.
+fn account<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    names.into_iter().map(account_component).collect::<Vec<_>>().join(":")
+}
+
+/// Turns a name into a component of an account that is valid in both syntaxes:
+/// words of letters and digits, starting with an uppercase letter and joined by dashes.
+fn account_component(name: &str) -> String {
+    let component = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match component.is_empty() {
+        true => "Unnamed".to_string(),
+        false => component,
+    }
+}
+
+/// Tags may only contain ascii letters, digits and dashes.
+fn tag(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The iso code of the currency, an uppercase code derived from its name or its id as a fallback.
+fn commodity(currency: &currencies::Model) -> String {
+    if let Some(iso_code) = &currency.iso_code {
+        return iso_code.to_ascii_uppercase();
+    }
+
+    let code: String = currency
+        .name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(24)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    match code.len() >= 2 && code.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        true => code,
+        false => format!("C{}", currency.id),
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", single_line(text).replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> Entry {
+        Entry {
+            date: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            cleared: true,
+            payee: Some("Bakery \"Crumbs\"".to_string()),
+            narration: "Bread".to_string(),
+            tags: vec!["food".to_string()],
+            metadata: vec![("id", "42".to_string())],
+            postings: vec![
+                Posting {
+                    account: "Assets:Checking".to_string(),
+                    amount: "-3.50 EUR".to_string(),
+                    total_price: None,
+                },
+                Posting {
+                    account: "Expenses:Food".to_string(),
+                    amount: "3.50 EUR".to_string(),
+                    total_price: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_format_entry() {
+        assert_eq!(
+            format_entry(&entry(), JournalSyntax::Ledger),
+            "2026-10-19 * Bakery \"Crumbs\" | Bread\n    ; :food:\n    ; id: 42\n    Assets:Checking  -3.50 EUR\n    Expenses:Food  3.50 EUR\n"
+        );
+        assert_eq!(
+            format_entry(&entry(), JournalSyntax::Beancount),
+            "2026-10-19 * \"Bakery \\\"Crumbs\\\"\" \"Bread\" #food\n  id: \"42\"\n    Assets:Checking  -3.50 EUR\n    Expenses:Food  3.50 EUR\n"
+        );
+    }
+
+    #[test]
+    fn test_account() {
+        assert_eq!(
+            account(["Expenses", "food & drinks", "Café"]),
+            "Expenses:Food-Drinks:Café"
+        );
+        assert_eq!(account(["Assets", "::"]), "Assets:Unnamed");
+        assert_eq!(tag("Holiday 2026!"), "Holiday-2026");
+    }
+
+    #[test]
+    fn test_asset_accounts() {
+        let bank_account = |id: i64, name: &str| bank_accounts::Model {
+            id,
+            currency_id: 1,
+            linked_back_account_id: None,
+            name: name.to_string(),
+            description: None,
+            iban: None,
+            balance: 0,
+            original_balance: 0,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        };
+
+        let accounts = asset_accounts(&[
+            bank_account(1, "Checking"),
+            bank_account(2, "checking!"),
+            bank_account(3, "Savings"),
+        ]);
+        assert_eq!(accounts[&1], "Assets:Checking-1");
+        assert_eq!(accounts[&2], "Assets:Checking-2");
+        assert_eq!(accounts[&3], "Assets:Savings");
+    }
+
+    #[test]
+    fn test_commodity() {
+        let currency = |name: &str, iso_code: Option<&str>| currencies::Model {
+            id: 7,
+            user_id: None,
+            name: name.to_string(),
+            symbol: "$".to_string(),
+            iso_code: iso_code.map(str::to_string),
+            decimal_places: 2,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        };
+
+        assert_eq!(commodity(&currency("US Dollar", Some("usd"))), "USD");
+        assert_eq!(commodity(&currency("Bitcoin", None)), "BITCOIN");
+        assert_eq!(commodity(&currency("€", None)), "C7");
+    }
+}
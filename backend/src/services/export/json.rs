@@ -0,0 +1,54 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{bank_accounts, categories, currencies, tags, transaction_splits, transactions};
+use crate::services::export::{export_error, ExportData};
+use chrono::Utc;
+use serde::Serialize;
+
+/// The version of the structure of the json export.
+const JSON_EXPORT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    version: u32,
+    exported_at: chrono::DateTime<Utc>,
+    currencies: &'a [currencies::Model],
+    bank_accounts: &'a [bank_accounts::Model],
+    categories: &'a [categories::Model],
+    tags: &'a [tags::Model],
+    transactions: Vec<JsonTransaction<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonTransaction<'a> {
+    #[serde(flatten)]
+    transaction: &'a transactions::Model,
+    tag_ids: &'a [i64],
+    splits: &'a [transaction_splits::Model],
+}
+
+/// Writes all data into a single json document. Amounts stay in the smallest unit of their currency.
+pub fn write_json(data: &ExportData) -> AppResult<Vec<u8>> {
+    let export = JsonExport {
+        version: JSON_EXPORT_VERSION,
+        exported_at: Utc::now(),
+        currencies: &data.currencies,
+        bank_accounts: &data.bank_accounts,
+        categories: &data.categories,
+        tags: &data.tags,
+        transactions: data
+            .transactions
+            .iter()
+            .map(|transaction| JsonTransaction {
+                transaction,
+                tag_ids: data
+                    .transaction_tags
+                    .get(&transaction.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+                splits: data.splits.get(&transaction.id).map(Vec::as_slice).unwrap_or_default(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_vec_pretty(&export).map_err(export_error)
+}
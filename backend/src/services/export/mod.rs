@@ -0,0 +1,221 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::sea_orm_active_enums::ExportFormat;
+use crate::models::_entities::{
+    bank_accounts, categories, currencies, exports, taggings, tags, transaction_parties, transaction_splits,
+    transactions,
+};
+use crate::models::user_permissions::Permission;
+use crate::services::export::archive::ArchiveData;
+use loco_rs::app::AppContext;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityName, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+pub mod archive;
+pub mod csv;
+pub mod journal;
+pub mod json;
+
+/// Everything of a user that is part of an export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportData {
+    pub currencies: Vec<currencies::Model>,
+    pub bank_accounts: Vec<bank_accounts::Model>,
+    pub categories: Vec<categories::Model>,
+    pub tags: Vec<tags::Model>,
+    /// Ordered by their booking date (or creation date if they were not booked yet).
+    pub transactions: Vec<transactions::Model>,
+    /// The tag ids of each transaction.
+    pub transaction_tags: HashMap<i64, Vec<i64>>,
+    /// The split lines of each split transaction.
+    pub splits: HashMap<i64, Vec<transaction_splits::Model>>,
+    /// The bank account of every party that represents one of the bank accounts.
+    pub parties: HashMap<i64, i64>,
+}
+
+impl ExportData {
+    /// Loads the transactions of all bank accounts the user can read, and the categories, tags and currencies
+    /// the user can use.
+    pub async fn load(db: &impl ConnectionTrait, user_id: i64) -> AppResult<Self> {
+        let bank_accounts =
+            bank_accounts::Model::find_all_with_permissions(db, user_id, &[], Permission::Read.into()).await?;
+        let bank_account_ids: Vec<i64> = bank_accounts.iter().map(|bank_account| bank_account.id).collect();
+        let parties = transaction_parties::Model::find_all_for_bank_accounts(db, &bank_account_ids)
+            .await?
+            .into_iter()
+            .filter_map(|party| Some((party.id, party.bank_account_id?)))
+            .collect();
+
+        let mut transactions = transactions::Entity::find()
+            .filter(
+                transactions::Column::Id
+                    .in_subquery(transactions::Model::visible_ids(user_id, Permission::Read.into())),
+            )
+            .all(db)
+            .await?;
+        transactions.sort_by_key(|transaction| (transaction.effective_date(), transaction.id));
+        let transaction_ids: Vec<i64> = transactions.iter().map(|transaction| transaction.id).collect();
+
+        Ok(Self {
+            currencies: currencies::Model::find_all_accessible(db, user_id).await?,
+            bank_accounts,
+            categories: categories::Model::find_all_accessible(db, user_id).await?,
+            tags: tags::Model::find_all_by_user_id(db, user_id).await?,
+            transaction_tags: taggings::Model::find_tag_ids_for_entities(
+                db,
+                transactions::Entity.table_name(),
+                transaction_ids.clone(),
+            )
+            .await?,
+            splits: transaction_splits::Model::find_by_transaction_ids(db, transaction_ids).await?,
+            transactions,
+            parties,
+        })
+    }
+
+    /// The bank account that the party represents.
+    pub fn bank_account(&self, party_id: Option<i64>) -> Option<&bank_accounts::Model> {
+        let bank_account_id = self.parties.get(&party_id?)?;
+
+        self.bank_accounts
+            .iter()
+            .find(|bank_account| bank_account.id == *bank_account_id)
+    }
+
+    pub fn currency(&self, currency_id: i64) -> Option<&currencies::Model> {
+        self.currencies.iter().find(|currency| currency.id == currency_id)
+    }
+
+    /// The full path of every category, from the root to the category itself.
+    pub fn category_paths(&self) -> HashMap<i64, Vec<String>> {
+        let parents: HashMap<i64, &categories::Model> =
+            self.categories.iter().map(|category| (category.id, category)).collect();
+
+        self.categories
+            .iter()
+            .map(|category| {
+                let mut path = vec![category.name.clone()];
+                // The visited list guards against cycles in the tree.
+                let mut visited = vec![category.id];
+                let mut parent_id = category.parent_id;
+                while let Some(parent) = parent_id
+                    .filter(|id| !visited.contains(id))
+                    .and_then(|id| parents.get(&id))
+                {
+                    visited.push(parent.id);
+                    path.insert(0, parent.name.clone());
+                    parent_id = parent.parent_id;
+                }
+
+                (category.id, path)
+            })
+            .collect()
+    }
+
+    /// The names of the tags of the transaction.
+    pub fn tag_names(&self, transaction_id: i64) -> Vec<&str> {
+        let tag_ids = self
+            .transaction_tags
+            .get(&transaction_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        self.tags
+            .iter()
+            .filter(|tag| tag_ids.contains(&tag.id))
+            .map(|tag| tag.name.as_str())
+            .collect()
+    }
+}
+
+/// Loads the data of the user of the export and writes it in the format of the export.
+pub async fn write_export(ctx: &AppContext, export: &exports::Model) -> AppResult<Vec<u8>> {
+    let load = || ExportData::load(&ctx.db, export.user_id);
+
+    match export.format {
+        ExportFormat::Csv => csv::write_csv_archive(&load().await?),
+        ExportFormat::Json => json::write_json(&load().await?),
+        ExportFormat::Ledger => Ok(journal::write_journal(&load().await?, journal::JournalSyntax::Ledger).into_bytes()),
+        ExportFormat::Beancount => {
+            Ok(journal::write_journal(&load().await?, journal::JournalSyntax::Beancount).into_bytes())
+        }
+        ExportFormat::Archive => archive::write_archive(&ArchiveData::load(ctx, export.user_id).await?),
+    }
+}
+
+/// Bundles the files into a zip archive.
+fn write_zip<N: Into<String>>(files: impl IntoIterator<Item = (N, Vec<u8>)>) -> AppResult<Vec<u8>> {
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+        archive
+            .start_file(name, SimpleFileOptions::default())
+            .map_err(export_error)?;
+        archive.write_all(&content).map_err(export_error)?;
+    }
+
+    Ok(archive.finish().map_err(export_error)?.into_inner())
+}
+
+/// Formats an amount in the smallest unit of its currency as a decimal number, e.g. `-1234` as `-12.34`.
+pub fn format_amount(amount: i64, decimal_places: i32) -> String {
+    let decimal_places = decimal_places.max(0) as u32;
+    if decimal_places == 0 {
+        return amount.to_string();
+    }
+
+    let divisor = 10u64.pow(decimal_places);
+    let sign = if amount < 0 { "-" } else { "" };
+    let amount = amount.unsigned_abs();
+
+    format!(
+        "{sign}{}.{:0width$}",
+        amount / divisor,
+        amount % divisor,
+        width = decimal_places as usize
+    )
+}
+
+fn export_error(err: impl ToString) -> AppError {
+    AppError::GeneralInternalServerError(format!("Failed to write the export: {}", err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(-123450, 2), "-1234.50");
+        assert_eq!(format_amount(5, 2), "0.05");
+        assert_eq!(format_amount(-5, 3), "-0.005");
+        assert_eq!(format_amount(1600, 0), "1600");
+        assert_eq!(format_amount(i64::MIN, 2), "-92233720368547758.08");
+    }
+
+    #[test]
+    fn test_category_paths() {
+        let category = |id, parent_id, name: &str| categories::Model {
+            id,
+            parent_id,
+            user_id: Some(1),
+            name: name.to_string(),
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        };
+        let data = ExportData {
+            categories: vec![
+                category(1, None, "Housing"),
+                category(2, Some(1), "Rent"),
+                category(3, Some(4), "Loop"),
+                category(4, Some(3), "Back"),
+            ],
+            ..Default::default()
+        };
+
+        let paths = data.category_paths();
+        assert_eq!(paths[&2], vec!["Housing", "Rent"]);
+        assert_eq!(paths[&3], vec!["Back", "Loop"]);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::import_profiles;
+use crate::models::_entities::sea_orm_active_enums::ImportSignConvention;
+use crate::services::import::{parse_amount, parse_date, ImportedTransaction, ParsedRow};
+use crate::utils::iban::normalize_iban;
+use csv::{ReaderBuilder, StringRecord, Trim};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// References a column of a csv file either by its header name or by its zero based index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum ColumnReference {
+    Index(usize),
+    Name(String),
+}
+
+/// Maps the columns of a csv file to the fields of a transaction.
+///
+/// Depending on the sign convention either `amount` or `debit` and `credit` have to be set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CsvColumnMapping {
+    pub booking_date: ColumnReference,
+    pub amount: Option<ColumnReference>,
+    pub debit: Option<ColumnReference>,
+    pub credit: Option<ColumnReference>,
+    pub name: Option<ColumnReference>,
+    pub purpose: Option<ColumnReference>,
+    pub note: Option<ColumnReference>,
+    pub counterparty_name: Option<ColumnReference>,
+    pub counterparty_iban: Option<ColumnReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvImportSettings {
+    pub column_mapping: CsvColumnMapping,
+    pub date_format: String,
+    pub decimal_separator: char,
+    pub thousands_separator: Option<char>,
+    pub delimiter: u8,
+    pub has_header: bool,
+    pub skip_rows: usize,
+    pub sign_convention: ImportSignConvention,
+}
+
+impl TryFrom<&import_profiles::Model> for CsvImportSettings {
+    type Error = AppError;
+
+    fn try_from(profile: &import_profiles::Model) -> Result<Self, Self::Error> {
+        let column_mapping = serde_json::from_value(profile.column_mapping.clone())
+            .map_err(|err| AppError::DbParseJson(err.to_string()))?;
+        let decimal_separator = profile
+            .decimal_separator
+            .chars()
+            .next()
+            .ok_or_else(|| AppError::DbParseJson("Import profile has no decimal separator.".to_string()))?;
+        let delimiter = match profile.delimiter.as_bytes() {
+            [delimiter] => *delimiter,
+            _ => {
+                return Err(AppError::DbParseJson(
+                    "Import profile has an invalid delimiter.".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            column_mapping,
+            date_format: profile.date_format.clone(),
+            decimal_separator,
+            thousands_separator: profile.thousands_separator.as_ref().and_then(|s| s.chars().next()),
+            delimiter,
+            has_header: profile.has_header,
+            skip_rows: profile.skip_rows.max(0) as usize,
+            sign_convention: profile.sign_convention.clone(),
+        })
+    }
+}
+
+/// The column indices after resolving all header names.
+struct ResolvedColumns {
+    booking_date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    name: Option<usize>,
+    purpose: Option<usize>,
+    note: Option<usize>,
+    counterparty_name: Option<usize>,
+    counterparty_iban: Option<usize>,
+}
+
+impl ResolvedColumns {
+    fn resolve(mapping: &CsvColumnMapping, headers: Option<&StringRecord>) -> AppResult<Self> {
+        let resolve_optional = |reference: &Option<ColumnReference>| {
+            reference
+                .as_ref()
+                .map(|reference| resolve_column(reference, headers))
+                .transpose()
+        };
+
+        Ok(Self {
+            booking_date: resolve_column(&mapping.booking_date, headers)?,
+            amount: resolve_optional(&mapping.amount)?,
+            debit: resolve_optional(&mapping.debit)?,
+            credit: resolve_optional(&mapping.credit)?,
+            name: resolve_optional(&mapping.name)?,
+            purpose: resolve_optional(&mapping.purpose)?,
+            note: resolve_optional(&mapping.note)?,
+            counterparty_name: resolve_optional(&mapping.counterparty_name)?,
+            counterparty_iban: resolve_optional(&mapping.counterparty_iban)?,
+        })
+    }
+}
+
+fn resolve_column(reference: &ColumnReference, headers: Option<&StringRecord>) -> AppResult<usize> {
+    match (reference, headers) {
+        (ColumnReference::Index(index), _) => Ok(*index),
+        (ColumnReference::Name(name), Some(headers)) => headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| AppError::InvalidImportFile(format!("Column '{}' does not exist.", name))),
+        (ColumnReference::Name(name), None) => Err(AppError::InvalidImportFile(format!(
+            "Column '{}' can only be referenced by name if the file has a header.",
+            name
+        ))),
+    }
+}
+
+/// Parses the content of a csv file with the given settings.
+///
+/// Errors of single rows do not abort the parsing and are reported per row instead.
+/// Only errors that affect the whole file (e.g. missing columns) are returned as `Err`.
+pub fn parse_csv(settings: &CsvImportSettings, content: &str, decimal_places: u32) -> AppResult<Vec<ParsedRow>> {
+    let content: String = content
+        .trim_start_matches('\u{feff}')
+        .split_inclusive('\n')
+        .skip(settings.skip_rows)
+        .collect();
+
+    let mut reader = ReaderBuilder::new()
+        .delimiter(settings.delimiter)
+        .has_headers(settings.has_header)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = match settings.has_header {
+        true => Some(
+            reader
+                .headers()
+                .map_err(|err| AppError::InvalidImportFile(err.to_string()))?
+                .clone(),
+        ),
+        false => None,
+    };
+    let columns = ResolvedColumns::resolve(&settings.column_mapping, headers.as_ref())?;
+
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let row = line_number(record.position(), settings.skip_rows);
+                parse_record(settings, &columns, &record, decimal_places, row)
+            }
+            Err(err) => ParsedRow::failed(line_number(err.position(), settings.skip_rows), vec![err.to_string()]),
+        })
+        .collect())
+}
+
+fn line_number(position: Option<&csv::Position>, skipped_rows: usize) -> usize {
+    position.map(|position| position.line() as usize).unwrap_or_default() + skipped_rows
+}
+
+fn parse_record(
+    settings: &CsvImportSettings,
+    columns: &ResolvedColumns,
+    record: &StringRecord,
+    decimal_places: u32,
+    row: usize,
+) -> ParsedRow {
+    let field = |index: Option<usize>| {
+        index
+            .and_then(|index| record.get(index))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let mut errors = Vec::new();
+
+    let booking_date = match field(Some(columns.booking_date)) {
+        None => Err("Booking date is missing.".to_string()),
+        Some(raw) => parse_date(&raw, &settings.date_format),
+    }
+    .map_err(|err| errors.push(err))
+    .ok();
+
+    let amount = parse_signed_amount(settings, columns, &field, decimal_places)
+        .map_err(|err| errors.push(err))
+        .ok();
+
+    let purpose = field(columns.purpose);
+    let counterparty_name = field(columns.counterparty_name);
+    let name = field(columns.name)
+        .or_else(|| counterparty_name.clone())
+        .or_else(|| purpose.clone());
+    if name.is_none() {
+        errors.push("Name is missing.".to_string());
+    }
+
+    match (booking_date, amount, name) {
+        (Some(booking_date), Some(amount), Some(name)) if errors.is_empty() => ParsedRow::parsed(
+            row,
+            ImportedTransaction {
+                booking_date,
+                amount,
+                name,
+                purpose,
+                note: field(columns.note),
+                counterparty_name,
+                counterparty_iban: field(columns.counterparty_iban).map(|iban| normalize_iban(&iban)),
+                external_id: None,
+                category: None,
+            },
+        ),
+        _ => ParsedRow::failed(row, errors),
+    }
+}
+
+fn parse_signed_amount(
+    settings: &CsvImportSettings,
+    columns: &ResolvedColumns,
+    field: &impl Fn(Option<usize>) -> Option<String>,
+    decimal_places: u32,
+) -> Result<i64, String> {
+    let parse = |index: Option<usize>| {
+        field(index)
+            .map(|raw| {
+                parse_amount(
+                    &raw,
+                    settings.decimal_separator,
+                    settings.thousands_separator,
+                    decimal_places,
+                )
+            })
+            .transpose()
+    };
+
+    let amount = match settings.sign_convention {
+        ImportSignConvention::Signed => parse(columns.amount)?.ok_or("Amount is missing.")?,
+        ImportSignConvention::Inverted => -parse(columns.amount)?.ok_or("Amount is missing.")?,
+        ImportSignConvention::SeparateColumns => match (parse(columns.debit)?, parse(columns.credit)?) {
+            (None, None) => return Err("Debit and credit are missing.".to_string()),
+            (debit, credit) => credit.unwrap_or_default().abs() - debit.unwrap_or_default().abs(),
+        },
+    };
+
+    match amount {
+        0 => Err("Amount must not be zero.".to_string()),
+        amount => Ok(amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(sign_convention: ImportSignConvention, column_mapping: CsvColumnMapping) -> CsvImportSettings {
+        CsvImportSettings {
+            column_mapping,
+            date_format: "%d.%m.%Y".to_string(),
+            decimal_separator: ',',
+            thousands_separator: Some('.'),
+            delimiter: b';',
+            has_header: true,
+            skip_rows: 0,
+            sign_convention,
+        }
+    }
+
+    fn mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            booking_date: ColumnReference::Name("Date".to_string()),
+            amount: Some(ColumnReference::Name("Amount".to_string())),
+            debit: None,
+            credit: None,
+            name: None,
+            purpose: Some(ColumnReference::Name("Purpose".to_string())),
+            note: None,
+            counterparty_name: Some(ColumnReference::Index(1)),
+            counterparty_iban: Some(ColumnReference::Name("iban".to_string())),
+        }
+    }
+
+    #[test]
+    fn test_parse_signed_csv() {
+        let content = "Date;Payee;IBAN;Purpose;Amount\n\
+            01.02.2025;ACME Corp;de89 3704 0044 0532 0130 00;Salary;2.500,00\n\
+            03.02.2025;Bakery;;Bread;-3,50\n\
+            yesterday;Bakery;;Bread;abc\n";
+
+        let rows = parse_csv(&settings(ImportSignConvention::Signed, mapping()), content, 2).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        let salary = rows[0].transaction.as_ref().unwrap();
+        assert_eq!(rows[0].row, 2);
+        assert_eq!(salary.amount, 250000);
+        assert_eq!(salary.name, "ACME Corp");
+        assert_eq!(salary.purpose.as_deref(), Some("Salary"));
+        assert_eq!(salary.counterparty_iban.as_deref(), Some("DE89370400440532013000"));
+
+        let bread = rows[1].transaction.as_ref().unwrap();
+        assert_eq!(bread.amount, -350);
+        assert_eq!(bread.counterparty_iban, None);
+
+        assert!(rows[2].transaction.is_none());
+        assert_eq!(rows[2].row, 4);
+        assert_eq!(rows[2].errors.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_separate_columns_without_header() {
+        let mapping = CsvColumnMapping {
+            booking_date: ColumnReference::Index(0),
+            amount: None,
+            debit: Some(ColumnReference::Index(2)),
+            credit: Some(ColumnReference::Index(3)),
+            name: Some(ColumnReference::Index(1)),
+            purpose: None,
+            note: None,
+            counterparty_name: None,
+            counterparty_iban: None,
+        };
+        let mut settings = settings(ImportSignConvention::SeparateColumns, mapping);
+        settings.has_header = false;
+        settings.skip_rows = 2;
+
+        let content = "Export of account\n\n01.02.2025;Rent;800,00;\n02.02.2025;Refund;;12,00\n";
+        let rows = parse_csv(&settings, content, 2).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].row, 3);
+        assert_eq!(rows[0].transaction.as_ref().unwrap().amount, -80000);
+        assert_eq!(rows[1].transaction.as_ref().unwrap().amount, 1200);
+    }
+
+    #[test]
+    fn test_inverted_amount_and_missing_column() {
+        let rows = parse_csv(
+            &settings(ImportSignConvention::Inverted, mapping()),
+            "Date;Payee;IBAN;Purpose;Amount\n01.02.2025;Card;;;12,00\n",
+            2,
+        )
+        .unwrap();
+        assert_eq!(rows[0].transaction.as_ref().unwrap().amount, -1200);
+
+        let result = parse_csv(
+            &settings(ImportSignConvention::Signed, mapping()),
+            "Date;Payee;Purpose;Amount\n",
+            2,
+        );
+        assert!(result.is_err());
+    }
+}
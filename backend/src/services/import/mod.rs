@@ -0,0 +1,362 @@
+use crate::error::app_error::AppResult;
+use crate::models::_entities::{bank_accounts, categories, category_classifiers, possible_duplicates, transactions};
+use crate::models::category_classifiers::{suggest, CategorySuggestion};
+use crate::models::transaction_rules::RuleSet;
+use crate::models::transactions::{CounterpartyTransaction, NewTransaction};
+use crate::services::snowflake_generator::{SnowflakeGenerator, SnowflakeGeneratorInner};
+use crate::services::Service;
+use crate::utils::naive_bayes::NaiveBayes;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use loco_rs::app::AppContext;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+pub mod csv;
+pub mod ofx;
+pub mod qif;
+
+pub type ImportService = Arc<ImportServiceInner>;
+
+/// A single transaction as it was read from an import file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedTransaction {
+    pub booking_date: DateTime<FixedOffset>,
+    /// The amount in the smallest unit of the currency.
+    /// Positive amounts are incoming, negative amounts are outgoing.
+    pub amount: i64,
+    pub name: String,
+    pub purpose: Option<String>,
+    pub note: Option<String>,
+    pub counterparty_name: Option<String>,
+    pub counterparty_iban: Option<String>,
+    /// The id of the transaction at the bank, like the FITID of an OFX file.
+    pub external_id: Option<String>,
+    /// A category path like `Food:Groceries`.
+    /// Missing categories are created during the import.
+    pub category: Option<String>,
+}
+
+/// The separator between parent and child categories in `ImportedTransaction::category`.
+pub const CATEGORY_SEPARATOR: char = ':';
+
+/// The result of parsing a single row (or record) of an import file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedRow {
+    pub row: usize,
+    pub transaction: Option<ImportedTransaction>,
+    pub errors: Vec<String>,
+}
+
+impl ParsedRow {
+    pub fn parsed(row: usize, transaction: ImportedTransaction) -> Self {
+        Self {
+            row,
+            transaction: Some(transaction),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn failed(row: usize, errors: Vec<String>) -> Self {
+        Self {
+            row,
+            transaction: None,
+            errors,
+        }
+    }
+}
+
+/// What happened (or would happen during a dry-run) with a single parsed row.
+#[derive(Debug, Clone)]
+pub struct ImportRowOutcome {
+    pub row: usize,
+    pub transaction: Option<transactions::Model>,
+    pub duplicate_of: Option<i64>,
+    /// Suggested categories for uncategorized transactions (only during a dry-run).
+    pub category_suggestions: Vec<CategorySuggestion>,
+    pub errors: Vec<String>,
+}
+
+pub struct ImportServiceInner {
+    ctx: AppContext,
+    snowflake_generator: SnowflakeGenerator,
+}
+
+impl Service for ImportServiceInner {
+    async fn new(ctx: &AppContext) -> loco_rs::Result<Self> {
+        Ok(Self {
+            ctx: ctx.clone(),
+            snowflake_generator: SnowflakeGeneratorInner::get_arc(ctx).await?,
+        })
+    }
+
+    fn get_static_once() -> &'static OnceLock<Arc<Self>> {
+        static INSTANCE: OnceLock<Arc<ImportServiceInner>> = OnceLock::new();
+
+        &INSTANCE
+    }
+}
+
+impl ImportServiceInner {
+    /// Imports the parsed rows into the given bank account.
+    /// Categories are looked up (and created if missing) for the given user
+    /// and the rules of the user are applied to every transaction.
+    ///
+    /// Everything runs inside a single database transaction.
+    /// During a dry-run the transaction is rolled back, so the returned transactions are never persisted.
+    /// Rows that match an already existing transaction are skipped.
+    /// Incomes and expenses are paired with the opposite leg of a transfer between two own bank accounts.
+    /// A dry-run additionally suggests categories for transactions that no rule categorized.
+    pub async fn import(
+        &self,
+        user_id: i64,
+        bank_account: &bank_accounts::Model,
+        rows: Vec<ParsedRow>,
+        dry_run: bool,
+    ) -> AppResult<Vec<ImportRowOutcome>> {
+        let txn = self.ctx.db.begin().await?;
+        let rules = RuleSet::load(&txn, user_id).await?;
+        let mut category_ids = HashMap::new();
+
+        // Duplicates are detected before anything gets inserted.
+        // Otherwise, identical rows of the same file would be detected as duplicates of each other.
+        let mut pending = Vec::with_capacity(rows.len());
+        for row in rows {
+            let Some(imported) = row.transaction else {
+                pending.push((row.row, None, None, row.errors));
+                continue;
+            };
+
+            let category_id = match &imported.category {
+                None => None,
+                Some(path) => self.resolve_category(&txn, user_id, path, &mut category_ids).await?,
+            };
+            let mut new_transaction = NewTransaction::with_counterparty(
+                &txn,
+                &self.snowflake_generator,
+                bank_account,
+                CounterpartyTransaction {
+                    amount: imported.amount,
+                    name: imported.name,
+                    purpose: imported.purpose,
+                    note: imported.note,
+                    booking_date: Some(imported.booking_date),
+                    category_id,
+                    counterparty_name: imported.counterparty_name,
+                    counterparty_iban: imported.counterparty_iban,
+                },
+            )
+            .await?;
+            new_transaction.external_id = imported.external_id;
+            rules.apply(&mut new_transaction);
+            let duplicate = transactions::Model::find_exact_duplicate(&txn, bank_account.id, &new_transaction).await?;
+
+            pending.push((row.row, Some(new_transaction), duplicate.map(|d| d.id), row.errors));
+        }
+
+        let classifier = match dry_run {
+            true => category_classifiers::Model::load_classifier(&txn, user_id).await?,
+            false => NaiveBayes::default(),
+        };
+
+        let mut outcomes = Vec::with_capacity(pending.len());
+        let mut created_ids = Vec::new();
+        for (row, new_transaction, duplicate_of, errors) in pending {
+            let transaction = match (new_transaction, duplicate_of) {
+                (Some(new_transaction), None) => {
+                    let created =
+                        transactions::Model::create_without_detection(&txn, &self.snowflake_generator, new_transaction)
+                            .await?;
+                    created_ids.push(created.id);
+
+                    Some(created.pair_transfer(&txn, user_id).await?)
+                }
+                _ => None,
+            };
+            let category_suggestions = match &transaction {
+                Some(transaction) if transaction.category_id.is_none() => suggest(&classifier, transaction),
+                _ => Vec::new(),
+            };
+
+            outcomes.push(ImportRowOutcome {
+                row,
+                transaction,
+                duplicate_of,
+                category_suggestions,
+                errors,
+            });
+        }
+
+        // Possible duplicates are only searched among the transactions that existed before the import.
+        // A transaction that was paired into an already existing transfer is not new.
+        for transaction in outcomes.iter().filter_map(|outcome| outcome.transaction.as_ref()) {
+            if created_ids.contains(&transaction.id) {
+                possible_duplicates::Model::detect(&txn, &self.snowflake_generator, transaction, &created_ids).await?;
+            }
+        }
+
+        match dry_run {
+            true => txn.rollback().await?,
+            false => {
+                txn.commit().await?;
+
+                let categorized = outcomes
+                    .iter()
+                    .filter_map(|outcome| outcome.transaction.as_ref())
+                    .filter(|transaction| transaction.category_id.is_some())
+                    .count();
+                category_classifiers::Model::record_categorizations(
+                    &self.ctx,
+                    &self.snowflake_generator,
+                    user_id,
+                    categorized as i32,
+                )
+                .await?;
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn resolve_category(
+        &self,
+        db: &impl ConnectionTrait,
+        user_id: i64,
+        path: &str,
+        cache: &mut HashMap<String, Option<i64>>,
+    ) -> AppResult<Option<i64>> {
+        if let Some(category_id) = cache.get(path) {
+            return Ok(*category_id);
+        }
+
+        let category =
+            categories::Model::find_or_create_by_path(db, &self.snowflake_generator, user_id, path, CATEGORY_SEPARATOR)
+                .await?;
+        let category_id = category.map(|category| category.id);
+        cache.insert(path.to_string(), category_id);
+
+        Ok(category_id)
+    }
+}
+
+/// Parses a formatted amount into the smallest unit of the currency.
+///
+/// Currency symbols and whitespaces are ignored.
+/// Negative amounts can be written with a leading or trailing minus or in parentheses.
+pub fn parse_amount(
+    raw: &str,
+    decimal_separator: char,
+    thousands_separator: Option<char>,
+    decimal_places: u32,
+) -> Result<i64, String> {
+    let raw = raw.trim();
+    let mut negative = false;
+    let mut integer_part = String::new();
+    let mut fraction_part: Option<String> = None;
+
+    for (index, c) in raw.char_indices() {
+        match c {
+            '0'..='9' => match fraction_part.as_mut() {
+                Some(fraction) => fraction.push(c),
+                None => integer_part.push(c),
+            },
+            c if c == decimal_separator => {
+                if fraction_part.is_some() {
+                    return Err(format!("Invalid amount '{}': multiple decimal separators.", raw));
+                }
+                fraction_part = Some(String::new());
+            }
+            c if Some(c) == thousands_separator => {
+                if fraction_part.is_some() {
+                    return Err(format!("Invalid amount '{}': thousands separator after decimals.", raw));
+                }
+            }
+            '-' if index == 0 || index == raw.len() - 1 => negative = true,
+            '(' if index == 0 => negative = true,
+            ')' if index == raw.len() - 1 && negative => {}
+            '+' if index == 0 => {}
+            c if c.is_whitespace() || c.is_alphabetic() || !c.is_ascii() || c == '$' => {}
+            c => return Err(format!("Invalid amount '{}': unexpected character '{}'.", raw, c)),
+        }
+    }
+
+    if integer_part.is_empty() && fraction_part.as_ref().is_none_or(String::is_empty) {
+        return Err(format!("Invalid amount '{}': no digits found.", raw));
+    }
+
+    let mut fraction = fraction_part.unwrap_or_default();
+    let significant_length = fraction.trim_end_matches('0').len();
+    if significant_length > decimal_places as usize {
+        return Err(format!(
+            "Invalid amount '{}': more than {} decimal places.",
+            raw, decimal_places
+        ));
+    }
+    fraction.truncate(decimal_places as usize);
+    while fraction.len() < decimal_places as usize {
+        fraction.push('0');
+    }
+
+    let digits = format!("{}{}", integer_part, fraction);
+    let value = match digits.trim_start_matches('0') {
+        "" => 0,
+        digits => digits
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid amount '{}': value is too large.", raw))?,
+    };
+
+    Ok(if negative { -value } else { value })
+}
+
+/// Parses a date (or date and time) with the given `chrono` format.
+/// Dates without a time are interpreted as midnight UTC.
+pub fn parse_date(raw: &str, format: &str) -> Result<DateTime<FixedOffset>, String> {
+    let raw = raw.trim();
+
+    if let Ok(date_time) = DateTime::parse_from_str(raw, format) {
+        return Ok(date_time);
+    }
+
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(raw, format) {
+        return Ok(date_time.and_utc().fixed_offset());
+    }
+
+    NaiveDate::parse_from_str(raw, format)
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc().fixed_offset())
+        .map_err(|err| format!("Invalid date '{}' for format '{}': {}.", raw, format, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1.234,56", ',', Some('.'), 2), Ok(123456));
+        assert_eq!(parse_amount("-1,234.56", '.', Some(','), 2), Ok(-123456));
+        assert_eq!(parse_amount("12.5", '.', None, 2), Ok(1250));
+        assert_eq!(parse_amount("12", '.', None, 2), Ok(1200));
+        assert_eq!(parse_amount("12.50-", '.', None, 2), Ok(-1250));
+        assert_eq!(parse_amount("(7.00)", '.', None, 2), Ok(-700));
+        assert_eq!(parse_amount("+3,10 €", ',', None, 2), Ok(310));
+        assert_eq!(parse_amount("EUR 0.99", '.', None, 2), Ok(99));
+        assert_eq!(parse_amount("1500", '.', None, 0), Ok(1500));
+        assert_eq!(parse_amount("0.10", '.', None, 1), Ok(1));
+        assert!(parse_amount("1.234", '.', None, 2).is_err());
+        assert!(parse_amount("1.2.3", '.', None, 2).is_err());
+        assert!(parse_amount("1.234,56", ',', None, 2).is_err());
+        assert!(parse_amount("", '.', None, 2).is_err());
+        assert!(parse_amount("abc", '.', None, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let date = parse_date("24.12.2024", "%d.%m.%Y").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-12-24T00:00:00+00:00");
+
+        let date = parse_date("2024-12-24 13:37", "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-12-24T13:37:00+00:00");
+
+        assert!(parse_date("2024-12-24", "%d.%m.%Y").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::import::{parse_amount, ImportedTransaction, ParsedRow};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+const TRANSACTION_START: &str = "<STMTTRN>";
+const TRANSACTION_END: &str = "</STMTTRN>";
+
+/// Parses the statement transactions of an OFX (or QFX) file.
+///
+/// Both the SGML based OFX 1.x (without closing tags for values) and the XML based OFX 2.x are supported.
+pub fn parse_ofx(content: &str, decimal_places: u32) -> AppResult<Vec<ParsedRow>> {
+    let upper = content.to_ascii_uppercase();
+    if !upper.contains("<OFX>") {
+        return Err(AppError::InvalidImportFile("Missing <OFX> root element.".to_string()));
+    }
+
+    let mut rows = Vec::new();
+    let mut position = 0;
+    let mut line = 1;
+    while let Some(start) = upper[position..].find(TRANSACTION_START).map(|start| position + start) {
+        let block_start = start + TRANSACTION_START.len();
+        let block_end = upper[block_start..]
+            .find(TRANSACTION_END)
+            .map(|end| block_start + end)
+            .ok_or_else(|| AppError::InvalidImportFile("Unclosed <STMTTRN> element.".to_string()))?;
+
+        line += content[position..start].matches('\n').count();
+        rows.push(parse_transaction(
+            &content[block_start..block_end],
+            decimal_places,
+            line,
+        ));
+
+        line += content[start..block_end].matches('\n').count();
+        position = block_end + TRANSACTION_END.len();
+    }
+
+    Ok(rows)
+}
+
+fn parse_transaction(block: &str, decimal_places: u32, row: usize) -> ParsedRow {
+    let mut errors = Vec::new();
+
+    let booking_date = match value_of(block, "DTPOSTED") {
+        None => Err("DTPOSTED is missing.".to_string()),
+        Some(raw) => parse_ofx_date(&raw),
+    }
+    .map_err(|err| errors.push(err))
+    .ok();
+
+    let amount = match value_of(block, "TRNAMT") {
+        None => Err("TRNAMT is missing.".to_string()),
+        Some(raw) => {
+            // Most files use a dot, but some banks export their local decimal separator.
+            let decimal_separator = match raw.contains(',') && !raw.contains('.') {
+                true => ',',
+                false => '.',
+            };
+            parse_amount(&raw, decimal_separator, None, decimal_places)
+        }
+    }
+    .and_then(|amount| match amount {
+        0 => Err("Amount must not be zero.".to_string()),
+        amount => Ok(amount),
+    })
+    .map_err(|err| errors.push(err))
+    .ok();
+
+    // The payee is given either as NAME or as PAYEE aggregate, which contains the NAME and the address.
+    let counterparty_name = match aggregate_of(block, "PAYEE") {
+        Some(payee) => value_of(payee, "NAME"),
+        None => value_of(block, "NAME"),
+    };
+    let purpose = value_of(block, "MEMO");
+    let name = counterparty_name.clone().or_else(|| purpose.clone());
+    if name.is_none() {
+        errors.push("NAME and MEMO are missing.".to_string());
+    }
+
+    match (booking_date, amount, name) {
+        (Some(booking_date), Some(amount), Some(name)) if errors.is_empty() => ParsedRow::parsed(
+            row,
+            ImportedTransaction {
+                booking_date,
+                amount,
+                name,
+                purpose,
+                note: None,
+                counterparty_name,
+                counterparty_iban: None,
+                external_id: value_of(block, "FITID"),
+                category: None,
+            },
+        ),
+        _ => ParsedRow::failed(row, errors),
+    }
+}
+
+/// Returns the (unescaped) value of an element.
+/// The value ends at the next tag, which works for both SGML and XML.
+fn value_of(block: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = block.to_ascii_uppercase().find(&open)? + open.len();
+    let value = &block[start..];
+    let value = value[..value.find('<').unwrap_or(value.len())].trim();
+
+    match value.is_empty() {
+        true => None,
+        false => Some(
+            value
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        ),
+    }
+}
+
+/// Returns the content of an aggregate element, which (unlike values) is closed in both SGML and XML.
+fn aggregate_of<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    let upper = block.to_ascii_uppercase();
+    let open = format!("<{}>", tag);
+    let start = upper.find(&open)? + open.len();
+    let end = start + upper[start..].find(&format!("</{}>", tag))?;
+
+    Some(&block[start..end])
+}
+
+/// Parses an OFX date like `20250131`, `20250131120000.000` or `20250131120000[-5:EST]`.
+pub fn parse_ofx_date(raw: &str) -> Result<DateTime<FixedOffset>, String> {
+    let invalid = || format!("Invalid date '{}'.", raw);
+
+    let (date_time, zone) = match raw.split_once('[') {
+        Some((date_time, zone)) => (date_time, Some(zone.trim_end_matches(']'))),
+        None => (raw, None),
+    };
+    let digits = date_time.split('.').next().unwrap_or_default();
+
+    let naive = match digits.len() {
+        8 => NaiveDate::parse_from_str(digits, "%Y%m%d").map(|date| date.and_time(NaiveTime::MIN)),
+        12 => NaiveDateTime::parse_from_str(digits, "%Y%m%d%H%M"),
+        14 => NaiveDateTime::parse_from_str(digits, "%Y%m%d%H%M%S"),
+        _ => return Err(invalid()),
+    }
+    .map_err(|_| invalid())?;
+
+    // The zone looks like `-5:EST` or `+5.5`, the name is optional.
+    let offset_seconds = match zone.map(|zone| zone.split(':').next().unwrap_or_default()) {
+        None | Some("") => 0,
+        Some(hours) => {
+            let hours = hours.parse::<f64>().map_err(|_| invalid())?;
+            (hours * 3600.0).round() as i32
+        }
+    };
+    let offset = FixedOffset::east_opt(offset_seconds).ok_or_else(invalid)?;
+
+    naive.and_local_timezone(offset).single().ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ofx_date() {
+        assert_eq!(
+            parse_ofx_date("20250131").unwrap().to_rfc3339(),
+            "2025-01-31T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_ofx_date("20250131120000.000[-5:EST]").unwrap().to_rfc3339(),
+            "2025-01-31T12:00:00-05:00"
+        );
+        assert_eq!(
+            parse_ofx_date("202501311200[+5.5:IST]").unwrap().to_rfc3339(),
+            "2025-01-31T12:00:00+05:30"
+        );
+        assert!(parse_ofx_date("2025-01-31").is_err());
+    }
+
+    #[test]
+    fn test_parse_sgml_and_xml() {
+        let sgml = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX>\n<BANKTRANLIST>\n\
+            <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20250103\n<TRNAMT>-12.50\n<FITID>1\n<NAME>Coffee &amp; Co\n</STMTTRN>\n\
+            <STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20250104\n<TRNAMT>0\n<FITID>2\n</STMTTRN>\n\
+            </BANKTRANLIST>\n</OFX>\n";
+        let rows = parse_ofx(sgml, 2).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        let coffee = rows[0].transaction.as_ref().unwrap();
+        assert_eq!(rows[0].row, 6);
+        assert_eq!(coffee.amount, -1250);
+        assert_eq!(coffee.name, "Coffee & Co");
+        assert_eq!(coffee.counterparty_name.as_deref(), Some("Coffee & Co"));
+        assert_eq!(coffee.external_id.as_deref(), Some("1"));
+        assert!(rows[1].transaction.is_none());
+        assert_eq!(rows[1].errors.len(), 2);
+
+        let xml = "<?xml version=\"1.0\"?><OFX><STMTTRN><TRNTYPE>CREDIT</TRNTYPE><DTPOSTED>20250105</DTPOSTED>\
+            <TRNAMT>1500.00</TRNAMT><MEMO>Salary</MEMO></STMTTRN></OFX>";
+        let rows = parse_ofx(xml, 2).unwrap();
+
+        let salary = rows[0].transaction.as_ref().unwrap();
+        assert_eq!(salary.amount, 150000);
+        assert_eq!(salary.name, "Salary");
+        assert_eq!(salary.purpose.as_deref(), Some("Salary"));
+        assert_eq!(salary.counterparty_name, None);
+        assert_eq!(salary.external_id, None);
+
+        let payee = "<OFX><STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20250106</DTPOSTED><TRNAMT>-80.00</TRNAMT>\
+            <FITID>2025010601</FITID><PAYEE><NAME>City Power</NAME><ADDR1>Main Street 1</ADDR1><CITY>Springfield</CITY>\
+            </PAYEE><MEMO>Invoice 42</MEMO></STMTTRN></OFX>";
+        let rows = parse_ofx(payee, 2).unwrap();
+
+        let power = rows[0].transaction.as_ref().unwrap();
+        assert_eq!(power.name, "City Power");
+        assert_eq!(power.counterparty_name.as_deref(), Some("City Power"));
+        assert_eq!(power.purpose.as_deref(), Some("Invoice 42"));
+        assert_eq!(power.external_id.as_deref(), Some("2025010601"));
+
+        assert!(parse_ofx("Date;Amount", 2).is_err());
+    }
+}
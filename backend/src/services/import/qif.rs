@@ -0,0 +1,217 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::services::import::{parse_amount, parse_date, ImportedTransaction, ParsedRow, CATEGORY_SEPARATOR};
+use chrono::{DateTime, FixedOffset};
+
+/// The account types of QIF sections that contain bank transactions.
+const TRANSACTION_SECTIONS: [&str; 5] = ["bank", "cash", "ccard", "oth a", "oth l"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QifImportSettings {
+    /// QIF does not define a date format, it depends on the locale of the exporting application.
+    pub date_format: String,
+    pub decimal_separator: char,
+}
+
+impl Default for QifImportSettings {
+    fn default() -> Self {
+        Self {
+            date_format: "%m/%d/%Y".to_string(),
+            decimal_separator: '.',
+        }
+    }
+}
+
+#[derive(Default)]
+struct QifRecord {
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    category: Option<String>,
+}
+
+/// Parses all transactions of the bank, cash and credit card sections of a QIF file.
+///
+/// The `L` field becomes the category of a transaction.
+/// Transfers (`[Account]`) and split lines are ignored.
+pub fn parse_qif(settings: &QifImportSettings, content: &str, decimal_places: u32) -> AppResult<Vec<ParsedRow>> {
+    let mut rows = Vec::new();
+    let mut has_section = false;
+    let mut in_transactions = false;
+    let mut record = QifRecord::default();
+    let mut record_start = None;
+
+    for (index, line) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        let line = line.trim_end();
+        let Some(code) = line.chars().next() else {
+            continue;
+        };
+        let value = line[code.len_utf8()..].trim();
+
+        if code == '!' {
+            has_section = true;
+            if let Some(account_type) = value.strip_prefix("Type:") {
+                in_transactions = TRANSACTION_SECTIONS.contains(&account_type.trim().to_lowercase().as_str());
+            } else if !value.starts_with("Option") && !value.starts_with("Clear") {
+                // e.g. `!Account` which starts a list of accounts instead of transactions
+                in_transactions = false;
+            }
+            continue;
+        }
+
+        if !in_transactions {
+            continue;
+        }
+        record_start.get_or_insert(index + 1);
+
+        let value = Some(value.to_string()).filter(|value| !value.is_empty());
+        match code {
+            'D' => record.date = value,
+            'T' => record.amount = value,
+            'U' => record.amount = record.amount.take().or(value),
+            'P' => record.payee = value,
+            'M' => record.memo = value,
+            'L' => record.category = value,
+            '^' => {
+                let record = std::mem::take(&mut record);
+                let row = record_start.take().unwrap_or(index + 1);
+                rows.push(parse_record(settings, record, decimal_places, row));
+            }
+            _ => {}
+        }
+    }
+
+    if !has_section {
+        return Err(AppError::InvalidImportFile("Missing !Type header.".to_string()));
+    }
+
+    Ok(rows)
+}
+
+fn parse_record(settings: &QifImportSettings, record: QifRecord, decimal_places: u32, row: usize) -> ParsedRow {
+    let mut errors = Vec::new();
+
+    let booking_date = match record.date {
+        None => Err("Date (D) is missing.".to_string()),
+        Some(raw) => parse_qif_date(&raw, &settings.date_format),
+    }
+    .map_err(|err| errors.push(err))
+    .ok();
+
+    let thousands_separator = match settings.decimal_separator {
+        '.' => ',',
+        _ => '.',
+    };
+    let amount = match record.amount {
+        None => Err("Amount (T) is missing.".to_string()),
+        Some(raw) => parse_amount(
+            &raw,
+            settings.decimal_separator,
+            Some(thousands_separator),
+            decimal_places,
+        ),
+    }
+    .and_then(|amount| match amount {
+        0 => Err("Amount must not be zero.".to_string()),
+        amount => Ok(amount),
+    })
+    .map_err(|err| errors.push(err))
+    .ok();
+
+    let name = record.payee.clone().or_else(|| record.memo.clone());
+    if name.is_none() {
+        errors.push("Payee (P) and memo (M) are missing.".to_string());
+    }
+
+    match (booking_date, amount, name) {
+        (Some(booking_date), Some(amount), Some(name)) if errors.is_empty() => ParsedRow::parsed(
+            row,
+            ImportedTransaction {
+                booking_date,
+                amount,
+                name,
+                purpose: record.memo,
+                note: None,
+                counterparty_name: record.payee,
+                counterparty_iban: None,
+                external_id: None,
+                category: record.category.as_deref().and_then(parse_category),
+            },
+        ),
+        _ => ParsedRow::failed(row, errors),
+    }
+}
+
+/// Extracts the category path of an `L` field.
+/// Transfers are written as `[Account]` and a class can be appended with `/Class`.
+fn parse_category(raw: &str) -> Option<String> {
+    let category = raw.split('/').next().unwrap_or_default().trim();
+
+    match category.is_empty() || category.starts_with('[') {
+        true => None,
+        false => Some(
+            category
+                .split(CATEGORY_SEPARATOR)
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(&CATEGORY_SEPARATOR.to_string()),
+        ),
+    }
+}
+
+/// Quicken writes years after 2000 with two digits and an apostrophe like `1/ 5'24`.
+fn parse_qif_date(raw: &str, format: &str) -> Result<DateTime<FixedOffset>, String> {
+    let normalized: String = raw.replace('\'', "/").chars().filter(|c| !c.is_whitespace()).collect();
+
+    match raw.contains('\'') {
+        true => parse_date(&normalized, &format.replace("%Y", "%y")),
+        false => parse_date(&normalized, format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_qif() {
+        let content = "!Type:Bank\n\
+            D01/03/2025\nT-1,234.50\nPLandlord\nMRent January\nLHousing:Rent\n^\n\
+            D1/ 5'25\nU100.00\nPEmployer\nL[Savings]\n^\n\
+            D13/45/2025\nT5\n^\n\
+            !Account\nNChecking\nTBank\n^\n";
+        let rows = parse_qif(&QifImportSettings::default(), content, 2).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        let rent = rows[0].transaction.as_ref().unwrap();
+        assert_eq!(rows[0].row, 2);
+        assert_eq!(rent.amount, -123450);
+        assert_eq!(rent.name, "Landlord");
+        assert_eq!(rent.purpose.as_deref(), Some("Rent January"));
+        assert_eq!(rent.category.as_deref(), Some("Housing:Rent"));
+        assert_eq!(rent.booking_date.to_rfc3339(), "2025-01-03T00:00:00+00:00");
+
+        let salary = rows[1].transaction.as_ref().unwrap();
+        assert_eq!(salary.amount, 10000);
+        assert_eq!(salary.category, None);
+        assert_eq!(salary.booking_date.to_rfc3339(), "2025-01-05T00:00:00+00:00");
+
+        assert!(rows[2].transaction.is_none());
+        assert_eq!(rows[2].errors.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(
+            parse_category("Food : Groceries/Vacation").as_deref(),
+            Some("Food:Groceries")
+        );
+        assert_eq!(parse_category("[Checking]"), None);
+        assert_eq!(parse_category(""), None);
+    }
+
+    #[test]
+    fn test_missing_header() {
+        assert!(parse_qif(&QifImportSettings::default(), "D01/03/2025\nT5\n^\n", 2).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::error::app_error::{AppError, AppResult, RetryAfterArgs};
+use crate::mailers::auth::AuthMailer;
+use crate::models::users;
+use crate::services::Service;
+use crate::utils::context::AdditionalAppContextMethods;
+use chrono::{DateTime, Duration, Utc};
+use loco_rs::prelude::AppContext;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::IpAddr;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::warn;
+
+const ACCOUNT_KEY_PREFIX: &str = "failed_logins:account:";
+const IP_KEY_PREFIX: &str = "failed_logins:ip:";
+/// The number of locks the counters are spread over, see [`LoginProtectionServiceInner::check`].
+const LOCK_COUNT: usize = 64;
+
+/// Failed attempts are forgotten after this many hours without another failure.
+pub const FAILED_ATTEMPTS_RESET_HOURS: i64 = 24;
+
+/// Failed logins of a single account, no matter where they come from.
+pub const ACCOUNT_POLICY: BackoffPolicy = BackoffPolicy {
+    free_attempts: 3,
+    lockout_threshold: 10,
+    max_backoff_seconds: 60,
+    lockout_minutes: 15,
+};
+
+/// Failed logins from a single IP address, no matter which accounts they target.
+pub const IP_POLICY: BackoffPolicy = BackoffPolicy {
+    free_attempts: 10,
+    lockout_threshold: 50,
+    max_backoff_seconds: 60,
+    lockout_minutes: 15,
+};
+
+/// How long logging in is blocked after a number of failed attempts.
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    /// Failed attempts that are not delayed at all.
+    pub free_attempts: u32,
+    /// Reaching this many failed attempts locks logging in for `lockout_minutes`.
+    pub lockout_threshold: u32,
+    /// The delay doubles with every failed attempt until it reaches this limit.
+    pub max_backoff_seconds: i64,
+    pub lockout_minutes: i64,
+}
+
+impl BackoffPolicy {
+    /// The delay after the last of `count` failed attempts.
+    pub fn delay(&self, count: u32) -> Duration {
+        if count >= self.lockout_threshold {
+            return Duration::minutes(self.lockout_minutes);
+        }
+        if count <= self.free_attempts {
+            return Duration::zero();
+        }
+
+        let exponent = (count - self.free_attempts).min(32);
+        Duration::seconds(2_i64.pow(exponent).min(self.max_backoff_seconds))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedAttempts {
+    pub count: u32,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+impl FailedAttempts {
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.last_failed_at + Duration::hours(FAILED_ATTEMPTS_RESET_HOURS) <= now
+    }
+
+    /// The time until the next attempt is allowed, `None` if it is allowed right away.
+    pub fn retry_after(&self, policy: &BackoffPolicy, now: DateTime<Utc>) -> Option<Duration> {
+        let allowed_at = self.last_failed_at + policy.delay(self.count);
+
+        (allowed_at > now).then(|| allowed_at - now)
+    }
+}
+
+pub type LoginProtectionService = Arc<LoginProtectionServiceInner>;
+
+/// Throttles logins by counting the failed attempts per account and per IP address in the cache.
+pub struct LoginProtectionServiceInner {
+    ctx: AppContext,
+    locks: [Mutex<()>; LOCK_COUNT],
+}
+
+/// Holds the locks of the account and the IP address of a login attempt, see [`LoginProtectionServiceInner::check`].
+pub struct LoginAttempt<'a> {
+    _guards: Vec<MutexGuard<'a, ()>>,
+}
+
+impl Service for LoginProtectionServiceInner {
+    async fn new(ctx: &AppContext) -> loco_rs::Result<Self> {
+        Ok(Self {
+            ctx: ctx.clone(),
+            locks: std::array::from_fn(|_| Mutex::new(())),
+        })
+    }
+
+    fn get_static_once() -> &'static OnceLock<Arc<Self>> {
+        static INSTANCE: OnceLock<Arc<LoginProtectionServiceInner>> = OnceLock::new();
+
+        &INSTANCE
+    }
+}
+
+impl LoginProtectionServiceInner {
+    /// Rejects the login with `TooManyLoginAttempts` while the account or the IP address is backed off.
+    ///
+    /// The returned attempt has to be kept until the outcome of the login is recorded.
+    /// Until then, other logins of the same account or IP address wait, so parallel guesses can not all pass
+    /// the check before any of them is counted.
+    pub async fn check(&self, email: &str, ip: Option<IpAddr>) -> AppResult<LoginAttempt<'_>> {
+        let keys = Self::keys(email, ip);
+        let attempt = self.lock(&keys).await;
+
+        let now = Utc::now();
+        let mut retry_after = None;
+        for (key, policy) in keys {
+            if let Some(attempts) = self.get(&key, now).await? {
+                retry_after = retry_after.max(attempts.retry_after(&policy, now));
+            }
+        }
+
+        match retry_after {
+            None => Ok(attempt),
+            Some(retry_after) => Err(AppError::TooManyLoginAttempts(RetryAfterArgs {
+                // Rounded up, so clients that wait exactly this long are not rejected again.
+                retry_after: (retry_after.num_milliseconds() as u64).div_ceil(1000),
+            })),
+        }
+    }
+
+    /// Counts a failed login. Notifies the user by email once the account gets locked.
+    pub async fn record_failure(
+        &self,
+        _attempt: &LoginAttempt<'_>,
+        email: &str,
+        ip: Option<IpAddr>,
+        user: Option<&users::Model>,
+    ) -> AppResult<()> {
+        let now = Utc::now();
+        let account_attempts = self.increment(&Self::account_key(email), now).await?;
+        if let Some(ip) = ip {
+            self.increment(&Self::ip_key(ip), now).await?;
+        }
+
+        if account_attempts == ACCOUNT_POLICY.lockout_threshold {
+            warn!(
+                "Locked the login of an account after {} failed attempts",
+                account_attempts
+            );
+            if let Some(user) = user.filter(|_| self.ctx.is_mailer_enabled()) {
+                AuthMailer::send_lockout(&self.ctx, user, now + ACCOUNT_POLICY.delay(account_attempts)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forgets the failed attempts of the account after a successful login.
+    ///
+    /// The failed attempts of the IP address are kept, otherwise a valid login to one account would allow
+    /// to keep guessing the passwords of other accounts.
+    pub async fn reset(&self, _attempt: &LoginAttempt<'_>, email: &str) -> AppResult<()> {
+        self.ctx
+            .cache
+            .remove(&Self::account_key(email))
+            .await
+            .map_err(cache_error)?;
+
+        Ok(())
+    }
+
+    /// Locks the counters of the keys. The locks are taken in a fixed order, so two attempts can not deadlock.
+    async fn lock(&self, keys: &[(String, BackoffPolicy)]) -> LoginAttempt<'_> {
+        let mut indices: Vec<usize> = keys
+            .iter()
+            .map(|(key, _)| {
+                let mut hasher = DefaultHasher::new();
+                key.hash(&mut hasher);
+
+                hasher.finish() as usize % LOCK_COUNT
+            })
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut guards = Vec::with_capacity(indices.len());
+        for index in indices {
+            guards.push(self.locks[index].lock().await);
+        }
+
+        LoginAttempt { _guards: guards }
+    }
+
+    async fn get(&self, key: &str, now: DateTime<Utc>) -> AppResult<Option<FailedAttempts>> {
+        let Some(value) = self.ctx.cache.get(key).await.map_err(cache_error)? else {
+            return Ok(None);
+        };
+        let attempts: FailedAttempts = serde_json::from_str(&value).map_err(cache_error)?;
+
+        Ok((!attempts.is_stale(now)).then_some(attempts))
+    }
+
+    /// Counts another failed attempt and returns the number of failed attempts.
+    async fn increment(&self, key: &str, now: DateTime<Utc>) -> AppResult<u32> {
+        let attempts = FailedAttempts {
+            count: self.get(key, now).await?.map_or(0, |attempts| attempts.count) + 1,
+            last_failed_at: now,
+        };
+        let value = serde_json::to_string(&attempts).map_err(cache_error)?;
+        self.ctx.cache.insert(key, &value).await.map_err(cache_error)?;
+
+        Ok(attempts.count)
+    }
+
+    fn keys(email: &str, ip: Option<IpAddr>) -> Vec<(String, BackoffPolicy)> {
+        let mut keys = vec![(Self::account_key(email), ACCOUNT_POLICY)];
+        if let Some(ip) = ip {
+            keys.push((Self::ip_key(ip), IP_POLICY));
+        }
+
+        keys
+    }
+
+    fn account_key(email: &str) -> String {
+        format!("{ACCOUNT_KEY_PREFIX}{}", email.trim().to_lowercase())
+    }
+
+    fn ip_key(ip: IpAddr) -> String {
+        format!("{IP_KEY_PREFIX}{ip}")
+    }
+}
+
+fn cache_error(err: impl ToString) -> AppError {
+    AppError::CacheError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        assert_eq!(ACCOUNT_POLICY.delay(1), Duration::zero());
+        assert_eq!(ACCOUNT_POLICY.delay(3), Duration::zero());
+        assert_eq!(ACCOUNT_POLICY.delay(4), Duration::seconds(2));
+        assert_eq!(ACCOUNT_POLICY.delay(6), Duration::seconds(8));
+        assert_eq!(ACCOUNT_POLICY.delay(9), Duration::seconds(60));
+        assert_eq!(ACCOUNT_POLICY.delay(10), Duration::minutes(15));
+        assert_eq!(ACCOUNT_POLICY.delay(1000), Duration::minutes(15));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = Utc::now();
+        let attempts = FailedAttempts {
+            count: 5,
+            last_failed_at: now - Duration::seconds(1),
+        };
+
+        assert_eq!(attempts.retry_after(&ACCOUNT_POLICY, now), Some(Duration::seconds(3)));
+        assert_eq!(attempts.retry_after(&ACCOUNT_POLICY, now + Duration::seconds(3)), None);
+        assert!(!attempts.is_stale(now));
+        assert!(attempts.is_stale(now + Duration::hours(FAILED_ATTEMPTS_RESET_HOURS)));
+    }
+}
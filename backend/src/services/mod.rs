@@ -1,5 +1,7 @@
 use crate::services::custom_config::CustomConfigInner;
+use crate::services::import::ImportServiceInner;
 use crate::services::instance_handler::InstanceHandlerInner;
+use crate::services::login_protection::LoginProtectionServiceInner;
 use crate::services::snowflake_generator::SnowflakeGeneratorInner;
 use crate::services::status_service::StatusServiceInner;
 use crate::services::user_verification::UserVerificationServiceInner;
@@ -11,7 +13,10 @@ use std::future::Future;
 use std::sync::{Arc, OnceLock};
 
 pub mod custom_config;
+pub mod export;
+pub mod import;
 pub mod instance_handler;
+pub mod login_protection;
 pub mod secret_generator;
 pub mod snowflake_generator;
 pub mod status_service;
@@ -23,8 +28,10 @@ pub async fn configure_services(router: AxumRouter, ctx: &AppContext) -> Result<
         .layer(InstanceHandlerInner::get_extension(ctx).await?)
         .layer(SecretGeneratorInner::get_extension(ctx).await?)
         .layer(UserVerificationServiceInner::get_extension(ctx).await?)
+        .layer(LoginProtectionServiceInner::get_extension(ctx).await?)
         .layer(SnowflakeGeneratorInner::get_extension(ctx).await?)
-        .layer(StatusServiceInner::get_extension(ctx).await?))
+        .layer(StatusServiceInner::get_extension(ctx).await?)
+        .layer(ImportServiceInner::get_extension(ctx).await?))
 }
 
 pub trait Service
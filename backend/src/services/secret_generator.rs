@@ -47,4 +47,25 @@ impl SecretGeneratorInner {
 
         BASE64_URL_SAFE.encode(hash).chars().take(length).collect()
     }
+
+    /// Hashes a token for storing it at rest, as hex encoded SHA-256 digest.
+    ///
+    /// The tokens are random, so a plain digest is enough to make a leaked hash useless.
+    /// It matches the digest that the migration computed for the existing api keys.
+    pub fn hash_token(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        assert_eq!(
+            SecretGeneratorInner::hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
 }
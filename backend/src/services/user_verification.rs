@@ -47,6 +47,34 @@ impl UserVerificationServiceInner {
         Ok(model)
     }
 
+    /// Requests to change the email address of the user to the new one.
+    ///
+    /// The new email address has to be verified with the token that is sent to it, before it replaces the current one.
+    /// Without a configured mailer, the email address is changed right away.
+    pub async fn send_email_change_verification_or_change_email(
+        &self,
+        user: users::ActiveModel,
+        new_email: String,
+    ) -> AppResult<users::Model> {
+        let user = user
+            .set_pending_email(&self.ctx.db, new_email)
+            .await?
+            .into_active_model();
+        let model = match self.ctx.is_mailer_enabled() {
+            true => {
+                let model = user
+                    .set_email_verification_sent(&self.ctx.db, &self.secret_generator)
+                    .await?;
+                AuthMailer::send_email_change(&self.ctx, &model).await?;
+
+                model
+            }
+            false => user.confirm_email_change(&self.ctx.db).await?,
+        };
+
+        Ok(model)
+    }
+
     pub async fn send_forgot_password_email(&self, user: users::ActiveModel) -> AppResult<users::Model> {
         let model = user
             .set_forgot_password_sent(&self.ctx.db, &self.secret_generator)
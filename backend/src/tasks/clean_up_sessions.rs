@@ -0,0 +1,36 @@
+//! This task deletes all sessions that outlived their lifetime or were not used for longer than the idle timeout.
+//! Expired sessions are already rejected on use, this only keeps the `sessions` table small.
+//! Expired two-factor challenges of unfinished logins are deleted as well.
+//! It is run periodically by the scheduler.
+//!
+//! # Example
+//!
+//! ```sh
+//! cargo run task clean_up_sessions
+//! ```
+
+use crate::models::_entities::{sessions, two_factor_challenges};
+use crate::utils::context::AdditionalAppContextMethods;
+use loco_rs::prelude::*;
+use tracing::info;
+
+pub struct CleanUpSessions;
+#[async_trait]
+impl Task for CleanUpSessions {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "clean_up_sessions".to_string(),
+            detail: "Task for deleting all expired sessions".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let config = app_context.get_custom_config().await?;
+        let count = sessions::Model::delete_expired(&app_context.db, &config.session, chrono::Utc::now()).await?;
+        info!("Deleted {} expired sessions", count);
+        let count = two_factor_challenges::Model::delete_expired(&app_context.db, chrono::Utc::now()).await?;
+        info!("Deleted {} expired two-factor challenges", count);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+//! This task deletes all accounts whose scheduled deletion is due, together with all of their data.
+//! Each account is deleted in its own database transaction, the files that are no longer used
+//! are removed from the storage afterward. It is run nightly by the scheduler.
+//!
+//! # Example
+//!
+//! ```sh
+//! cargo run task delete_accounts
+//! ```
+
+use crate::models::_entities::users;
+use crate::models::account_deletions;
+use loco_rs::prelude::*;
+use std::path::PathBuf;
+use tracing::{error, info, warn};
+
+pub struct DeleteAccounts;
+#[async_trait]
+impl Task for DeleteAccounts {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "delete_accounts".to_string(),
+            detail: "Task for deleting all accounts whose scheduled deletion is due".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let mut count = 0;
+        for user in users::Model::find_all_due_for_deletion(&app_context.db, chrono::Utc::now()).await? {
+            let txn = app_context.db.begin().await?;
+            let file_paths = match account_deletions::delete_account(&txn, &user).await {
+                Ok(file_paths) => file_paths,
+                Err(err) => {
+                    error!("Could not delete the account of user {}: {}", user.id, err);
+                    continue;
+                }
+            };
+            txn.commit().await?;
+
+            for file_path in file_paths {
+                if let Err(err) = app_context.storage.delete(&PathBuf::from(&file_path)).await {
+                    warn!(
+                        "Could not delete file {} of deleted user {}: {}",
+                        file_path, user.id, err
+                    );
+                }
+            }
+            count += 1;
+        }
+        info!("Deleted {} accounts", count);
+
+        Ok(())
+    }
+}
@@ -1 +1,4 @@
+pub mod clean_up_sessions;
+pub mod delete_accounts;
 pub mod seed;
+pub mod snapshot_balances;
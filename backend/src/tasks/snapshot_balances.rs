@@ -0,0 +1,38 @@
+//! This task materializes the daily balance of every bank account up to yesterday,
+//! so the net worth history does not need to replay all transactions.
+//! It is run nightly by the scheduler and continues after the latest snapshot of each bank account.
+//!
+//! # Example
+//!
+//! ```sh
+//! cargo run task snapshot_balances
+//! ```
+
+use crate::models::_entities::{balance_snapshots, bank_accounts};
+use loco_rs::prelude::*;
+use tracing::info;
+
+pub struct SnapshotBalances;
+#[async_trait]
+impl Task for SnapshotBalances {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "snapshot_balances".to_string(),
+            detail: "Task for snapshotting the daily balances of all bank accounts".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let Some(yesterday) = chrono::Utc::now().date_naive().pred_opt() else {
+            return Ok(());
+        };
+
+        let mut count = 0;
+        for bank_account in bank_accounts::Model::find_all(&app_context.db).await? {
+            count += balance_snapshots::Model::snapshot(&app_context.db, &bank_account, yesterday).await?;
+        }
+        info!("Snapshotted {} daily balances", count);
+
+        Ok(())
+    }
+}
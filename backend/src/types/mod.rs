@@ -1 +1,3 @@
+pub mod pagination;
 pub mod snowflake;
+pub mod snowflake_list;
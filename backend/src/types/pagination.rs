@@ -0,0 +1,248 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::types::snowflake::Snowflake;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use loco_rs::app::AppContext;
+use sea_orm::sea_query::{Expr, SimpleExpr, ValueType};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, IdenStatic, Iterable, ModelTrait, Order, PrimaryKeyToColumn,
+    QueryFilter, QueryOrder, QuerySelect, Select,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{KnownFormat, ObjectBuilder, Required, SchemaFormat, Type};
+use utoipa::{IntoParams, PartialSchema, ToSchema};
+use validator::Validate;
+
+pub const DEFAULT_LIMIT: u64 = 50;
+pub const MAX_LIMIT: u64 = 200;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// A field that the items of a list can be sorted by.
+///
+/// The column must not be nullable. Items with the same value are ordered by their id,
+/// so the order is always stable and the id of the last item can be used as the cursor.
+pub trait SortField<E: EntityTrait> {
+    /// The column to sort by, `None` to sort by the id only.
+    fn column(&self) -> Option<E::Column>;
+}
+
+/// Sorts by the id, which is the order the items were created in (see [`Snowflake`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdSort {
+    #[default]
+    Id,
+}
+
+impl<E: EntityTrait> SortField<E> for IdSort {
+    fn column(&self) -> Option<E::Column> {
+        None
+    }
+}
+
+/// Keyset pagination of a list endpoint, extracted from the query string.
+///
+/// Instead of page numbers, the id of the last item of the previous page is used as the cursor.
+/// This keeps pages stable while items are added or removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pagination<S = IdSort> {
+    /// The id of the last item of the previous page.
+    pub cursor: Option<i64>,
+    pub limit: u64,
+    pub sort: S,
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct PaginationQuery<S> {
+    cursor: Option<Snowflake>,
+    #[validate(range(min = 1, max = "MAX_LIMIT"))]
+    limit: Option<u64>,
+    sort: Option<S>,
+    direction: Option<SortDirection>,
+}
+
+/// A page of a list and the cursor of the next page.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    /// Pass this as `cursor` to retrieve the next page. Missing on the last page.
+    pub next_cursor: Option<Snowflake>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            data: self.data.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl<S> Pagination<S> {
+    /// Loads a page of the query.
+    ///
+    /// One more item than requested is loaded to find out whether there is a next page.
+    /// When sorting by another column than the id, the cursor has to be an item of the query,
+    /// as its value is needed to find the items after it.
+    pub async fn load<E>(&self, db: &impl ConnectionTrait, query: Select<E>) -> AppResult<Page<E::Model>>
+    where
+        E: EntityTrait,
+        S: SortField<E>,
+    {
+        let id = id_column::<E>()?;
+        let column = self.sort.column().filter(|column| !is_same_column(column, &id));
+        let order = match self.direction {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        };
+
+        let after_cursor = match (self.cursor, column) {
+            (None, _) => None,
+            (Some(cursor), None) => Some(self.after_cursor::<E>(cursor, None, id)),
+            (Some(cursor), Some(column)) => {
+                // Only items of the query itself are valid cursors, so no values of other items are revealed.
+                let cursor_model = query
+                    .clone()
+                    .filter(id.eq(cursor))
+                    .one(db)
+                    .await?
+                    .ok_or_else(AppError::InvalidCursor)?;
+                Some(self.after_cursor::<E>(cursor, Some((column, cursor_model.get(column))), id))
+            }
+        };
+
+        let mut query = match column {
+            None => query.order_by(id, order),
+            Some(column) => query.order_by(column, order.clone()).order_by(id, order),
+        };
+        if let Some(after_cursor) = after_cursor {
+            query = query.filter(after_cursor);
+        }
+
+        let mut data = query.limit(self.limit + 1).all(db).await?;
+        let next_cursor = match data.len() as u64 > self.limit {
+            true => {
+                data.truncate(self.limit as usize);
+                data.last()
+                    .and_then(|model| <i64 as ValueType>::try_from(model.get(id)).ok())
+                    .map(Snowflake::new)
+            }
+            false => None,
+        };
+
+        Ok(Page { data, next_cursor })
+    }
+
+    /// Builds the condition that only matches items after the cursor, e.g. `(column, id) < (value of cursor, cursor)`.
+    fn after_cursor<E: EntityTrait>(
+        &self,
+        cursor: i64,
+        column: Option<(E::Column, sea_orm::Value)>,
+        id: E::Column,
+    ) -> SimpleExpr {
+        let compare = |left: Expr, right: SimpleExpr| match self.direction {
+            SortDirection::Asc => left.gt(right),
+            SortDirection::Desc => left.lt(right),
+        };
+        let Some((column, cursor_value)) = column else {
+            return compare(Expr::col((E::default(), id)), Expr::val(cursor).into());
+        };
+
+        compare(
+            Expr::tuple([
+                Expr::col((E::default(), column)).into(),
+                Expr::col((E::default(), id)).into(),
+            ]),
+            Expr::tuple([Expr::val(cursor_value).into(), Expr::val(cursor).into()]).into(),
+        )
+    }
+}
+
+/// Compares columns by their name, as the generated column enums do not implement `PartialEq`.
+fn is_same_column<C: ColumnTrait>(column: &C, other: &C) -> bool {
+    column.as_str() == other.as_str()
+}
+
+/// The (single) primary key column of the entity, which is a snowflake id for all entities.
+fn id_column<E: EntityTrait>() -> AppResult<E::Column> {
+    E::PrimaryKey::iter()
+        .next()
+        .map(PrimaryKeyToColumn::into_column)
+        .ok_or_else(|| AppError::GeneralInternalServerError("The entity has no primary key.".to_string()))
+}
+
+impl<S> FromRequestParts<AppContext> for Pagination<S>
+where
+    S: DeserializeOwned + Default + Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &AppContext) -> AppResult<Self> {
+        let Query(query) = Query::<PaginationQuery<S>>::try_from_uri(&parts.uri)
+            .map_err(|rejection| AppError::GeneralBadRequest(rejection.body_text()))?;
+        query.validate()?;
+
+        Ok(Self {
+            cursor: query.cursor.map(|cursor| cursor.id),
+            limit: query.limit.unwrap_or(DEFAULT_LIMIT),
+            sort: query.sort.unwrap_or_default(),
+            direction: query.direction.unwrap_or_default(),
+        })
+    }
+}
+
+impl<S: ToSchema> IntoParams for Pagination<S> {
+    fn into_params(_parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![
+            ParameterBuilder::new()
+                .name("cursor")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some("The `next_cursor` of the previous page."))
+                .schema(Some(Snowflake::schema()))
+                .build(),
+            ParameterBuilder::new()
+                .name("limit")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some("The maximum number of items per page."))
+                .schema(Some(
+                    ObjectBuilder::new()
+                        .default(Some(Value::from(DEFAULT_LIMIT)))
+                        .minimum(Some(1f64))
+                        .maximum(Some(MAX_LIMIT as f64))
+                        .schema_type(Type::Integer)
+                        .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int64)))
+                        .build(),
+                ))
+                .build(),
+            ParameterBuilder::new()
+                .name("sort")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some(
+                    "The field to sort by. Items with the same value are sorted by their id.",
+                ))
+                .schema(Some(S::schema()))
+                .build(),
+            ParameterBuilder::new()
+                .name("direction")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some("The sort direction, descending (newest first) by default."))
+                .schema(Some(SortDirection::schema()))
+                .build(),
+        ]
+    }
+}
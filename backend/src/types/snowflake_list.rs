@@ -0,0 +1,68 @@
+use crate::types::snowflake::Snowflake;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::openapi::schema::SchemaType;
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, Type};
+use utoipa::{PartialSchema, ToSchema};
+
+/// A comma separated list of snowflake ids, used in query strings (e.g. `?tag_ids=1,2,3`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnowflakeList(pub Vec<Snowflake>);
+
+impl SnowflakeList {
+    pub fn ids(&self) -> Vec<i64> {
+        self.0.iter().map(i64::from).collect()
+    }
+}
+
+impl ToSchema for SnowflakeList {}
+
+impl PartialSchema for SnowflakeList {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .description(Some("A comma separated list of unique identifiers."))
+            .schema_type(SchemaType::Type(Type::String))
+            .examples(vec![serde_json::json!("60503861139345408,60503861139345409")])
+            .build()
+            .into()
+    }
+}
+
+impl Serialize for SnowflakeList {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ids: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&ids.join(","))
+    }
+}
+
+impl<'de> Deserialize<'de> for SnowflakeList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse::<i64>().map(Snowflake::new).map_err(Error::custom))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let list: SnowflakeList = serde_json::from_str("\"1, 2,,3\"").unwrap();
+        assert_eq!(list.ids(), vec![1, 2, 3]);
+        assert_eq!(serde_json::to_string(&list).unwrap(), "\"1,2,3\"");
+
+        assert!(serde_json::from_str::<SnowflakeList>("\"1,abc\"").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+/// Converts an amount (in the smallest unit of its currency) into another currency.
+///
+/// The rate is the price of one major unit of the source currency in major units of the target currency
+/// (e.g. `1.08` for EUR to USD). Returns `None` if the result does not fit into an `i64`.
+pub fn convert_amount(amount: i64, rate: f64, source_decimal_places: i32, target_decimal_places: i32) -> Option<i64> {
+    let scale = 10f64.powi(target_decimal_places - source_decimal_places);
+    let converted = (amount as f64 * rate * scale).round();
+
+    match converted.is_finite() && converted.abs() < i64::MAX as f64 {
+        true => Some(converted as i64),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_amount() {
+        assert_eq!(convert_amount(10000, 1.08, 2, 2), Some(10800));
+        assert_eq!(convert_amount(-10000, 1.08, 2, 2), Some(-10800));
+        // 100.00 EUR to JPY (no decimal places).
+        assert_eq!(convert_amount(10000, 162.5, 2, 0), Some(16250));
+        // 16250 JPY to EUR.
+        assert_eq!(convert_amount(16250, 0.00615, 0, 2), Some(9994));
+        assert_eq!(convert_amount(i64::MAX, 2.0, 2, 2), None);
+    }
+}
@@ -0,0 +1,7 @@
+/// Normalizes an iban by removing all whitespaces and converting it to uppercase.
+pub fn normalize_iban(iban: &str) -> String {
+    iban.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
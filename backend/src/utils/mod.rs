@@ -1,5 +1,10 @@
 pub mod context;
 pub mod datetime;
 pub mod env;
+pub mod exchange_rate;
 pub mod folder;
+pub mod iban;
+pub mod naive_bayes;
 pub mod routes;
+pub mod similarity;
+pub mod totp;
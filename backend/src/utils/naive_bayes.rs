@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A multinomial naive Bayes classifier over tokens with Laplace smoothing.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NaiveBayes {
+    /// How many documents were seen per label.
+    label_counts: HashMap<i64, u32>,
+    /// How often each token was seen per label.
+    token_counts: HashMap<i64, HashMap<String, u32>>,
+    /// The sum of all token counts per label.
+    token_totals: HashMap<i64, u32>,
+    vocabulary_size: u32,
+}
+
+impl NaiveBayes {
+    pub fn train<I>(documents: I) -> Self
+    where
+        I: IntoIterator<Item = (i64, Vec<String>)>,
+    {
+        let mut classifier = Self::default();
+        let mut vocabulary = HashSet::new();
+
+        for (label, tokens) in documents {
+            *classifier.label_counts.entry(label).or_default() += 1;
+            let counts = classifier.token_counts.entry(label).or_default();
+            let total = classifier.token_totals.entry(label).or_default();
+            for token in tokens {
+                *counts.entry(token.clone()).or_default() += 1;
+                *total += 1;
+                vocabulary.insert(token);
+            }
+        }
+        classifier.vocabulary_size = vocabulary.len() as u32;
+
+        classifier
+    }
+
+    /// Returns the most likely labels with their probability (0 - 1), the most likely label first.
+    pub fn predict(&self, tokens: &[String], limit: usize) -> Vec<(i64, f64)> {
+        let documents: u32 = self.label_counts.values().sum();
+        if documents == 0 {
+            return Vec::new();
+        }
+
+        let vocabulary_size = f64::from(self.vocabulary_size.max(1));
+        let mut scores: Vec<(i64, f64)> = self
+            .label_counts
+            .iter()
+            .map(|(label, count)| {
+                let counts = self.token_counts.get(label);
+                let total = f64::from(self.token_totals.get(label).copied().unwrap_or_default());
+                let prior = (f64::from(*count) / f64::from(documents)).ln();
+
+                let likelihood: f64 = tokens
+                    .iter()
+                    .map(|token| {
+                        let count = counts.and_then(|counts| counts.get(token)).copied().unwrap_or_default();
+                        ((f64::from(count) + 1.0) / (total + vocabulary_size)).ln()
+                    })
+                    .sum();
+
+                (*label, prior + likelihood)
+            })
+            .collect();
+
+        // Converts the log scores into probabilities (log-sum-exp for numerical stability).
+        let max = scores.iter().map(|(_, score)| *score).fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = scores.iter().map(|(_, score)| (score - max).exp()).sum();
+        for (_, score) in scores.iter_mut() {
+            *score = (*score - max).exp() / sum;
+        }
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scores.truncate(limit);
+
+        scores
+    }
+}
+
+/// Splits a text into lowercase words. Numbers and single characters are skipped,
+/// they rarely say anything about the category (dates, reference numbers, ...).
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1 && !word.chars().all(|c| c.is_numeric()))
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<String> {
+        tokenize(text).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokens("REWE Markt GmbH/Berlin 24.12."),
+            vec!["rewe", "markt", "gmbh", "berlin"]
+        );
+        assert!(tokens("- 1 x").is_empty());
+    }
+
+    #[test]
+    fn test_predict() {
+        let classifier = NaiveBayes::train([
+            (1, tokens("REWE Markt Groceries")),
+            (1, tokens("Lidl Groceries")),
+            (2, tokens("Landlord Rent January")),
+            (3, tokens("Netflix Subscription")),
+        ]);
+
+        let suggestions = classifier.predict(&tokens("rewe city"), 3);
+        assert_eq!(suggestions.len(), 3);
+        assert_eq!(suggestions[0].0, 1);
+        assert!(suggestions[0].1 > suggestions[1].1);
+        assert!((suggestions.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        assert_eq!(classifier.predict(&tokens("rent february"), 1)[0].0, 2);
+        assert!(NaiveBayes::default().predict(&tokens("rent"), 3).is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// Compares two names and returns a similarity between `0.0` (nothing in common) and `1.0` (equal).
+///
+/// Case, punctuation and whitespaces are ignored.
+/// The similarity is the Sørensen–Dice coefficient of the character bigrams.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a);
+    let b = normalize(b);
+
+    if a == b {
+        return 1.0;
+    }
+    if a.len() < 2 || b.len() < 2 {
+        return 0.0;
+    }
+
+    let mut bigrams: HashMap<(char, char), usize> = HashMap::new();
+    for bigram in a.windows(2) {
+        *bigrams.entry((bigram[0], bigram[1])).or_default() += 1;
+    }
+
+    let mut matches = 0;
+    for bigram in b.windows(2) {
+        if let Some(count) = bigrams.get_mut(&(bigram[0], bigram[1])).filter(|count| **count > 0) {
+            *count -= 1;
+            matches += 1;
+        }
+    }
+
+    (2 * matches) as f64 / (a.len() + b.len() - 2) as f64
+}
+
+fn normalize(value: &str) -> Vec<char> {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("ACME Corp.", "acme corp"), 1.0);
+        assert_eq!(name_similarity("", "acme"), 0.0);
+        assert_eq!(name_similarity("abc", "xyz"), 0.0);
+        assert!(name_similarity("Amazon Marketplace", "AMAZON MKTPLACE") > 0.6);
+        assert!(name_similarity("Netflix", "Spotify") < 0.3);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::app_error::{AppError, AppResult};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+pub const TOTP_ISSUER: &str = "financrr";
+pub const TOTP_DIGITS: usize = 6;
+pub const TOTP_STEP_SECONDS: u64 = 30;
+/// Codes of the previous and the next step are accepted as well, to tolerate clock drift.
+pub const TOTP_SKEW: u8 = 1;
+
+/// Generates a new random secret, encoded as base32.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// Builds the TOTP generator for the base32 encoded secret of the account.
+pub fn build_totp(secret: &str, account_name: &str) -> AppResult<TOTP> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| AppError::GeneralInternalServerError(err.to_string()))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW,
+        TOTP_STEP_SECONDS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|err| AppError::GeneralInternalServerError(err.to_string()))
+}
+
+/// Returns the base64 encoded PNG of the QR code that authenticator apps scan.
+pub fn qr_code_png(totp: &TOTP) -> AppResult<String> {
+    totp.get_qr_base64().map_err(AppError::GeneralInternalServerError)
+}
+
+/// Checks the code against the time of `unix_seconds`.
+/// Returns the time step the code belongs to, so it can be accepted only once.
+pub fn verify_code(totp: &TOTP, code: &str, unix_seconds: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS {
+        return None;
+    }
+
+    // Every step is checked on its own, to find out which one matched.
+    let exact = TOTP {
+        skew: 0,
+        ..totp.clone()
+    };
+    let current_step = unix_seconds / TOTP_STEP_SECONDS;
+    (current_step.saturating_sub(TOTP_SKEW as u64)..=current_step + TOTP_SKEW as u64)
+        .find(|step| exact.check(code, step * TOTP_STEP_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_code() {
+        let totp = build_totp(&generate_secret(), "user@financrr.test").unwrap();
+        let now = 1_700_000_000;
+
+        let step = now / TOTP_STEP_SECONDS;
+
+        let code = totp.generate(now);
+        assert_eq!(verify_code(&totp, &code, now), Some(step));
+        assert_eq!(verify_code(&totp, &format!(" {code} "), now), Some(step));
+        assert_eq!(verify_code(&totp, &code, now + TOTP_STEP_SECONDS), Some(step));
+        assert_eq!(verify_code(&totp, &code, now + 3 * TOTP_STEP_SECONDS), None);
+        assert_eq!(verify_code(&totp, "", now), None);
+
+        let next_code = totp.generate(now + TOTP_STEP_SECONDS);
+        assert_eq!(verify_code(&totp, &next_code, now), Some(step + 1));
+    }
+
+    #[test]
+    fn test_otpauth_url() {
+        let totp = build_totp("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP", "user@financrr.test").unwrap();
+
+        let url = totp.get_url();
+        assert!(url.starts_with("otpauth://totp/financrr:user%40financrr.test?"));
+        assert!(url.contains("secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP"));
+        assert!(url.contains("issuer=financrr"));
+    }
+}
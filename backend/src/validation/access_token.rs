@@ -0,0 +1,11 @@
+use crate::validation::ValidationResult;
+use chrono::{DateTime, FixedOffset};
+use validator::ValidationError;
+
+pub fn validate_expires_in_future(expires_at: &DateTime<FixedOffset>) -> ValidationResult {
+    if *expires_at <= chrono::Utc::now() {
+        return Err(ValidationError::new("The expiry must lie in the future"));
+    }
+
+    Ok(())
+}
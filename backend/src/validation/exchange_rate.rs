@@ -0,0 +1,11 @@
+use crate::controllers::exchange_rate::ExchangeRateParams;
+use crate::validation::ValidationResult;
+use validator::ValidationError;
+
+pub fn validate_exchange_rate(params: &ExchangeRateParams) -> ValidationResult {
+    if params.source_currency_id == params.target_currency_id {
+        return Err(ValidationError::new("The source and target currency must differ"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::controllers::import::{ImportProfileParams, DECIMAL_SEPARATORS};
+use crate::models::_entities::sea_orm_active_enums::ImportSignConvention;
+use crate::validation::ValidationResult;
+use validator::ValidationError;
+
+pub fn validate_import_profile(params: &ImportProfileParams) -> ValidationResult {
+    if !params.delimiter.is_ascii() {
+        return Err(ValidationError::new("Delimiter must be an ASCII character"));
+    }
+
+    if params.thousands_separator.as_ref() == Some(&params.decimal_separator) {
+        return Err(ValidationError::new(
+            "Decimal and thousands separator must be different",
+        ));
+    }
+
+    let mapping = &params.column_mapping;
+    match params.sign_convention {
+        ImportSignConvention::Signed | ImportSignConvention::Inverted if mapping.amount.is_none() => {
+            Err(ValidationError::new("Column mapping requires an amount column"))
+        }
+        ImportSignConvention::SeparateColumns if mapping.debit.is_none() && mapping.credit.is_none() => {
+            Err(ValidationError::new("Column mapping requires a debit or credit column"))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_decimal_separator(separator: &str) -> ValidationResult {
+    if !DECIMAL_SEPARATORS.contains(&separator) {
+        return Err(ValidationError::new("Decimal separator must be either '.' or ','"));
+    }
+
+    Ok(())
+}
@@ -1,5 +1,11 @@
 use validator::ValidationError;
 
+pub mod access_token;
+pub mod exchange_rate;
+pub mod import;
+pub mod report;
+pub mod rule;
+pub mod transaction;
 pub mod user;
 
 pub type ValidationResult = Result<(), ValidationError>;
@@ -0,0 +1,25 @@
+use crate::controllers::report::{NetWorthQuery, ReportQuery, MAX_NET_WORTH_DAYS};
+use crate::validation::ValidationResult;
+use validator::ValidationError;
+
+pub fn validate_report_query(query: &ReportQuery) -> ValidationResult {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(ValidationError::new("The start date must not be after the end date"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_net_worth_query(query: &NetWorthQuery) -> ValidationResult {
+    let (from, to) = query.range();
+    if from > to {
+        return Err(ValidationError::new("The start date must not be after the end date"));
+    }
+    if (to - from).num_days() >= MAX_NET_WORTH_DAYS {
+        return Err(ValidationError::new("The date range is too long"));
+    }
+
+    Ok(())
+}
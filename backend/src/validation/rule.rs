@@ -0,0 +1,30 @@
+use crate::controllers::rule::TransactionRuleParams;
+use crate::validation::ValidationResult;
+use validator::ValidationError;
+
+pub fn validate_transaction_rule(params: &TransactionRuleParams) -> ValidationResult {
+    let has_condition = params.name_contains.is_some()
+        || params.purpose_contains.is_some()
+        || params.source_iban.is_some()
+        || params.destination_iban.is_some()
+        || params.min_amount.is_some()
+        || params.max_amount.is_some()
+        || params.transaction_type.is_some();
+    if !has_condition {
+        return Err(ValidationError::new("Rule requires at least one condition"));
+    }
+
+    if params.category_id.is_none() && params.tag_ids.is_empty() && params.rename_to.is_none() {
+        return Err(ValidationError::new("Rule requires at least one action"));
+    }
+
+    if let (Some(min_amount), Some(max_amount)) = (params.min_amount, params.max_amount) {
+        if min_amount > max_amount {
+            return Err(ValidationError::new(
+                "Minimum amount must not be greater than the maximum amount",
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::controllers::transaction::{CreateTransferParams, SplitTransactionParams, TransactionSearchQuery};
+use crate::validation::ValidationResult;
+use validator::ValidationError;
+
+pub fn validate_non_zero_amount(amount: &i64) -> ValidationResult {
+    if *amount == 0 {
+        return Err(ValidationError::new("Amount must not be zero"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_split_transaction(params: &SplitTransactionParams) -> ValidationResult {
+    if params.splits.len() == 1 {
+        return Err(ValidationError::new("A split requires at least two lines"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_create_transfer(params: &CreateTransferParams) -> ValidationResult {
+    if params.exchange_rate.is_some() && params.destination_amount.is_some() {
+        return Err(ValidationError::new(
+            "Either the exchange rate or the destination amount can be given, not both",
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_transaction_search(query: &TransactionSearchQuery) -> ValidationResult {
+    if let (Some(min_amount), Some(max_amount)) = (query.min_amount, query.max_amount) {
+        if min_amount > max_amount {
+            return Err(ValidationError::new(
+                "The minimum amount must not exceed the maximum amount",
+            ));
+        }
+    }
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(ValidationError::new("The start date must not be after the end date"));
+        }
+    }
+
+    Ok(())
+}
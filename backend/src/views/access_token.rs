@@ -0,0 +1,34 @@
+use crate::models::_entities::personal_access_tokens::Model;
+use crate::models::personal_access_tokens::Scope;
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AccessTokenResponse {
+    pub id: Snowflake,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// The token itself. Only returned once, when the token is created.
+    pub token: Option<String>,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub last_used_at: Option<DateTime<FixedOffset>>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl From<Model> for AccessTokenResponse {
+    fn from(value: Model) -> Self {
+        let scopes = value.get_scopes().iter().collect();
+
+        Self {
+            id: Snowflake::new(value.id),
+            name: value.name,
+            scopes,
+            token: None,
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::models::_entities::audit_logs::Model;
+use crate::models::_entities::invite_codes;
+use crate::models::_entities::sea_orm_active_enums::AuditAction;
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Snowflake,
+    /// The admin who performed the action. `null` once the admin was deleted.
+    pub actor_id: Option<Snowflake>,
+    /// The User the action was performed on. `null` once the User was deleted.
+    pub target_user_id: Option<Snowflake>,
+    pub action: AuditAction,
+    /// Additional information depending on the action, e.g. the reason for disabling an account.
+    pub details: Option<serde_json::Value>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl From<Model> for AuditLogResponse {
+    fn from(value: Model) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            actor_id: value.actor_id.map(Snowflake::new),
+            target_user_id: value.target_user_id.map(Snowflake::new),
+            action: value.action,
+            details: value.details,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InviteCodeResponse {
+    pub id: Snowflake,
+    /// The invite code, only returned when it is created.
+    pub code: Option<String>,
+    pub created_by_id: Option<Snowflake>,
+    /// The code can only be used to register this email address.
+    pub email: Option<String>,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl From<invite_codes::Model> for InviteCodeResponse {
+    fn from(value: invite_codes::Model) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            code: None,
+            created_by_id: value.created_by_id.map(Snowflake::new),
+            email: value.email,
+            max_uses: value.max_uses,
+            uses: value.uses,
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+        }
+    }
+}
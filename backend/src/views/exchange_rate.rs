@@ -0,0 +1,29 @@
+use crate::models::_entities::exchange_rates::Model;
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeRateResponse {
+    pub id: Snowflake,
+    pub source_currency_id: Snowflake,
+    pub target_currency_id: Snowflake,
+    /// The price of one major unit of the source currency in major units of the target currency.
+    pub rate: f64,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<Model> for ExchangeRateResponse {
+    fn from(value: Model) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            source_currency_id: Snowflake::new(value.source_currency_id),
+            target_currency_id: Snowflake::new(value.target_currency_id),
+            rate: value.rate,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
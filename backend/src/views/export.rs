@@ -0,0 +1,38 @@
+use crate::models::_entities::exports::Model;
+use crate::models::_entities::sea_orm_active_enums::{ExportFormat, ExportStatus};
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportResponse {
+    pub id: Snowflake,
+    pub format: ExportFormat,
+    pub status: ExportStatus,
+    /// Why the export failed.
+    pub error: Option<String>,
+    /// Where the file can be downloaded once the export is finished.
+    pub download_url: Option<String>,
+    pub finished_at: Option<DateTime<FixedOffset>>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl From<Model> for ExportResponse {
+    fn from(value: Model) -> Self {
+        let download_url = match value.status {
+            ExportStatus::Finished => Some(format!("/api/v1/exports/{}/download", value.id)),
+            _ => None,
+        };
+
+        Self {
+            id: Snowflake::new(value.id),
+            format: value.format,
+            status: value.status,
+            error: value.error,
+            download_url,
+            finished_at: value.finished_at,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::app_error::AppError;
+use crate::models::_entities::import_profiles::Model;
+use crate::models::_entities::sea_orm_active_enums::ImportSignConvention;
+use crate::services::import::csv::CsvColumnMapping;
+use crate::services::import::ImportRowOutcome;
+use crate::types::snowflake::Snowflake;
+use crate::views::transaction::{CategorySuggestionResponse, TransactionResponse};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportProfileResponse {
+    pub id: Snowflake,
+    pub name: String,
+    pub column_mapping: CsvColumnMapping,
+    pub date_format: String,
+    pub decimal_separator: String,
+    pub thousands_separator: Option<String>,
+    pub delimiter: String,
+    pub has_header: bool,
+    pub skip_rows: i32,
+    pub sign_convention: ImportSignConvention,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl TryFrom<Model> for ImportProfileResponse {
+    type Error = AppError;
+
+    fn try_from(value: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: Snowflake::new(value.id),
+            name: value.name,
+            column_mapping: serde_json::from_value(value.column_mapping)
+                .map_err(|err| AppError::DbParseJson(err.to_string()))?,
+            date_format: value.date_format,
+            decimal_separator: value.decimal_separator,
+            thousands_separator: value.thousands_separator,
+            delimiter: value.delimiter,
+            has_header: value.has_header,
+            skip_rows: value.skip_rows,
+            sign_convention: value.sign_convention,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowResponse {
+    /// The line of the file this row starts at.
+    pub row: usize,
+    /// The created transaction. During a dry-run this transaction is not persisted.
+    pub transaction: Option<TransactionResponse>,
+    /// The already existing transaction this row is a duplicate of.
+    pub duplicate_of: Option<Snowflake>,
+    /// Suggested categories for uncategorized transactions (only during a dry-run).
+    pub category_suggestions: Vec<CategorySuggestionResponse>,
+    pub errors: Vec<String>,
+}
+
+impl From<ImportRowOutcome> for ImportRowResponse {
+    fn from(value: ImportRowOutcome) -> Self {
+        Self {
+            row: value.row,
+            transaction: value.transaction.map(TransactionResponse::from),
+            duplicate_of: value.duplicate_of.map(Snowflake::new),
+            category_suggestions: value
+                .category_suggestions
+                .into_iter()
+                .map(CategorySuggestionResponse::from)
+                .collect(),
+            errors: value.errors,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportResponse {
+    pub dry_run: bool,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResponse>,
+}
+
+impl From<(Vec<ImportRowOutcome>, bool)> for ImportResponse {
+    fn from((outcomes, dry_run): (Vec<ImportRowOutcome>, bool)) -> Self {
+        let rows: Vec<ImportRowResponse> = outcomes.into_iter().map(ImportRowResponse::from).collect();
+
+        Self {
+            dry_run,
+            imported: rows.iter().filter(|row| row.transaction.is_some()).count(),
+            duplicates: rows.iter().filter(|row| row.duplicate_of.is_some()).count(),
+            failed: rows.iter().filter(|row| !row.errors.is_empty()).count(),
+            rows,
+        }
+    }
+}
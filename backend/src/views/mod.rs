@@ -1,4 +1,14 @@
+pub mod access_token;
+pub mod admin;
 pub mod auth;
+pub mod exchange_rate;
+pub mod export;
+pub mod import;
+pub mod reconciliation;
+pub mod report;
+pub mod rule;
 pub mod session;
 pub mod status;
+pub mod transaction;
+pub mod two_factor;
 pub mod user;
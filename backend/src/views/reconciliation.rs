@@ -0,0 +1,74 @@
+use crate::models::_entities::reconciliations::Model;
+use crate::models::reconciliations::ReconciliationPreview;
+use crate::types::snowflake::Snowflake;
+use crate::views::transaction::TransactionResponse;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReconciliationResponse {
+    pub id: Snowflake,
+    pub bank_account_id: Snowflake,
+    pub statement_date: DateTime<FixedOffset>,
+    pub statement_balance: i64,
+    pub transaction_ids: Vec<Snowflake>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<(Model, Vec<i64>)> for ReconciliationResponse {
+    fn from((value, transaction_ids): (Model, Vec<i64>)) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            bank_account_id: Snowflake::new(value.bank_account_id),
+            statement_date: value.statement_date,
+            statement_balance: value.statement_balance,
+            transaction_ids: transaction_ids.into_iter().map(Snowflake::new).collect(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UnclearedTransactionResponse {
+    pub transaction: TransactionResponse,
+    /// The (signed) change of the balance of the bank account.
+    pub amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReconciliationPreviewResponse {
+    /// The statement balance of the last reconciliation or the original balance of the bank account.
+    pub opening_balance: i64,
+    pub last_statement_date: Option<DateTime<FixedOffset>>,
+    pub statement_balance: i64,
+    /// The balance after reconciling all uncleared transactions.
+    pub cleared_balance: i64,
+    /// The difference between the statement balance and the cleared balance.
+    pub difference: i64,
+    pub uncleared: Vec<UnclearedTransactionResponse>,
+}
+
+impl From<(ReconciliationPreview, i64)> for ReconciliationPreviewResponse {
+    fn from((value, statement_balance): (ReconciliationPreview, i64)) -> Self {
+        let cleared_balance = value.cleared_balance();
+
+        Self {
+            opening_balance: value.opening_balance,
+            last_statement_date: value.last_statement_date,
+            statement_balance,
+            cleared_balance,
+            difference: statement_balance - cleared_balance,
+            uncleared: value
+                .uncleared
+                .into_iter()
+                .map(|(transaction, amount)| UnclearedTransactionResponse {
+                    transaction: TransactionResponse::from(transaction),
+                    amount,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::models::forecasts::{AccountForecast, ForecastDay, ForecastItem};
+use crate::models::reports::{CategoryReportRow, NetWorthRow, ReportRow};
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// All amounts are positive and in the smallest unit of the currency.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CashFlowResponse {
+    /// The start of the month.
+    pub month: DateTime<FixedOffset>,
+    pub currency_id: Snowflake,
+    pub income: i64,
+    pub expense: i64,
+    pub transaction_count: i64,
+}
+
+impl From<ReportRow<DateTime<FixedOffset>>> for CashFlowResponse {
+    fn from(value: ReportRow<DateTime<FixedOffset>>) -> Self {
+        Self {
+            month: value.key,
+            currency_id: Snowflake::new(value.currency_id),
+            income: value.totals.income,
+            expense: value.totals.expense,
+            transaction_count: value.totals.count,
+        }
+    }
+}
+
+/// All amounts are positive and in the smallest unit of the currency.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategorySpendingResponse {
+    /// Missing for uncategorized transactions.
+    pub category_id: Option<Snowflake>,
+    pub currency_id: Snowflake,
+    /// Only the transactions of the category itself.
+    pub income: i64,
+    /// Only the transactions of the category itself.
+    pub expense: i64,
+    /// Including all children of the category.
+    pub total_income: i64,
+    /// Including all children of the category.
+    pub total_expense: i64,
+}
+
+impl From<CategoryReportRow> for CategorySpendingResponse {
+    fn from(value: CategoryReportRow) -> Self {
+        Self {
+            category_id: value.category_id.map(Snowflake::new),
+            currency_id: Snowflake::new(value.currency_id),
+            income: value.own.income,
+            expense: value.own.expense,
+            total_income: value.total.income,
+            total_expense: value.total.expense,
+        }
+    }
+}
+
+/// All amounts are positive and in the smallest unit of the currency.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CounterpartyResponse {
+    /// Missing if the transactions have no counterparty name.
+    pub name: Option<String>,
+    pub currency_id: Snowflake,
+    pub income: i64,
+    pub expense: i64,
+    pub transaction_count: i64,
+}
+
+impl From<ReportRow<Option<String>>> for CounterpartyResponse {
+    fn from(value: ReportRow<Option<String>>) -> Self {
+        Self {
+            name: value.key,
+            currency_id: Snowflake::new(value.currency_id),
+            income: value.totals.income,
+            expense: value.totals.expense,
+            transaction_count: value.totals.count,
+        }
+    }
+}
+
+/// The balance is in the smallest unit of the currency and may be negative.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NetWorthResponse {
+    /// The last day of the period, or the last day of the report if the period continues after it.
+    pub date: NaiveDate,
+    pub currency_id: Snowflake,
+    pub net_worth: i64,
+}
+
+impl From<NetWorthRow> for NetWorthResponse {
+    fn from(value: NetWorthRow) -> Self {
+        Self {
+            date: value.date,
+            currency_id: Snowflake::new(value.currency_id),
+            net_worth: value.balance,
+        }
+    }
+}
+
+/// All amounts are in the smallest unit of the currency of the bank account.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForecastResponse {
+    pub bank_account_id: Snowflake,
+    pub currency_id: Snowflake,
+    /// The current balance.
+    pub balance: i64,
+    /// Only days with at least one expected change, in chronological order.
+    pub days: Vec<ForecastDayResponse>,
+}
+
+impl From<AccountForecast> for ForecastResponse {
+    fn from(value: AccountForecast) -> Self {
+        Self {
+            bank_account_id: Snowflake::new(value.bank_account.id),
+            currency_id: Snowflake::new(value.bank_account.currency_id),
+            balance: value.bank_account.balance,
+            days: value.days.into_iter().map(ForecastDayResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForecastDayResponse {
+    pub date: NaiveDate,
+    /// The projected balance at the end of the day.
+    pub balance: i64,
+    /// Whether the projected balance is negative.
+    pub negative: bool,
+    pub changes: Vec<ForecastChangeResponse>,
+}
+
+impl From<ForecastDay> for ForecastDayResponse {
+    fn from(value: ForecastDay) -> Self {
+        Self {
+            date: value.date,
+            balance: value.balance,
+            negative: value.balance < 0,
+            changes: value.items.into_iter().map(ForecastChangeResponse::from).collect(),
+        }
+    }
+}
+
+/// Either an execution of a recurring transaction or a pending transaction.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForecastChangeResponse {
+    /// The name of the contract or the transaction.
+    pub name: String,
+    /// Negative if the money leaves the bank account.
+    pub amount: i64,
+    pub recurring_transaction_id: Option<Snowflake>,
+    pub contract_id: Option<Snowflake>,
+    pub pending_transaction_id: Option<Snowflake>,
+}
+
+impl From<ForecastItem> for ForecastChangeResponse {
+    fn from(value: ForecastItem) -> Self {
+        Self {
+            name: value.name,
+            amount: value.amount,
+            recurring_transaction_id: value.recurring_transaction_id.map(Snowflake::new),
+            contract_id: value.contract_id.map(Snowflake::new),
+            pending_transaction_id: value.pending_transaction_id.map(Snowflake::new),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::models::_entities::sea_orm_active_enums::TransactionType;
+use crate::models::_entities::transaction_rules::Model;
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransactionRuleResponse {
+    pub id: Snowflake,
+    pub name: String,
+    pub position: i32,
+    pub stop_processing: bool,
+    pub name_contains: Option<String>,
+    pub purpose_contains: Option<String>,
+    pub source_iban: Option<String>,
+    pub destination_iban: Option<String>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub transaction_type: Option<TransactionType>,
+    pub category_id: Option<Snowflake>,
+    pub tag_ids: Vec<Snowflake>,
+    pub rename_to: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<(Model, Vec<i64>)> for TransactionRuleResponse {
+    fn from((value, tag_ids): (Model, Vec<i64>)) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            name: value.name,
+            position: value.position,
+            stop_processing: value.stop_processing,
+            name_contains: value.name_contains,
+            purpose_contains: value.purpose_contains,
+            source_iban: value.source_iban,
+            destination_iban: value.destination_iban,
+            min_amount: value.min_amount,
+            max_amount: value.max_amount,
+            transaction_type: value.transaction_type,
+            category_id: value.category_id.map(Snowflake::new),
+            tag_ids: tag_ids.into_iter().map(Snowflake::new).collect(),
+            rename_to: value.rename_to,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
@@ -11,7 +11,8 @@ use utoipa::ToSchema;
 pub struct SessionResponse {
     pub id: Snowflake,
     pub user: UserResponse,
-    pub api_key: String,
+    /// The Bearer Token of the Session. Only returned once, when the Session is created.
+    pub api_key: Option<String>,
     pub name: Option<String>,
     pub user_agent: Option<String>,
     pub last_accessed_at: Option<DateTime<FixedOffset>>,
@@ -19,6 +20,33 @@ pub struct SessionResponse {
     pub updated_at: DateTime<FixedOffset>,
 }
 
+/// A session as listed to its user. Does not contain the api key.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionInfoResponse {
+    pub id: Snowflake,
+    pub name: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_accessed_at: Option<DateTime<FixedOffset>>,
+    /// Whether this is the session of the request.
+    pub current: bool,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl SessionInfoResponse {
+    pub fn new(session: sessions::Model, current_session_id: i64) -> Self {
+        Self {
+            id: Snowflake::new(session.id),
+            name: session.name,
+            user_agent: session.user_agent,
+            last_accessed_at: session.last_accessed_at,
+            current: session.id == current_session_id,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+        }
+    }
+}
+
 impl From<(sessions::Model, users::Model)> for SessionResponse {
     fn from(value: (Model, users::Model)) -> Self {
         let user = UserResponse::from(value.1);
@@ -26,7 +54,7 @@ impl From<(sessions::Model, users::Model)> for SessionResponse {
         Self {
             id: Snowflake::new(session.id),
             user,
-            api_key: session.api_key,
+            api_key: None,
             name: session.name,
             user_agent: session.user_agent,
             last_accessed_at: session.last_accessed_at,
@@ -0,0 +1,131 @@
+use crate::models::_entities::sea_orm_active_enums::{PossibleDuplicateStatus, TransactionType};
+use crate::models::_entities::transactions::Model;
+use crate::models::_entities::{possible_duplicates, transaction_splits};
+use crate::models::category_classifiers::CategorySuggestion;
+use crate::types::snowflake::Snowflake;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransactionResponse {
+    pub id: Snowflake,
+    pub source_id: Option<Snowflake>,
+    pub destination_id: Option<Snowflake>,
+    pub currency_id: Snowflake,
+    pub category_id: Option<Snowflake>,
+    pub file_attachment_id: Option<Snowflake>,
+    pub source_name: Option<String>,
+    pub source_iban: Option<String>,
+    pub destination_name: Option<String>,
+    pub destination_iban: Option<String>,
+    pub r#type: TransactionType,
+    pub amount: i64,
+    /// The amount in the currency of the destination bank account,
+    /// only set for transfers between bank accounts with different currencies.
+    pub destination_amount: Option<i64>,
+    pub name: String,
+    pub purpose: Option<String>,
+    pub note: Option<String>,
+    pub booking_date: Option<DateTime<FixedOffset>>,
+    /// Reconciled transactions are locked against edits.
+    pub reconciled_at: Option<DateTime<FixedOffset>>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<Model> for TransactionResponse {
+    fn from(value: Model) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            source_id: value.source_id.map(Snowflake::new),
+            destination_id: value.destination_id.map(Snowflake::new),
+            currency_id: Snowflake::new(value.currency_id),
+            category_id: value.category_id.map(Snowflake::new),
+            file_attachment_id: value.file_attachment_id.map(Snowflake::new),
+            source_name: value.source_name,
+            source_iban: value.source_iban,
+            destination_name: value.destination_name,
+            destination_iban: value.destination_iban,
+            r#type: value.r#type,
+            amount: value.amount,
+            destination_amount: value.destination_amount,
+            name: value.name,
+            purpose: value.purpose,
+            note: value.note,
+            booking_date: value.booking_date,
+            reconciled_at: value.reconciled_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PossibleDuplicateResponse {
+    pub id: Snowflake,
+    /// How likely both transactions are duplicates (0 - 100).
+    pub score: i16,
+    pub status: PossibleDuplicateStatus,
+    /// The transaction that was created later.
+    pub transaction: TransactionResponse,
+    /// The already existing transaction it probably duplicates.
+    pub duplicate: TransactionResponse,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+impl From<(possible_duplicates::Model, Model, Model)> for PossibleDuplicateResponse {
+    fn from((value, transaction, duplicate): (possible_duplicates::Model, Model, Model)) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            score: value.score,
+            status: value.status,
+            transaction: TransactionResponse::from(transaction),
+            duplicate: TransactionResponse::from(duplicate),
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CategorySuggestionResponse {
+    pub category_id: Snowflake,
+    /// How confident the suggestion is (0 - 1).
+    pub confidence: f64,
+}
+
+impl From<CategorySuggestion> for CategorySuggestionResponse {
+    fn from(value: CategorySuggestion) -> Self {
+        Self {
+            category_id: Snowflake::new(value.category_id),
+            confidence: value.confidence,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransactionSplitResponse {
+    pub id: Snowflake,
+    pub transaction_id: Snowflake,
+    pub amount: i64,
+    pub category_id: Option<Snowflake>,
+    pub note: Option<String>,
+    pub tag_ids: Vec<Snowflake>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+impl From<(transaction_splits::Model, Vec<i64>)> for TransactionSplitResponse {
+    fn from((value, tag_ids): (transaction_splits::Model, Vec<i64>)) -> Self {
+        Self {
+            id: Snowflake::new(value.id),
+            transaction_id: Snowflake::new(value.transaction_id),
+            amount: value.amount,
+            category_id: value.category_id.map(Snowflake::new),
+            note: value.note,
+            tag_ids: tag_ids.into_iter().map(Snowflake::new).collect(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
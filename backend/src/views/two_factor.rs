@@ -0,0 +1,27 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorEnrollmentResponse {
+    /// The base32 encoded TOTP secret, for authenticator apps that can not scan the QR code.
+    pub secret: String,
+    /// The `otpauth://` URI of the secret.
+    pub otpauth_uri: String,
+    /// The QR code of the `otpauth://` URI, as base64 encoded PNG.
+    pub qr_code_png: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    /// One-time codes that can be entered instead of a TOTP code. Only returned once.
+    pub recovery_codes: Vec<String>,
+}
+
+/// Returned instead of a Session when the User has two-factor authentication enabled.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorChallengeResponse {
+    /// Exchanged together with a TOTP or recovery code for a Session.
+    pub challenge_token: String,
+    pub expires_at: DateTime<FixedOffset>,
+}
@@ -8,23 +8,40 @@ use utoipa::ToSchema;
 pub struct UserResponse {
     pub id: Snowflake,
     pub email: String,
+    /// The new email address of a requested email change, until it is verified.
+    pub pending_email: Option<String>,
     pub name: String,
     pub flags: i32,
     pub email_verification_sent_at: Option<DateTime<FixedOffset>>,
     pub email_verified_at: Option<DateTime<FixedOffset>>,
+    /// Reports are converted into this currency.
+    pub preferred_currency_id: Option<Snowflake>,
+    /// The account and all of its data will be deleted at this time, unless the deletion is cancelled.
+    pub deletion_scheduled_at: Option<DateTime<FixedOffset>>,
+    /// Whether a TOTP code is required to log in.
+    pub two_factor_enabled: bool,
+    /// Disabled accounts can not log in until an admin enables them again.
+    pub disabled_at: Option<DateTime<FixedOffset>>,
     pub created_at: DateTime<FixedOffset>,
     pub updated_at: DateTime<FixedOffset>,
 }
 
 impl From<Model> for UserResponse {
     fn from(value: Model) -> Self {
+        let two_factor_enabled = value.is_two_factor_enabled();
+
         Self {
             id: Snowflake::new(value.id),
             email: value.email,
+            pending_email: value.pending_email,
             name: value.name,
             flags: value.flags,
             email_verification_sent_at: value.email_verification_sent_at,
             email_verified_at: value.email_verified_at,
+            preferred_currency_id: value.preferred_currency_id.map(Snowflake::new),
+            deletion_scheduled_at: value.deletion_scheduled_at,
+            two_factor_enabled,
+            disabled_at: value.disabled_at,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
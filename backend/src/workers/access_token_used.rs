@@ -0,0 +1,28 @@
+use crate::models::_entities::personal_access_tokens;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct AccessTokenUsedWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccessTokenUsedWorkerArgs {
+    pub access_token_id: i64,
+}
+
+#[async_trait]
+impl BackgroundWorker<AccessTokenUsedWorkerArgs> for AccessTokenUsedWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: AccessTokenUsedWorkerArgs) -> Result<()> {
+        let access_token = personal_access_tokens::Model::find_by_id(&self.ctx.db, args.access_token_id).await?;
+        if let Some(access_token) = access_token {
+            access_token.update_last_used_at(&self.ctx.db).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::error::app_error::{AppError, AppResult};
+use crate::models::_entities::exports;
+use crate::services::export::write_export;
+use bytes::Bytes;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, warn};
+
+pub struct ExportWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportWorkerArgs {
+    pub export_id: i64,
+}
+
+#[async_trait]
+impl BackgroundWorker<ExportWorkerArgs> for ExportWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    /// Writes the export into the storage. A failure is recorded on the export instead of retrying the job.
+    async fn perform(&self, args: ExportWorkerArgs) -> Result<()> {
+        let export = exports::Model::find_by_id(&self.ctx.db, args.export_id).await?;
+
+        match self.write(&export).await {
+            Ok(file_path) => {
+                export.finish(&self.ctx.db, file_path).await?;
+                info!("Finished export {}", args.export_id);
+            }
+            Err(err) => {
+                warn!("Export {} failed: {}", args.export_id, err);
+                let error = err.details.clone().unwrap_or_else(|| err.to_string());
+                export.fail(&self.ctx.db, error).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ExportWorker {
+    async fn write(&self, export: &exports::Model) -> AppResult<String> {
+        let content = write_export(&self.ctx, export).await?;
+
+        let file_path = export.storage_path();
+        self.ctx
+            .storage
+            .upload(Path::new(&file_path), &Bytes::from(content))
+            .await
+            .map_err(|err| AppError::StorageError(err.to_string()))?;
+
+        Ok(file_path)
+    }
+}
@@ -1 +1,5 @@
+pub mod access_token_used;
+pub mod export;
+pub mod reapply_rules;
 pub mod session_used;
+pub mod train_category_classifier;
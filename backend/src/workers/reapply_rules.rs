@@ -0,0 +1,56 @@
+use crate::models::_entities::transactions;
+use crate::models::transaction_rules::RuleSet;
+use crate::models::user_permissions::Permission;
+use loco_rs::prelude::*;
+use sea_orm::{PaginatorTrait, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// The amount of transactions that are processed (and committed) at once.
+const BATCH_SIZE: u64 = 500;
+
+pub struct ReapplyRulesWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReapplyRulesWorkerArgs {
+    pub user_id: i64,
+}
+
+#[async_trait]
+impl BackgroundWorker<ReapplyRulesWorkerArgs> for ReapplyRulesWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    /// Applies the rules of the user to all not reconciled transactions the user is allowed to edit.
+    async fn perform(&self, args: ReapplyRulesWorkerArgs) -> Result<()> {
+        let rules = RuleSet::load(&self.ctx.db, args.user_id).await?;
+        let mut pages = transactions::Entity::find()
+            .filter(transactions::Column::Id.in_subquery(transactions::Model::visible_ids(
+                args.user_id,
+                Permission::Read | Permission::Write,
+            )))
+            // Reconciled transactions are locked against edits.
+            .filter(transactions::Column::ReconciledAt.is_null())
+            .order_by_asc(transactions::Column::Id)
+            .paginate(&self.ctx.db, BATCH_SIZE);
+
+        let mut changed = 0;
+        while let Some(transactions) = pages.fetch_and_next().await? {
+            let txn = self.ctx.db.begin().await?;
+            for transaction in transactions {
+                changed += usize::from(rules.apply_to_existing(&txn, transaction).await?);
+            }
+            txn.commit().await?;
+        }
+
+        info!(
+            "Reapplied rules of user {} and changed {} transactions",
+            args.user_id, changed
+        );
+
+        Ok(())
+    }
+}
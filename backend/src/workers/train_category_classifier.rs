@@ -0,0 +1,28 @@
+use crate::models::_entities::category_classifiers;
+use crate::services::snowflake_generator::SnowflakeGeneratorInner;
+use crate::services::Service;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct TrainCategoryClassifierWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrainCategoryClassifierWorkerArgs {
+    pub user_id: i64,
+}
+
+#[async_trait]
+impl BackgroundWorker<TrainCategoryClassifierWorkerArgs> for TrainCategoryClassifierWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: TrainCategoryClassifierWorkerArgs) -> Result<()> {
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&self.ctx).await?;
+        category_classifiers::Model::train(&self.ctx.db, &snowflake_generator, args.user_id).await?;
+
+        Ok(())
+    }
+}
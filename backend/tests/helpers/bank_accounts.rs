@@ -0,0 +1,56 @@
+use financrr::models::_entities::{bank_accounts, currencies};
+use financrr::models::user_permissions;
+use financrr::models::user_permissions::Permission;
+use financrr::models::users;
+use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
+use financrr::services::Service;
+use loco_rs::app::AppContext;
+use sea_orm::{ActiveModelTrait, EntityName, Set};
+
+/// Creates a bank account in a new currency of the user and grants the user all permissions on it.
+/// Every bank account needs its own currency, because bank accounts can not share one.
+pub async fn create_bank_account(ctx: &AppContext, user: &users::Model, name: &str) -> bank_accounts::Model {
+    let snowflake_generator = SnowflakeGeneratorInner::get_arc(ctx).await.unwrap();
+
+    let currency = currencies::ActiveModel {
+        id: Set(snowflake_generator.next_id().unwrap()),
+        user_id: Set(Some(user.id)),
+        name: Set(format!("{} Currency", name)),
+        symbol: Set("T".to_string()),
+        iso_code: Set(None),
+        decimal_places: Set(2),
+        created_at: Set(chrono::Utc::now().into()),
+        updated_at: Set(chrono::Utc::now().into()),
+    }
+    .insert(&ctx.db)
+    .await
+    .unwrap();
+
+    let bank_account = bank_accounts::ActiveModel {
+        id: Set(snowflake_generator.next_id().unwrap()),
+        currency_id: Set(currency.id),
+        linked_back_account_id: Set(None),
+        name: Set(name.to_string()),
+        description: Set(None),
+        iban: Set(None),
+        balance: Set(0),
+        original_balance: Set(0),
+        created_at: Set(chrono::Utc::now().into()),
+        updated_at: Set(chrono::Utc::now().into()),
+    }
+    .insert(&ctx.db)
+    .await
+    .unwrap();
+
+    user_permissions::Model::grant(
+        &ctx.db,
+        user.id,
+        bank_accounts::Entity.table_name(),
+        bank_account.id,
+        Permission::Read | Permission::Write | Permission::Delete,
+    )
+    .await
+    .unwrap();
+
+    bank_account
+}
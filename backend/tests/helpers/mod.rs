@@ -1,3 +1,4 @@
+pub mod bank_accounts;
 pub mod faker;
 pub mod init;
 pub mod session;
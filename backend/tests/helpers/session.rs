@@ -1,5 +1,4 @@
 use crate::helpers::users::clean_up_user_model;
-use financrr::controllers::session::CreateSessionParams;
 use financrr::models::_entities::sessions;
 use financrr::models::users;
 use financrr::services::secret_generator::SecretGeneratorInner;
@@ -7,17 +6,12 @@ use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
 use financrr::services::Service;
 use loco_rs::app::AppContext;
 
-pub async fn generate_session(ctx: &AppContext, user: &users::Model, password: &str) -> sessions::Model {
+/// Creates a session for the user and returns it together with its api key.
+pub async fn generate_session(ctx: &AppContext, user: &users::Model) -> (sessions::Model, String) {
     let snowflake_generator = SnowflakeGeneratorInner::get_arc(ctx).await.unwrap();
     let secret_generator = SecretGeneratorInner::get_arc(ctx).await.unwrap();
-    let params = CreateSessionParams {
-        email: user.email.clone(),
-        password: password.to_string(),
-        name: None,
-        user_agent: None,
-    };
 
-    sessions::Model::create_session(&ctx.db, &snowflake_generator, &secret_generator, user, &params)
+    sessions::Model::create_session(&ctx.db, &snowflake_generator, &secret_generator, user, None, None)
         .await
         .unwrap()
 }
@@ -17,6 +17,7 @@ pub async fn create_user(ctx: &AppContext, email: &str, password: &str, activate
         email: email.to_string(),
         password: password.to_string(),
         name: DEFAULT_NAME.to_string(),
+        invite_code: None,
     };
 
     let user = users::Model::create_with_password(&ctx.db, &snowflake_generator, &register_params)
@@ -33,6 +33,7 @@ async fn can_create_with_password() {
         email: "test@financrr.dev".to_string(),
         password: "Password1234".to_string(),
         name: "Test Account".to_string(),
+        invite_code: None,
     };
     let snowflake_generator = SnowflakeGeneratorInner::get_arc(&boot.app_context).await.unwrap();
 
@@ -0,0 +1,77 @@
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::models::personal_access_tokens::Scope;
+use financrr::views::access_token::AccessTokenResponse;
+use loco_rs::prelude::request;
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_use_scoped_access_tokens() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+
+        let response = request
+            .post("/api/v1/access-tokens")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "name": "Import script",
+                "scopes": ["import"],
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let created: AccessTokenResponse = response.json();
+        assert_eq!(created.scopes, vec![Scope::Import]);
+        let token_authorization = format!("Bearer {}", created.token.unwrap());
+
+        let response = request
+            .get("/api/v1/import/profiles")
+            .add_header("Authorization", token_authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // The token lacks the scope for reading transactions.
+        let response = request
+            .get("/api/v1/transactions/duplicates")
+            .add_header("Authorization", token_authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        // Tokens can only be managed with a login session.
+        let response = request
+            .get("/api/v1/access-tokens")
+            .add_header("Authorization", token_authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        let response = request
+            .get("/api/v1/access-tokens")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let access_tokens: Vec<AccessTokenResponse> = response.json();
+        assert_eq!(access_tokens.len(), 1);
+        assert!(access_tokens[0].token.is_none());
+
+        let response = request
+            .delete(&format!("/api/v1/access-tokens/{}", created.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        let response = request
+            .get("/api/v1/import/profiles")
+            .add_header("Authorization", token_authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
@@ -0,0 +1,159 @@
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_email, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::models::_entities::sea_orm_active_enums::AuditAction;
+use financrr::models::_entities::{invite_codes, sessions};
+use financrr::types::pagination::Page;
+use financrr::views::admin::{AuditLogResponse, InviteCodeResponse};
+use financrr::views::user::UserResponse;
+use loco_rs::prelude::request;
+use sea_orm::IntoActiveModel;
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_manage_users_as_admin() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const ADMIN_EMAIL: &str = "can.manage.users.admin@financrr.test";
+        const USER_EMAIL: &str = "can.manage.users.user@financrr.test";
+        let admin = create_user_with_email(&ctx, ADMIN_EMAIL)
+            .await
+            .into_active_model()
+            .set_admin(&ctx.db, true)
+            .await
+            .unwrap();
+        let user = create_user_with_email(&ctx, USER_EMAIL).await;
+        let (_, admin_api_key) = generate_session(&ctx, &admin).await;
+        let (user_session, user_api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", admin_api_key);
+
+        // Users without the admin flag are rejected.
+        let response = request
+            .get("/api/v1/admin/users")
+            .add_header("Authorization", format!("Bearer {}", user_api_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .get("/api/v1/admin/users?q=CAN.MANAGE.USERS.USER")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let page: Page<UserResponse> = response.json();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.data[0].email, USER_EMAIL);
+
+        // Admins can not lock themselves out.
+        let response = request
+            .post(&format!("/api/v1/admin/users/{}/disable", admin.id))
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+
+        let response = request
+            .post(&format!("/api/v1/admin/users/{}/disable", user.id))
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "reason": "Spam" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let disabled: UserResponse = response.json();
+        assert!(disabled.disabled_at.is_some());
+        assert!(sessions::Model::find_by_id(&ctx.db, user_session.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let response = request
+            .post("/api/v1/sessions")
+            .json(&json!({ "email": USER_EMAIL, "password": DEFAULT_PASSWORD }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .post(&format!("/api/v1/admin/users/{}/enable", user.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .post("/api/v1/sessions")
+            .json(&json!({ "email": USER_EMAIL, "password": DEFAULT_PASSWORD }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        let response = request
+            .get(&format!("/api/v1/admin/audit-logs?user_id={}", user.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let page: Page<AuditLogResponse> = response.json();
+        let actions: Vec<AuditAction> = page.data.iter().map(|audit_log| audit_log.action).collect();
+        assert_eq!(actions, vec![AuditAction::EnableAccount, AuditAction::DisableAccount]);
+        assert_eq!(page.data[1].details, Some(json!({ "reason": "Spam" })));
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_create_invite_codes() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const ADMIN_EMAIL: &str = "can.create.invite.codes@financrr.test";
+        const INVITED_EMAIL: &str = "invited@financrr.test";
+        let admin = create_user_with_email(&ctx, ADMIN_EMAIL)
+            .await
+            .into_active_model()
+            .set_admin(&ctx.db, true)
+            .await
+            .unwrap();
+        let (_, admin_api_key) = generate_session(&ctx, &admin).await;
+        let authorization = format!("Bearer {}", admin_api_key);
+
+        let response = request
+            .post("/api/v1/admin/invite-codes")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "email": INVITED_EMAIL, "max_uses": 1 }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let invite_code: InviteCodeResponse = response.json();
+        let code = invite_code.code.unwrap();
+        assert_eq!(ctx.mailer.unwrap().deliveries().count, 1);
+
+        // The code is restricted to the invited email address and can only be used once.
+        let now = chrono::Utc::now();
+        assert!(!invite_codes::Model::redeem(&ctx.db, &code, "other@financrr.test", now)
+            .await
+            .unwrap());
+        assert!(
+            invite_codes::Model::redeem(&ctx.db, &code, "Invited@financrr.test", now)
+                .await
+                .unwrap()
+        );
+        assert!(!invite_codes::Model::redeem(&ctx.db, &code, INVITED_EMAIL, now)
+            .await
+            .unwrap());
+
+        let response = request
+            .delete(&format!("/api/v1/admin/invite-codes/{}", invite_code.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        let response = request
+            .get("/api/v1/admin/invite-codes")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let invite_codes: Vec<InviteCodeResponse> = response.json();
+        assert!(invite_codes.is_empty());
+    })
+    .await;
+}
@@ -0,0 +1,83 @@
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::models::_entities::exports;
+use financrr::models::_entities::sea_orm_active_enums::{ExportFormat, ExportStatus};
+use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
+use financrr::services::Service;
+use financrr::views::export::ExportResponse;
+use loco_rs::prelude::request;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_download_finished_export() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, owner_key) = generate_session(&ctx, &owner).await;
+        let other = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, other_key) = generate_session(&ctx, &other).await;
+
+        // The worker runs in the foreground in tests, so the export is finished right away.
+        let response = request
+            .post("/api/v1/exports")
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .json(&json!({ "format": "json" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+        let export: ExportResponse = response.json();
+
+        let response = request
+            .get(&format!("/api/v1/exports/{}", export.id))
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let export: ExportResponse = response.json();
+        assert_eq!(export.status, ExportStatus::Finished);
+        let download_url = export.download_url.expect("A finished export has a download url");
+        assert_eq!(download_url, format!("/api/v1/exports/{}/download", export.id));
+
+        let response = request
+            .get(&download_url)
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.headers().get("content-type").unwrap(), "application/json");
+        let _: Value = response.json();
+
+        // Only the owner can download the export.
+        let response = request
+            .get(&download_url)
+            .add_header("Authorization", format!("Bearer {}", other_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_download_pending_export() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&ctx).await.unwrap();
+        let export = exports::Model::create(&ctx.db, &snowflake_generator, user.id, ExportFormat::Csv)
+            .await
+            .unwrap();
+
+        let response = request
+            .get(&format!("/api/v1/exports/{}/download", export.id))
+            .add_header("Authorization", format!("Bearer {}", api_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+    })
+    .await;
+}
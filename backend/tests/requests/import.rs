@@ -0,0 +1,200 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::views::import::{ImportProfileResponse, ImportResponse};
+use financrr::views::transaction::PossibleDuplicateResponse;
+use loco_rs::prelude::request;
+use serde_json::json;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("import_request");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+fn profile_payload(name: &str) -> serde_json::Value {
+    json!({
+        "name": name,
+        "column_mapping": {
+            "booking_date": "Date",
+            "amount": "Amount",
+            "counterparty_name": 1,
+        },
+        "date_format": "%d.%m.%Y",
+        "decimal_separator": ",",
+        "thousands_separator": ".",
+        "delimiter": ";",
+        "sign_convention": "signed",
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_manage_import_profiles() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+
+        let response = request
+            .post("/api/v1/import/profiles")
+            .add_header("Authorization", authorization.clone())
+            .json(&profile_payload("My Bank"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        let profile: ImportProfileResponse = response.json();
+        assert_eq!(profile.name, "My Bank");
+        assert!(profile.has_header);
+        assert_eq!(profile.skip_rows, 0);
+
+        let path = format!("/api/v1/import/profiles/{}", profile.id);
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization.clone())
+            .json(&profile_payload("My other Bank"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let updated: ImportProfileResponse = response.json();
+        assert_eq!(updated.name, "My other Bank");
+
+        let response = request
+            .get("/api/v1/import/profiles")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let profiles: Vec<ImportProfileResponse> = response.json();
+        assert_eq!(profiles.len(), 1);
+
+        let response = request
+            .delete(&path)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        let response = request.get(&path).add_header("Authorization", authorization).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_create_invalid_import_profile() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+
+        let mut payload = profile_payload("Invalid");
+        payload["sign_convention"] = json!("separate_columns");
+
+        let response = request
+            .post("/api/v1/import/profiles")
+            .add_header("Authorization", format!("Bearer {}", api_key))
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_import_ofx_with_transaction_ids() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        // Both payments have the same values, but the bank assigned them different ids.
+        let ofx = "<OFX><BANKTRANLIST>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250103<TRNAMT>-2.50<FITID>A1<PAYEE><NAME>Bakery</NAME></PAYEE></STMTTRN>\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20250103<TRNAMT>-2.50<FITID>A2<PAYEE><NAME>Bakery</NAME></PAYEE></STMTTRN>\
+            </BANKTRANLIST></OFX>";
+        let path = format!("/api/v1/import/ofx?bank_account_id={}", bank_account.id);
+
+        let response = request
+            .post(&path)
+            .add_header("Authorization", authorization.clone())
+            .text(ofx)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let result: ImportResponse = response.json();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.duplicates, 0);
+        let transaction = result.rows[0].transaction.as_ref().unwrap();
+        assert_eq!(transaction.destination_name.as_deref(), Some("Bakery"));
+
+        // Importing the statement again only finds the transactions by their ids.
+        let response = request
+            .post(&path)
+            .add_header("Authorization", authorization)
+            .text(ofx.replace("Bakery", "BAKERY"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let result: ImportResponse = response.json();
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.duplicates, 2);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn does_not_detect_rows_of_the_same_import_as_possible_duplicates() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+        let path = format!("/api/v1/import/ofx?bank_account_id={}", bank_account.id);
+
+        let response = request
+            .post(&path)
+            .add_header("Authorization", authorization.clone())
+            .text("<OFX><STMTTRN><DTPOSTED>20250103<TRNAMT>-2.50<NAME>Bakery</STMTTRN></OFX>")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let result: ImportResponse = response.json();
+        let existing = result.rows[0].transaction.as_ref().unwrap().id.id;
+
+        // Two purchases at the same bakery, which looks like the already imported one.
+        let response = request
+            .post(&path)
+            .add_header("Authorization", authorization.clone())
+            .text(
+                "<OFX><STMTTRN><DTPOSTED>20250103<TRNAMT>-2.50<NAME>Bakery Ltd</STMTTRN>\
+                <STMTTRN><DTPOSTED>20250103<TRNAMT>-2.50<NAME>Bakery Ltd</STMTTRN></OFX>",
+            )
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let result: ImportResponse = response.json();
+        assert_eq!(result.imported, 2);
+
+        let response = request
+            .get("/api/v1/transactions/duplicates")
+            .add_header("Authorization", authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let duplicates: Vec<PossibleDuplicateResponse> = response.json();
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates.iter().all(|duplicate| duplicate.duplicate.id.id == existing));
+    })
+    .await;
+}
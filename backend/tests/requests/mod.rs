@@ -1,4 +1,14 @@
+mod access_token;
+mod admin;
+mod export;
+mod import;
 mod openapi;
 mod path_normaliztation;
+mod rate_limit;
+mod reconciliation;
+mod report;
+mod rule;
 mod session;
+mod transaction;
+mod two_factor;
 mod user;
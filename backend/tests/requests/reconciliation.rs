@@ -0,0 +1,155 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::models::_entities::categories;
+use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
+use financrr::services::Service;
+use financrr::views::reconciliation::ReconciliationResponse;
+use financrr::views::transaction::{PossibleDuplicateResponse, TransactionResponse};
+use loco_rs::prelude::request;
+use sea_orm::{ActiveModelTrait, Set};
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_change_reconciled_transaction() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "bank_account_id": bank_account.id.to_string(),
+                "amount": -1000,
+                "name": "Supermarket",
+                "booking_date": "2025-01-03T12:00:00+00:00",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let transaction: TransactionResponse = response.json();
+
+        let response = request
+            .post("/api/v1/reconciliations")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "bank_account_id": bank_account.id.to_string(),
+                "statement_date": "2025-01-31T23:59:59+00:00",
+                "statement_balance": -1000,
+                "transaction_ids": [transaction.id.to_string()],
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let reconciliation: ReconciliationResponse = response.json();
+        assert_eq!(reconciliation.transaction_ids, vec![transaction.id.clone()]);
+
+        let path = format!("/api/v1/transactions/{}", transaction.id);
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "name": "Groceries" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+
+        let response = request
+            .delete(&path)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+
+        // The transaction is left unchanged.
+        let response = request.get(&path).add_header("Authorization", authorization).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let unchanged: TransactionResponse = response.json();
+        assert_eq!(unchanged.name, "Supermarket");
+        assert!(unchanged.reconciled_at.is_some());
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_change_reconciled_transaction_by_merging() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&ctx).await.unwrap();
+        let category = categories::ActiveModel {
+            id: Set(snowflake_generator.next_id().unwrap()),
+            parent_id: Set(None),
+            user_id: Set(Some(user.id)),
+            name: Set("Groceries".to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(&ctx.db)
+        .await
+        .unwrap();
+        let payload = |category_id: Option<String>| {
+            json!({
+                "bank_account_id": bank_account.id.to_string(),
+                "amount": -1000,
+                "name": "Supermarket",
+                "booking_date": "2025-01-03T12:00:00+00:00",
+                "category_id": category_id,
+            })
+        };
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&payload(None))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let reconciled: TransactionResponse = response.json();
+        let response = request
+            .post("/api/v1/reconciliations")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "bank_account_id": bank_account.id.to_string(),
+                "statement_date": "2025-01-31T23:59:59+00:00",
+                "statement_balance": -1000,
+                "transaction_ids": [reconciled.id.to_string()],
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        // The duplicate has more information, but the reconciled transaction is kept as it is.
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&payload(Some(category.id.to_string())))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        let response = request
+            .get("/api/v1/transactions/duplicates")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        let duplicates: Vec<PossibleDuplicateResponse> = response.json();
+        assert_eq!(duplicates.len(), 1);
+
+        let response = request
+            .post(&format!("/api/v1/transactions/duplicates/{}/merge", duplicates[0].id))
+            .add_header("Authorization", authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let kept: TransactionResponse = response.json();
+        assert_eq!(kept.id, reconciled.id);
+        assert_eq!(kept.category_id, None);
+    })
+    .await;
+}
@@ -0,0 +1,296 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use financrr::app::App;
+use financrr::models::_entities::sea_orm_active_enums::TransactionType;
+use financrr::models::_entities::{balance_snapshots, categories, pending_transactions, tags, transaction_parties};
+use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
+use financrr::services::Service;
+use financrr::views::report::{CashFlowResponse, CategorySpendingResponse, ForecastResponse, NetWorthResponse};
+use financrr::views::transaction::TransactionResponse;
+use loco_rs::app::AppContext;
+use loco_rs::boot::run_task;
+use loco_rs::prelude::{request, task};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde_json::json;
+use serial_test::serial;
+
+fn transaction_payload(bank_account_id: i64, amount: i64, name: &str) -> serde_json::Value {
+    json!({
+        "bank_account_id": bank_account_id.to_string(),
+        "amount": amount,
+        "name": name,
+        "booking_date": "2025-01-03T12:00:00+00:00",
+    })
+}
+
+const NET_WORTH_PATH: &str = "/api/v1/reports/net-worth?from=2025-01-01&to=2025-01-31&interval=day";
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+}
+
+/// The net worth before, between and after the transactions of [`can_replay_net_worth`].
+fn balances(rows: Vec<NetWorthResponse>) -> [i64; 3] {
+    [2, 5, 31].map(|day| rows.iter().find(|row| row.date == date(day)).unwrap().net_worth)
+}
+
+async fn find_snapshot(ctx: &AppContext, bank_account_id: i64, day: u32) -> Option<i64> {
+    balance_snapshots::Entity::find()
+        .filter(balance_snapshots::Column::BankAccountId.eq(bank_account_id))
+        .filter(balance_snapshots::Column::Date.eq(date(day)))
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .map(|snapshot| snapshot.balance)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_create_reports() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let checking = create_bank_account(&ctx, &user, "Checking").await;
+        let savings = create_bank_account(&ctx, &user, "Savings").await;
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&ctx).await.unwrap();
+        let category = categories::ActiveModel {
+            id: Set(snowflake_generator.next_id().unwrap()),
+            parent_id: Set(None),
+            user_id: Set(Some(user.id)),
+            name: Set("Groceries".to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(&ctx.db)
+        .await
+        .unwrap();
+        let tag = tags::ActiveModel {
+            id: Set(snowflake_generator.next_id().unwrap()),
+            user_id: Set(user.id),
+            name: Set("Household".to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            updated_at: Set(chrono::Utc::now().into()),
+        }
+        .insert(&ctx.db)
+        .await
+        .unwrap();
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&transaction_payload(checking.id, 5000, "Salary"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&transaction_payload(checking.id, -1000, "Supermarket"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let expense: TransactionResponse = response.json();
+        let response = request
+            .put(&format!("/api/v1/transactions/{}/splits", expense.id))
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "splits": [
+                { "amount": 600, "category_id": category.id.to_string(), "tag_ids": [tag.id.to_string()] },
+                { "amount": 400 },
+            ] }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request
+            .post("/api/v1/transactions/transfers")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "source_bank_account_id": checking.id.to_string(),
+                "destination_bank_account_id": savings.id.to_string(),
+                "amount": 2000,
+                "destination_amount": 2000,
+                "name": "Savings",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        // The transfer between both bank accounts of the user does not change the total.
+        let response = request
+            .get("/api/v1/reports/cash-flow")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let rows: Vec<CashFlowResponse> = response.json();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].currency_id.id, checking.currency_id);
+        assert_eq!(rows[0].income, 5000);
+        assert_eq!(rows[0].expense, 1000);
+        assert_eq!(rows[0].transaction_count, 2);
+
+        // The split transaction is counted per line.
+        let response = request
+            .get("/api/v1/reports/categories")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let rows: Vec<CategorySpendingResponse> = response.json();
+        assert_eq!(rows.len(), 2);
+        let groceries = rows
+            .iter()
+            .find(|row| row.category_id.as_ref().map(|id| id.id) == Some(category.id))
+            .unwrap();
+        assert_eq!(groceries.expense, 600);
+        assert_eq!(groceries.total_expense, 600);
+        let uncategorized = rows.iter().find(|row| row.category_id.is_none()).unwrap();
+        assert_eq!(uncategorized.income, 5000);
+        assert_eq!(uncategorized.expense, 400);
+
+        // The tag of a split line matches the whole transaction.
+        let response = request
+            .get(&format!("/api/v1/reports/cash-flow?tag_ids={}", tag.id))
+            .add_header("Authorization", authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let rows: Vec<CashFlowResponse> = response.json();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].income, 0);
+        assert_eq!(rows[0].expense, 1000);
+        assert_eq!(rows[0].transaction_count, 1);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_replay_net_worth() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let checking = create_bank_account(&ctx, &user, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&transaction_payload(checking.id, 5000, "Salary"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let mut payload = transaction_payload(checking.id, -1000, "Supermarket");
+        payload["booking_date"] = json!("2025-01-10T12:00:00+00:00");
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let expense: TransactionResponse = response.json();
+
+        // Without snapshots, the history is replayed from the transactions.
+        let response = request
+            .get(NET_WORTH_PATH)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(balances(response.json()), [0, 5000, 4000]);
+
+        let vars = task::Vars::from_cli_args(vec![]);
+        run_task::<App>(&ctx, Some(&"snapshot_balances".to_string()), &vars)
+            .await
+            .unwrap();
+        assert_eq!(find_snapshot(&ctx, checking.id, 31).await, Some(4000));
+        let response = request
+            .get(NET_WORTH_PATH)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(balances(response.json()), [0, 5000, 4000]);
+
+        // Removing a transaction drops the snapshots from its booking date on.
+        let response = request
+            .delete(&format!("/api/v1/transactions/{}", expense.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+        assert_eq!(find_snapshot(&ctx, checking.id, 5).await, Some(5000));
+        assert_eq!(find_snapshot(&ctx, checking.id, 10).await, None);
+        assert_eq!(find_snapshot(&ctx, checking.id, 31).await, None);
+        let response = request
+            .get(NET_WORTH_PATH)
+            .add_header("Authorization", authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(balances(response.json()), [0, 5000, 5000]);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_flag_negative_forecast() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let checking = create_bank_account(&ctx, &user, "Checking").await;
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&ctx).await.unwrap();
+        let party =
+            transaction_parties::Model::find_or_create_for_bank_account(&ctx.db, &snowflake_generator, checking.id)
+                .await
+                .unwrap();
+
+        // The rent leaves the empty bank account before the salary arrives.
+        for (name, days, r#type, amount) in [
+            ("Rent", 10, TransactionType::Expense, 1000),
+            ("Salary", 20, TransactionType::Income, 3000),
+        ] {
+            let (source_id, destination_id) = match r#type {
+                TransactionType::Income => (None, Some(party.id)),
+                _ => (Some(party.id), None),
+            };
+            pending_transactions::ActiveModel {
+                id: Set(snowflake_generator.next_id().unwrap()),
+                source_id: Set(source_id),
+                destination_id: Set(destination_id),
+                currency_id: Set(checking.currency_id),
+                category_id: Set(None),
+                file_attachment_id: Set(None),
+                source_name: Set(None),
+                source_iban: Set(None),
+                destination_name: Set(None),
+                destination_iban: Set(None),
+                r#type: Set(r#type),
+                amount: Set(amount),
+                name: Set(name.to_string()),
+                purpose: Set(None),
+                note: Set(None),
+                value_date: Set(Some((chrono::Utc::now() + chrono::Duration::days(days)).into())),
+                created_at: Set(chrono::Utc::now().into()),
+                updated_at: Set(chrono::Utc::now().into()),
+            }
+            .insert(&ctx.db)
+            .await
+            .unwrap();
+        }
+
+        let response = request
+            .get(&format!("/api/v1/reports/forecast?bank_account_ids={}", checking.id))
+            .add_header("Authorization", format!("Bearer {}", api_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let forecasts: Vec<ForecastResponse> = response.json();
+        assert_eq!(forecasts.len(), 1);
+        let days: Vec<(i64, bool)> = forecasts[0]
+            .days
+            .iter()
+            .map(|day| (day.balance, day.negative))
+            .collect();
+        assert_eq!(days, [(-1000, true), (2000, false)]);
+    })
+    .await;
+}
@@ -0,0 +1,152 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::views::rule::TransactionRuleResponse;
+use financrr::views::transaction::TransactionResponse;
+use loco_rs::prelude::request;
+use serde_json::json;
+use serial_test::serial;
+
+fn rule_payload(name: &str, position: i32) -> serde_json::Value {
+    json!({
+        "name": name,
+        "position": position,
+        "name_contains": "rewe",
+        "max_amount": 10000,
+        "rename_to": "Groceries",
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_manage_rules() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+
+        for (name, position) in [("Second", 2), ("First", 1)] {
+            let response = request
+                .post("/api/v1/rules")
+                .add_header("Authorization", authorization.clone())
+                .json(&rule_payload(name, position))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+        }
+
+        let response = request
+            .get("/api/v1/rules")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let rules: Vec<TransactionRuleResponse> = response.json();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].name, "First");
+        assert!(!rules[0].stop_processing);
+
+        let path = format!("/api/v1/rules/{}", rules[0].id);
+        let mut payload = rule_payload("First", 3);
+        payload["stop_processing"] = json!(true);
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization.clone())
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let updated: TransactionRuleResponse = response.json();
+        assert_eq!(updated.position, 3);
+        assert!(updated.stop_processing);
+
+        let response = request
+            .post("/api/v1/rules/reapply")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+
+        let response = request
+            .delete(&path)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        let response = request.get(&path).add_header("Authorization", authorization).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_create_invalid_rule() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+
+        let response = request
+            .post("/api/v1/rules")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "name": "No conditions", "rename_to": "Groceries" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let mut payload = rule_payload("Unknown tag", 0);
+        payload["tag_ids"] = json!(["1"]);
+        let response = request
+            .post("/api/v1/rules")
+            .add_header("Authorization", authorization)
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_apply_rules_to_transfers() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let checking = create_bank_account(&ctx, &user, "Checking").await;
+        let savings = create_bank_account(&ctx, &user, "Savings").await;
+
+        let response = request
+            .post("/api/v1/rules")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({
+                "name": "Transfers",
+                "position": 1,
+                "transaction_type": "Transfer",
+                "rename_to": "Own transfer",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        let response = request
+            .post("/api/v1/transactions/transfers")
+            .add_header("Authorization", authorization)
+            .json(&json!({
+                "source_bank_account_id": checking.id.to_string(),
+                "destination_bank_account_id": savings.id.to_string(),
+                "amount": 1000,
+                "destination_amount": 1000,
+                "name": "Savings",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let transfer: TransactionResponse = response.json();
+        assert_eq!(transfer.name, "Own transfer");
+    })
+    .await;
+}
@@ -1,13 +1,17 @@
 use crate::helpers::init::init_test;
-use crate::helpers::session::clean_up_session_response;
+use crate::helpers::session::{clean_up_session_response, generate_session};
 use crate::helpers::users::{create_unverified_user_with_email, create_user_with_email, DEFAULT_PASSWORD};
 use axum::http::StatusCode;
 use financrr::app::App;
-use financrr::views::session::SessionResponse;
+use financrr::models::_entities::sessions;
+use financrr::services::secret_generator::SecretGeneratorInner;
+use financrr::types::pagination::Page;
+use financrr::views::session::{SessionInfoResponse, SessionResponse};
 use insta::{assert_json_snapshot, with_settings};
 use loco_rs::prelude::request;
 use rstest::rstest;
-use sea_orm::IntoActiveModel;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, Set};
 use serde_json::json;
 use serial_test::serial;
 
@@ -82,6 +86,38 @@ async fn can_login_without_verify() {
     .await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_login_after_too_many_failed_attempts() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const EMAIL: &str = "cannot.login.after.too.many.failed.attempts@financrr.test";
+        let _ = create_user_with_email(&ctx, EMAIL).await;
+
+        let invalid_payload = json!({
+            "email": EMAIL,
+            "password": "invalid-password",
+        });
+        for _ in 0..4 {
+            let response = request.post("/api/v1/sessions").json(&invalid_payload).await;
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        }
+
+        // Even the correct password is rejected while the account is backed off.
+        let payload = json!({
+            "email": EMAIL,
+            "password": DEFAULT_PASSWORD,
+        });
+        let response = request.post("/api/v1/sessions").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        let error: serde_json::Value = response.json();
+        let retry_after = error["reference"]["payload"]["retry_after"].as_u64().unwrap();
+        assert!(retry_after > 0 && retry_after <= 2);
+    })
+    .await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn can_get_current_session() {
@@ -101,7 +137,7 @@ async fn can_get_current_session() {
         let session_response: SessionResponse = response.json();
         let response = request
             .get("/api/v1/sessions/current")
-            .add_header("Authorization", format!("Bearer {}", session_response.api_key))
+            .add_header("Authorization", format!("Bearer {}", session_response.api_key.unwrap()))
             .await;
         assert_eq!(response.status_code(), StatusCode::OK);
 
@@ -114,3 +150,164 @@ async fn can_get_current_session() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_manage_sessions() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const EMAIL: &str = "can.manage.sessions@financrr.test";
+        let user = create_user_with_email(&ctx, EMAIL).await;
+        let (_, current) = generate_session(&ctx, &user).await;
+        let (other_session, other) = generate_session(&ctx, &user).await;
+        let auth = |api_key: &str| format!("Bearer {api_key}");
+
+        let response = request
+            .get("/api/v1/sessions")
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let sessions: Page<SessionInfoResponse> = response.json();
+        assert_eq!(sessions.data.len(), 2);
+        assert_eq!(sessions.data.iter().filter(|session| session.current).count(), 1);
+        assert_eq!(sessions.next_cursor, None);
+
+        let response = request
+            .get("/api/v1/sessions?limit=1")
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let first_page: Page<SessionInfoResponse> = response.json();
+        assert_eq!(first_page.data.len(), 1);
+        assert_eq!(first_page.data[0].id, sessions.data[0].id);
+        let cursor = first_page.next_cursor.expect("There is a second page");
+
+        let response = request
+            .get(&format!("/api/v1/sessions?limit=1&cursor={}", cursor.id))
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let second_page: Page<SessionInfoResponse> = response.json();
+        assert_eq!(second_page.data.len(), 1);
+        assert_eq!(second_page.data[0].id, sessions.data[1].id);
+        assert_eq!(second_page.next_cursor, None);
+
+        let response = request
+            .patch(&format!("/api/v1/sessions/{}", other_session.id))
+            .add_header("Authorization", auth(&current))
+            .json(&json!({ "name": "Laptop" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let renamed: SessionInfoResponse = response.json();
+        assert_eq!(renamed.name.as_deref(), Some("Laptop"));
+        assert!(!renamed.current);
+
+        let response = request
+            .delete("/api/v1/sessions/others")
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+        let response = request
+            .get("/api/v1/sessions/current")
+            .add_header("Authorization", auth(&other))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        let response = request
+            .delete("/api/v1/sessions/current")
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+        let response = request
+            .get("/api/v1/sessions/current")
+            .add_header("Authorization", auth(&current))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_use_expired_session() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const EMAIL: &str = "cannot.use.expired.session@financrr.test";
+        let user = create_user_with_email(&ctx, EMAIL).await;
+        let days_ago =
+            |days: i64| -> DateTimeWithTimeZone { (chrono::Utc::now() - chrono::Duration::days(days)).into() };
+
+        // The first session exceeds its lifetime although it was used recently,
+        // the second one was not used for longer than the idle timeout.
+        for (created_at, last_accessed_at) in [(days_ago(91), Some(days_ago(1))), (days_ago(35), Some(days_ago(31)))] {
+            let (session, api_key) = generate_session(&ctx, &user).await;
+            let mut session = session.into_active_model();
+            session.created_at = Set(created_at);
+            session.last_accessed_at = Set(last_accessed_at);
+            session.update(&ctx.db).await.unwrap();
+
+            let response = request
+                .get("/api/v1/sessions/current")
+                .add_header("Authorization", format!("Bearer {api_key}"))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+            let error: serde_json::Value = response.json();
+            assert_eq!(error["error_code"]["code"], 7004);
+        }
+
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let response = request
+            .get("/api/v1/sessions/current")
+            .add_header("Authorization", format!("Bearer {api_key}"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_only_store_hashed_api_key() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const EMAIL: &str = "can.only.store.hashed.api.key@financrr.test";
+        let user = create_user_with_email(&ctx, EMAIL).await;
+
+        let payload = json!({
+            "email": user.email,
+            "password": DEFAULT_PASSWORD,
+        });
+        let response = request.post("/api/v1/sessions").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let created: SessionResponse = response.json();
+        let api_key = created.api_key.expect("The api key is returned on creation");
+
+        let session = sessions::Entity::find_by_id(created.id.id)
+            .one(&ctx.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(session.api_key_hash, api_key);
+        assert_eq!(session.api_key_hash, SecretGeneratorInner::hash_token(&api_key));
+
+        // The api key is never returned again.
+        let response = request
+            .get("/api/v1/sessions/current")
+            .add_header("Authorization", format!("Bearer {api_key}"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let current: SessionResponse = response.json();
+        assert_eq!(current.api_key, None);
+
+        // The hash itself can not be used as api key.
+        let response = request
+            .get("/api/v1/sessions/current")
+            .add_header("Authorization", format!("Bearer {}", session.api_key_hash))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    })
+    .await;
+}
@@ -0,0 +1,362 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_password, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::models::_entities::bank_accounts;
+use financrr::models::user_permissions;
+use financrr::models::user_permissions::Permission;
+use financrr::types::pagination::Page;
+use financrr::views::transaction::{TransactionResponse, TransactionSplitResponse};
+use loco_rs::prelude::request;
+use sea_orm::EntityName;
+use serde_json::json;
+use serial_test::serial;
+
+fn transaction_payload(bank_account_id: i64, amount: i64) -> serde_json::Value {
+    json!({
+        "bank_account_id": bank_account_id.to_string(),
+        "amount": amount,
+        "name": "Supermarket",
+        "booking_date": "2025-01-03T12:00:00+00:00",
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_split_transaction() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&transaction_payload(bank_account.id, -1000))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let transaction: TransactionResponse = response.json();
+        let path = format!("/api/v1/transactions/{}/splits", transaction.id);
+
+        // The lines have to sum up to the (absolute) amount of the transaction.
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "splits": [{ "amount": 600 }, { "amount": 300 }] }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "splits": [{ "amount": 600, "note": "Groceries" }, { "amount": 400 }] }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let splits: Vec<TransactionSplitResponse> = response.json();
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].amount, 600);
+        assert_eq!(splits[0].note.as_deref(), Some("Groceries"));
+
+        let response = request
+            .get(&path)
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let splits: Vec<TransactionSplitResponse> = response.json();
+        assert_eq!(splits.len(), 2);
+
+        // An empty list removes the split again.
+        let response = request
+            .put(&path)
+            .add_header("Authorization", authorization)
+            .json(&json!({ "splits": [] }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let splits: Vec<TransactionSplitResponse> = response.json();
+        assert!(splits.is_empty());
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_split_transaction_of_other_user() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, owner_key) = generate_session(&ctx, &owner).await;
+        let other = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, other_key) = generate_session(&ctx, &other).await;
+        let bank_account = create_bank_account(&ctx, &owner, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .json(&transaction_payload(bank_account.id, -1000))
+            .await;
+        let transaction: TransactionResponse = response.json();
+
+        let response = request
+            .put(&format!("/api/v1/transactions/{}/splits", transaction.id))
+            .add_header("Authorization", format!("Bearer {}", other_key))
+            .json(&json!({ "splits": [{ "amount": 1000 }] }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_paginate_transactions_sorted_by_amount() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        let mut created = Vec::new();
+        for amount in [300, 100, 200, 100] {
+            let response = request
+                .post("/api/v1/transactions")
+                .add_header("Authorization", authorization.clone())
+                .json(&transaction_payload(bank_account.id, amount))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+            let transaction: TransactionResponse = response.json();
+            created.push((transaction.amount, transaction.id.id));
+        }
+
+        // Transactions with the same amount are ordered by their id.
+        for direction in ["asc", "desc"] {
+            let mut expected = created.clone();
+            expected.sort();
+            if direction == "desc" {
+                expected.reverse();
+            }
+
+            let mut loaded = Vec::new();
+            let mut cursor = None;
+            loop {
+                let path = match cursor {
+                    None => format!("/api/v1/transactions/search?sort=amount&direction={direction}&limit=1"),
+                    Some(cursor) => {
+                        format!("/api/v1/transactions/search?sort=amount&direction={direction}&limit=1&cursor={cursor}")
+                    }
+                };
+                let response = request
+                    .get(&path)
+                    .add_header("Authorization", authorization.clone())
+                    .await;
+                assert_eq!(response.status_code(), StatusCode::OK);
+                let page: Page<TransactionResponse> = response.json();
+                loaded.extend(
+                    page.data
+                        .iter()
+                        .map(|transaction| (transaction.amount, transaction.id.id)),
+                );
+
+                match page.next_cursor {
+                    Some(next_cursor) => cursor = Some(next_cursor.id),
+                    None => break,
+                }
+            }
+            assert_eq!(loaded, expected);
+        }
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_paginate_from_deleted_transaction() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", authorization.clone())
+            .json(&transaction_payload(bank_account.id, -1000))
+            .await;
+        let transaction: TransactionResponse = response.json();
+        let response = request
+            .delete(&format!("/api/v1/transactions/{}", transaction.id))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+
+        // Sorting by another column needs the value of the cursor, which is gone.
+        let response = request
+            .get(&format!(
+                "/api/v1/transactions/search?sort=amount&cursor={}",
+                transaction.id
+            ))
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        // The id alone is enough to continue when sorting by the id.
+        let response = request
+            .get(&format!("/api/v1/transactions/search?cursor={}", transaction.id))
+            .add_header("Authorization", authorization)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_paginate_from_transaction_of_other_user() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, owner_key) = generate_session(&ctx, &owner).await;
+        let other = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, other_key) = generate_session(&ctx, &other).await;
+        let bank_account = create_bank_account(&ctx, &owner, "Checking").await;
+
+        let response = request
+            .post("/api/v1/transactions")
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .json(&transaction_payload(bank_account.id, -1000))
+            .await;
+        let transaction: TransactionResponse = response.json();
+
+        // The cursor has to be part of the list, otherwise its value would leak through the page.
+        let response = request
+            .get(&format!(
+                "/api/v1/transactions/search?sort=amount&cursor={}",
+                transaction.id
+            ))
+            .add_header("Authorization", format!("Bearer {}", other_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn cannot_change_transfer_with_read_only_destination() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let owner = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, owner_key) = generate_session(&ctx, &owner).await;
+        let member = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, member_key) = generate_session(&ctx, &member).await;
+        let checking = create_bank_account(&ctx, &owner, "Checking").await;
+        let savings = create_bank_account(&ctx, &owner, "Savings").await;
+
+        let response = request
+            .post("/api/v1/transactions/transfers")
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .json(&json!({
+                "source_bank_account_id": checking.id.to_string(),
+                "destination_bank_account_id": savings.id.to_string(),
+                "amount": 1000,
+                "destination_amount": 1000,
+                "name": "Savings",
+                "booking_date": "2025-01-03T12:00:00+00:00",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let transfer: TransactionResponse = response.json();
+
+        // The member may change the source, but may only read the destination.
+        let table_name = bank_accounts::Entity.table_name();
+        let all = Permission::Read | Permission::Write | Permission::Delete;
+        user_permissions::Model::grant(&ctx.db, member.id, table_name, checking.id, all)
+            .await
+            .unwrap();
+        user_permissions::Model::grant(&ctx.db, member.id, table_name, savings.id, Permission::Read.into())
+            .await
+            .unwrap();
+
+        let path = format!("/api/v1/transactions/{}", transfer.id);
+        let response = request
+            .put(&path)
+            .add_header("Authorization", format!("Bearer {}", member_key))
+            .json(&json!({ "name": "Savings", "booking_date": "2024-01-03T12:00:00+00:00" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .delete(&path)
+            .add_header("Authorization", format!("Bearer {}", member_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .delete(&path)
+            .add_header("Authorization", format!("Bearer {}", owner_key))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_search_transactions() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_user_with_password(&ctx, DEFAULT_PASSWORD).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let bank_account = create_bank_account(&ctx, &user, "Checking").await;
+
+        for (name, purpose, amount) in [
+            ("Supermarket", "Weekly groceries", -1000),
+            ("Bakery", "Bread rolls", -300),
+            ("Salary", "January", 5000),
+        ] {
+            let mut payload = transaction_payload(bank_account.id, amount);
+            payload["name"] = json!(name);
+            payload["purpose"] = json!(purpose);
+            let response = request
+                .post("/api/v1/transactions")
+                .add_header("Authorization", authorization.clone())
+                .json(&payload)
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+        }
+
+        // The text is matched against the purpose as well.
+        for (query, expected) in [
+            ("q=groceries", vec!["Supermarket"]),
+            ("q=bread%20or%20groceries", vec!["Bakery", "Supermarket"]),
+            ("q=-groceries&type=expense", vec!["Bakery"]),
+            ("type=income", vec!["Salary"]),
+            ("type=expense&min_amount=500", vec!["Supermarket"]),
+            ("max_amount=500", vec!["Bakery"]),
+        ] {
+            let response = request
+                .get(&format!("/api/v1/transactions/search?{query}"))
+                .add_header("Authorization", authorization.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+            let page: Page<TransactionResponse> = response.json();
+            let mut names: Vec<String> = page.data.into_iter().map(|transaction| transaction.name).collect();
+            names.sort();
+            assert_eq!(names, expected, "{query}");
+        }
+    })
+    .await;
+}
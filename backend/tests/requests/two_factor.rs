@@ -0,0 +1,134 @@
+use crate::helpers::init::init_test;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{create_user_with_email, DEFAULT_PASSWORD};
+use axum::http::StatusCode;
+use financrr::app::App;
+use financrr::utils::totp;
+use financrr::views::session::SessionResponse;
+use financrr::views::two_factor::{RecoveryCodesResponse, TwoFactorChallengeResponse, TwoFactorEnrollmentResponse};
+use financrr::views::user::UserResponse;
+use loco_rs::prelude::request;
+use serde_json::json;
+use serial_test::serial;
+
+/// Generates the code of the time step `steps` after the current one.
+fn code_at(secret: &str, email: &str, steps: u64) -> String {
+    totp::build_totp(secret, email)
+        .unwrap()
+        .generate(chrono::Utc::now().timestamp() as u64 + steps * totp::TOTP_STEP_SECONDS)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_login_with_two_factor() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        const EMAIL: &str = "can.login.with.two.factor@financrr.test";
+        let user = create_user_with_email(&ctx, EMAIL).await;
+        let (_, api_key) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+        let password = json!({ "password": DEFAULT_PASSWORD });
+
+        let response = request
+            .post("/api/v1/users/me/two-factor")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "password": "invalid-password" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post("/api/v1/users/me/two-factor")
+            .add_header("Authorization", authorization.clone())
+            .json(&password)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let enrollment: TwoFactorEnrollmentResponse = response.json();
+        assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+
+        let response = request
+            .post("/api/v1/users/me/two-factor/confirm")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "code": code_at(&enrollment.secret, EMAIL, 0) }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let recovery_codes: RecoveryCodesResponse = response.json();
+        assert_eq!(recovery_codes.recovery_codes.len(), 10);
+
+        let login = json!({ "email": EMAIL, "password": DEFAULT_PASSWORD });
+        let response = request.post("/api/v1/sessions").json(&login).await;
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+        let challenge: TwoFactorChallengeResponse = response.json();
+
+        // The code used for the confirmation is already spent.
+        let response = request
+            .post("/api/v1/sessions/two-factor")
+            .json(&json!({
+                "challenge_token": challenge.challenge_token,
+                "code": code_at(&enrollment.secret, EMAIL, 0),
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post("/api/v1/sessions/two-factor")
+            .json(&json!({
+                "challenge_token": challenge.challenge_token,
+                "code": code_at(&enrollment.secret, EMAIL, 1),
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let session: SessionResponse = response.json();
+        assert!(session.api_key.is_some());
+        assert!(session.user.two_factor_enabled);
+
+        // The challenge can only be used once.
+        let response = request
+            .post("/api/v1/sessions/two-factor")
+            .json(&json!({
+                "challenge_token": challenge.challenge_token,
+                "code": code_at(&enrollment.secret, EMAIL, 1),
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        // TOTP codes can only be used once, even with a new challenge.
+        let response = request.post("/api/v1/sessions").json(&login).await;
+        let challenge: TwoFactorChallengeResponse = response.json();
+        let response = request
+            .post("/api/v1/sessions/two-factor")
+            .json(&json!({
+                "challenge_token": challenge.challenge_token,
+                "code": code_at(&enrollment.secret, EMAIL, 1),
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        // Recovery codes can only be used once.
+        for expected_status_code in [StatusCode::CREATED, StatusCode::BAD_REQUEST] {
+            let response = request.post("/api/v1/sessions").json(&login).await;
+            let challenge: TwoFactorChallengeResponse = response.json();
+            let response = request
+                .post("/api/v1/sessions/two-factor")
+                .json(&json!({
+                    "challenge_token": challenge.challenge_token,
+                    "code": recovery_codes.recovery_codes[0],
+                }))
+                .await;
+            assert_eq!(response.status_code(), expected_status_code);
+        }
+
+        let response = request
+            .delete("/api/v1/users/me/two-factor")
+            .add_header("Authorization", authorization)
+            .json(&password)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let user: UserResponse = response.json();
+        assert!(!user.two_factor_enabled);
+
+        let response = request.post("/api/v1/sessions").json(&login).await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+    })
+    .await;
+}
@@ -1,9 +1,15 @@
 use crate::helpers::init::init_test;
-use crate::helpers::users::{clean_up_user_model, create_user_with_email, generate_unactivated_user};
+use crate::helpers::session::generate_session;
+use crate::helpers::users::{clean_up_user_model, create_user_with_email, generate_unactivated_user, DEFAULT_PASSWORD};
 use axum::http::StatusCode;
+use enumflags2::BitFlags;
 use financrr::app::App;
 use financrr::controllers::user::{ForgotParams, ResetParams, VerifyParams};
+use financrr::models::_entities::{personal_access_tokens, sessions};
 use financrr::models::users;
+use financrr::services::secret_generator::SecretGeneratorInner;
+use financrr::services::snowflake_generator::SnowflakeGeneratorInner;
+use financrr::services::Service;
 use financrr::utils::context::AdditionalAppContextMethods;
 use financrr::views::user::UserResponse;
 use insta::{assert_debug_snapshot, assert_json_snapshot, with_settings};
@@ -98,6 +104,20 @@ async fn can_reset_password() {
         assert!(ctx.is_mailer_enabled());
         const EMAIL: &str = "can.reset.password@financrr.test";
         let user = create_user_with_email(&ctx, EMAIL).await;
+        let (session, _) = generate_session(&ctx, &user).await;
+        let snowflake_generator = SnowflakeGeneratorInner::get_arc(&ctx).await.unwrap();
+        let secret_generator = SecretGeneratorInner::get_arc(&ctx).await.unwrap();
+        let (access_token, _) = personal_access_tokens::Model::create(
+            &ctx.db,
+            &snowflake_generator,
+            &secret_generator,
+            user.id,
+            "CLI".to_string(),
+            BitFlags::all(),
+            None,
+        )
+        .await
+        .unwrap();
 
         let payload = ForgotParams {
             email: user.email.clone(),
@@ -138,6 +158,92 @@ async fn can_reset_password() {
         assert!(db_user.reset_token.is_none());
 
         assert!(db_user.verify_password(NEW_PASSWORD));
+        assert!(sessions::Model::find_by_id(&ctx.db, session.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(personal_access_tokens::Model::find_by_id(&ctx.db, access_token.id)
+            .await
+            .unwrap()
+            .is_none());
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn can_manage_profile() {
+    init_test!();
+
+    request::<App, _, _>(|request, ctx| async move {
+        assert!(ctx.is_mailer_enabled());
+        const EMAIL: &str = "can.manage.profile@financrr.test";
+        const NEW_EMAIL: &str = "can.manage.profile.new@financrr.test";
+        let user = create_user_with_email(&ctx, EMAIL).await;
+        let (session, api_key) = generate_session(&ctx, &user).await;
+        let (other_session, _) = generate_session(&ctx, &user).await;
+        let authorization = format!("Bearer {}", api_key);
+
+        let response = request
+            .patch("/api/v1/users/me")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "name": "New Name" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .get("/api/v1/users/me")
+            .add_header("Authorization", authorization.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let user_response: UserResponse = response.json();
+        assert_eq!(user_response.name, "New Name");
+
+        const NEW_PASSWORD: &str = "NewPassword123";
+        let response = request
+            .put("/api/v1/users/me/password")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "current_password": "invalid-password", "new_password": NEW_PASSWORD }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .put("/api/v1/users/me/password")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "current_password": DEFAULT_PASSWORD, "new_password": NEW_PASSWORD }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(sessions::Model::find_by_id(&ctx.db, session.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(sessions::Model::find_by_id(&ctx.db, other_session.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let response = request
+            .put("/api/v1/users/me/email")
+            .add_header("Authorization", authorization.clone())
+            .json(&json!({ "email": NEW_EMAIL, "password": NEW_PASSWORD }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+        let user_response: UserResponse = response.json();
+        assert_eq!(user_response.email, EMAIL);
+        assert_eq!(user_response.pending_email.as_deref(), Some(NEW_EMAIL));
+
+        let db_user = users::Model::find_by_email(&ctx.db, EMAIL).await.unwrap().unwrap();
+        let payload = VerifyParams {
+            email: NEW_EMAIL.to_string(),
+            token: db_user.email_verification_token.unwrap(),
+        };
+        let response = request.post("/api/v1/users/verify").form(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let db_user = users::Model::find_by_email(&ctx.db, NEW_EMAIL).await.unwrap().unwrap();
+        assert!(db_user.pending_email.is_none());
+        assert!(db_user.email_verification_token.is_none());
+        assert!(db_user.verify_password(NEW_PASSWORD));
     })
     .await;
 }
@@ -0,0 +1,49 @@
+use crate::helpers::bank_accounts::create_bank_account;
+use crate::helpers::init::load_envs;
+use crate::helpers::session::generate_session;
+use crate::helpers::users::create_user_with_email;
+use financrr::app::App;
+use financrr::models::_entities::{bank_accounts, sessions, users};
+use loco_rs::boot::run_task;
+use loco_rs::prelude::*;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn test_run_delete_accounts_task() {
+    load_envs();
+
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+    let task = "delete_accounts".to_string();
+    let vars = task::Vars::from_cli_args(vec![]);
+
+    let due = create_user_with_email(ctx, "run.delete.accounts.task.due@financrr.test").await;
+    let (session, _) = generate_session(ctx, &due).await;
+    let bank_account = create_bank_account(ctx, &due, "Checking").await;
+    let due = due.into_active_model().schedule_deletion(&ctx.db).await.unwrap();
+    let kept = create_user_with_email(ctx, "run.delete.accounts.task.kept@financrr.test").await;
+    let kept = kept.into_active_model().schedule_deletion(&ctx.db).await.unwrap();
+
+    // Nothing is deleted within the grace period.
+    run_task::<App>(ctx, Some(&task), &vars).await.unwrap();
+    assert!(users::Entity::find_by_id(due.id).one(&ctx.db).await.unwrap().is_some());
+
+    let mut expired = due.clone().into_active_model();
+    expired.deletion_scheduled_at = Set(Some((chrono::Utc::now() - chrono::Duration::minutes(1)).into()));
+    expired.update(&ctx.db).await.unwrap();
+    run_task::<App>(ctx, Some(&task), &vars).await.unwrap();
+
+    assert!(users::Entity::find_by_id(due.id).one(&ctx.db).await.unwrap().is_none());
+    assert!(sessions::Entity::find_by_id(session.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(bank_accounts::Entity::find_by_id(bank_account.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(users::Entity::find_by_id(kept.id).one(&ctx.db).await.unwrap().is_some());
+}
@@ -1 +1 @@
-
+mod delete_accounts;
@@ -3,7 +3,7 @@ use loco_rs::prelude::*;
 
 use crate::helpers::init::load_envs;
 use crate::helpers::session::generate_session;
-use crate::helpers::users::{create_user_with_email, DEFAULT_PASSWORD};
+use crate::helpers::users::create_user_with_email;
 use financrr::models::_entities::sessions;
 use financrr::workers::session_used::SessionUsedWorker;
 use financrr::workers::session_used::SessionUsedWorkerArgs;
@@ -18,7 +18,7 @@ async fn test_run_session_used_worker() {
 
     const EMAIL: &str = "run.session.used.worker@financrr.test";
     let user = create_user_with_email(&boot.app_context, EMAIL).await;
-    let session = generate_session(&boot.app_context, &user, DEFAULT_PASSWORD).await;
+    let (session, _) = generate_session(&boot.app_context, &user).await;
 
     assert!(session.last_accessed_at.is_none());
 